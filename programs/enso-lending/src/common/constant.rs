@@ -1,4 +1,7 @@
-use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize, InitSpace};
+use anchor_lang::prelude::{borsh, err, AnchorDeserialize, AnchorSerialize, InitSpace, Pubkey, Result};
+use solana_program::pubkey;
+
+use crate::{GlobalConfigError, SettingAccountError};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
 pub enum LendOfferStatus {
@@ -8,6 +11,13 @@ pub enum LendOfferStatus {
     Loaned,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum BorrowRequestStatus {
+    Created,
+    Filled,
+    Canceled,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
 pub enum LoanOfferStatus {
     Matched,
@@ -16,34 +26,205 @@ pub enum LoanOfferStatus {
     BorrowerPaid,
     Liquidating,
     Liquidated,
-    Finished
+    Finished,
+    Defaulted,
+    Canceled
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum Role {
+    TierAdmin,
+    Funder,
+    Liquidator,
+    Settler,
+    Pauser,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum PauseAction {
+    NewOffers,
+    NewLoans,
+    Withdrawals,
+    Liquidations,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug, Default)]
+pub struct PauseFlags {
+    pub new_offers: bool,
+    pub new_loans: bool,
+    pub withdrawals: bool,
+    pub liquidations: bool,
+}
+
+impl PauseFlags {
+    pub fn is_paused(&self, action: PauseAction) -> bool {
+        match action {
+            PauseAction::NewOffers => self.new_offers,
+            PauseAction::NewLoans => self.new_loans,
+            PauseAction::Withdrawals => self.withdrawals,
+            PauseAction::Liquidations => self.liquidations,
+        }
+    }
+}
+
+/// Role of an account in the swap adapter's instruction. Every kind except `Extra` is filled
+/// in by the liquidation itself; `Extra` slots are taken from the remaining accounts in order.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum SwapAccountKind {
+    Authority,
+    Source,
+    Destination,
+    SourceMint,
+    DestinationMint,
+    SourceTokenProgram,
+    DestinationTokenProgram,
+    Extra,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct SwapAccountSlot {
+    pub kind: SwapAccountKind,
+    pub is_writable: bool,
+    /// Pins an `Extra` slot to a known account, such as the pool the adapter must trade on.
+    pub address: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct CollateralPosition {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct RiskParameters {
+    pub initial_ltv_bps: u64,
+    pub liquidation_threshold_bps: u64,
+    pub liquidation_penalty_bps: u64,
+}
+
+impl RiskParameters {
+    /// A loan may be opened up to `initial_ltv_bps` and becomes liquidatable past
+    /// `liquidation_threshold_bps`, so the threshold can not sit below the initial LTV.
+    pub fn validate(&self) -> Result<()> {
+        if self.initial_ltv_bps == 0
+            || self.initial_ltv_bps > self.liquidation_threshold_bps
+            || self.liquidation_threshold_bps >= BPS_DENOMINATOR
+            || self.liquidation_penalty_bps > BPS_DENOMINATOR
+        {
+            return err!(SettingAccountError::InvalidRiskParameters);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct AuctionParameters {
+    pub start_premium_bps: u64,
+    pub floor_discount_bps: u64,
+    pub duration: i64,
+}
+
+impl AuctionParameters {
+    /// The auction price decays from the oracle price plus `start_premium_bps` to the oracle
+    /// price minus `floor_discount_bps` over `duration` seconds.
+    pub fn validate(&self) -> Result<()> {
+        if self.floor_discount_bps >= BPS_DENOMINATOR || self.duration <= 0 {
+            return err!(GlobalConfigError::InvalidAuctionParameters);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum ProposalAction {
+    FinishLiquidateContract {
+        loan_offer: Pubkey,
+        collateral_swapped_amount: u64,
+        liquidated_price: u64,
+        #[max_len(100)]
+        liquidated_tx: String,
+    },
+    SystemFinishLoanOffer {
+        loan_offer: Pubkey,
+        loan_amount: u64,
+        interest_amount: u64,
+    },
+    CloseSettingAccount {
+        setting_account: Pubkey,
+    },
+    CoverBadDebt {
+        loan_offer: Pubkey,
+        amount: u64,
+    },
 }
 
 pub const ENSO_SEED: &[u8] = b"enso";
 pub const SETTING_ACCOUNT_SEED: &[u8] = b"setting_account";
 pub const LEND_OFFER_ACCOUNT_SEED: &[u8] = b"lend_offer";
 pub const LOAN_OFFER_ACCOUNT_SEED: &[u8] = b"loan_offer";
+pub const GLOBAL_CONFIG_SEED: &[u8] = b"global_config";
+pub const ROLE_ACCOUNT_SEED: &[u8] = b"role_account";
+pub const PENDING_SETTING_CHANGE_SEED: &[u8] = b"pending_setting_change";
+pub const APPROVAL_CONFIG_SEED: &[u8] = b"approval_config";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const ASSET_CONFIG_SEED: &[u8] = b"asset_config";
+pub const COLLATERAL_VAULT_SEED: &[u8] = b"collateral_vault";
+pub const NATIVE_COLLATERAL_VAULT_SEED: &[u8] = b"native_collateral_vault";
+pub const LIQUIDATION_AUCTION_SEED: &[u8] = b"liquidation_auction";
+pub const AUCTION_PROCEEDS_SEED: &[u8] = b"auction_proceeds";
+pub const RESERVE_FUND_SEED: &[u8] = b"reserve_fund";
+pub const RESERVE_VAULT_SEED: &[u8] = b"reserve_vault";
+pub const SWAP_ADAPTER_SEED: &[u8] = b"swap_adapter";
+pub const SWAP_SOURCE_SEED: &[u8] = b"swap_source";
+pub const SWAP_PROCEEDS_SEED: &[u8] = b"swap_proceeds";
+pub const LEND_ESCROW_SEED: &[u8] = b"lend_escrow";
+pub const BORROW_REQUEST_SEED: &[u8] = b"borrow_request";
+pub const BORROW_REQUEST_ESCROW_SEED: &[u8] = b"borrow_request_escrow";
+pub const ORDER_BOOK_SEED: &[u8] = b"order_book";
+
+pub const MAX_ROLE_MEMBERS: usize = 10;
+pub const MAX_APPROVERS: usize = 10;
+pub const MAX_COLLATERAL_POSITIONS: usize = 4;
+pub const MAX_SWAP_ACCOUNTS: usize = 16;
+pub const MAX_ORDER_BOOK_ENTRIES: usize = 128;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+pub const USD_DECIMALS: u8 = 6;
+
+pub const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
 
-#[cfg(feature = "staging")]
-pub const OPERATE_SYSTEM_PUBKEY: &str = "sysvYFEXhxW7FP32Ha15BBGWBEfMq1e1ScvFq61u5mG";
-#[cfg(not(feature = "staging"))]
-pub const OPERATE_SYSTEM_PUBKEY: &str = "opty8HWBKX3wW8c9qMPkmB4xnrCpMWWmQwqq7yGzmr4";
-#[cfg(feature = "staging")]
-pub const HOT_WALLET_PUBKEY: &str = "hotbEp8jbFUwfAGTUtLupGXE2JtrfZENLgRcSQsYk56";
-#[cfg(not(feature = "staging"))]
-pub const HOT_WALLET_PUBKEY: &str = "Hot7zcvBTa3NybAnKrKtjcW1yJcoDWao39ZAoBn4mfPu";
-
-pub const SOL_USD_PRICE_FEED_ID: &str = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
-pub const USDC_USD_PRICE_FEED_ID: &str = "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
-
-#[cfg(feature = "maximum-age-price-updater")]
-pub const MAXIMUM_AGE_PRICE_UPDATE: u64 = 100_000;
-#[cfg(not(feature = "maximum-age-price-updater"))]
-pub const MAXIMUM_AGE_PRICE_UPDATE: u64 = 75;
-
-#[cfg(feature = "dev")]
-pub const MIN_BORROW_HEALTH_RATIO: f64 = 1.1;
-#[cfg(not(feature = "dev"))]
-pub const MIN_BORROW_HEALTH_RATIO: f64 = 1.2;
-
-pub const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn risk_parameters(initial_ltv_bps: u64, liquidation_threshold_bps: u64, liquidation_penalty_bps: u64) -> RiskParameters {
+        RiskParameters {
+            initial_ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_penalty_bps,
+        }
+    }
+
+    #[test]
+    fn accepts_threshold_at_or_above_initial_ltv() {
+        assert!(risk_parameters(5_000, 8_000, 500).validate().is_ok());
+        assert!(risk_parameters(8_000, 8_000, 0).validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_inconsistent_risk_parameters() {
+        let invalid_risk_parameters = [
+            risk_parameters(0, 8_000, 500),
+            risk_parameters(8_001, 8_000, 500),
+            risk_parameters(5_000, BPS_DENOMINATOR, 500),
+            risk_parameters(5_000, 8_000, BPS_DENOMINATOR + 1),
+        ];
+
+        for risk_parameters in invalid_risk_parameters {
+            assert_eq!(risk_parameters.validate().unwrap_err(), SettingAccountError::InvalidRiskParameters.into());
+        }
+    }
+}
@@ -1,11 +1,141 @@
 use anchor_lang::error_code;
 
+#[error_code]
+pub enum GlobalConfigError {
+    #[msg("Invalid admin account")]
+    InvalidAdmin,
+    #[msg("Invalid program data account")]
+    InvalidProgramData,
+    #[msg("Setting change delay must not be negative")]
+    NegativeSettingChangeDelay,
+    #[msg("Withdraw request cooldown must not be negative")]
+    NegativeWithdrawRequestCooldown,
+    #[msg("Invalid liquidation auction parameters")]
+    InvalidAuctionParameters,
+    #[msg("Liquidation slippage must not exceed 100%")]
+    SlippageTooHigh,
+}
+
+#[error_code]
+pub enum RoleError {
+    #[msg("Signer does not hold the required role")]
+    Unauthorized,
+    #[msg("Role member limit reached")]
+    RoleMemberLimit,
+    #[msg("Member already holds the role")]
+    MemberAlreadyGranted,
+    #[msg("Member does not hold the role")]
+    MemberNotFound,
+}
+
+#[error_code]
+pub enum ApprovalError {
+    #[msg("Signer is not a configured approver")]
+    NotApprover,
+    #[msg("Approver already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal was already executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Proposal has not reached the approval threshold")]
+    ThresholdNotMet,
+    #[msg("Proposal action does not match the executed instruction")]
+    ProposalActionMismatch,
+    #[msg("Approval threshold must be between 1 and the number of approvers")]
+    InvalidThreshold,
+    #[msg("Approver list contains duplicates")]
+    DuplicateApprover,
+    #[msg("Approver limit reached")]
+    ApproverLimit,
+}
+
+#[error_code]
+pub enum MathError {
+    #[msg("Math overflow")]
+    Overflow,
+    #[msg("Division by zero")]
+    DivisionByZero,
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Oracle price must be positive")]
+    InvalidPrice,
+}
+
+#[error_code]
+pub enum AssetConfigError {
+    #[msg("Asset is not enabled")]
+    AssetDisabled,
+    #[msg("Price feed id must be a 32 byte hex string")]
+    InvalidPriceFeedId,
+    #[msg("Collateral haircut exceeds the denominator")]
+    HaircutTooHigh,
+    #[msg("Max price age must be positive")]
+    ZeroMaxPriceAge,
+}
+
+#[error_code]
+pub enum MintError {
+    #[msg("Mint uses an unsupported token extension")]
+    UnsupportedExtension,
+}
+
+#[error_code]
+pub enum CollateralPositionError {
+    #[msg("Collateral position limit reached")]
+    PositionLimit,
+    #[msg("Collateral position not found")]
+    PositionNotFound,
+    #[msg("Remaining accounts do not match the collateral positions")]
+    PositionAccountsMismatch,
+    #[msg("A collateral position with a higher liquidation priority must be seized first")]
+    LiquidationPriorityViolated,
+    #[msg("Primary collateral can not be held as a basket position")]
+    PrimaryCollateralMint,
+}
+
+#[error_code]
+pub enum CollateralVaultError {
+    #[msg("Collateral vault holds less than the recorded collateral amount")]
+    InsufficientBalance,
+}
+
+#[error_code]
+pub enum AuctionError {
+    #[msg("Liquidation auction has ended")]
+    Ended,
+    #[msg("Liquidation auction is still running and the debt is not covered")]
+    StillRunning,
+    #[msg("Auction price is above the bidder limit")]
+    PriceAboveLimit,
+    #[msg("Bid amount must be greater than zero")]
+    ZeroBidAmount,
+}
+
+#[error_code]
+pub enum PauseError {
+    #[msg("Action is paused for the protocol")]
+    ProtocolPaused,
+    #[msg("Action is paused for this tier")]
+    TierPaused,
+}
+
 #[error_code]
 pub enum SettingAccountError {
     #[msg("Invalid tier id")]
     InvalidTierId,   
     #[msg("Invalid owner account")]
     InvalidOwner, 
+    #[msg("Signer is not the pending owner of the tier")]
+    PendingOwnerMismatch,
+    #[msg("Pending setting change is not effective yet")]
+    SettingChangeNotEffective,
+    #[msg("Fee basis points exceed the denominator")]
+    FeeTooHigh,
+    #[msg("Initial LTV, liquidation threshold or liquidation penalty is out of range")]
+    InvalidRiskParameters,
+    #[msg("Minimum lend amount must be positive and not above the maximum")]
+    InvalidAmountBounds,
 }
 
 #[error_code]
@@ -26,6 +156,8 @@ pub enum LendOfferError {
     InvalidOfferId,
     #[msg("Invalid receiver")]
     InvalidReceiver,
+    #[msg("Draw amount must be positive and within the remaining amount of the lend offer")]
+    InvalidDrawAmount,
 }
 
 #[error_code]
@@ -70,6 +202,20 @@ pub enum LoanOfferError {
     CanNotCreateLoanCauseLendInterestUpdated
 }
 
+#[error_code]
+pub enum WithdrawRequestError {
+    #[msg("No pending withdraw request")]
+    NoPendingRequest,
+    #[msg("A withdraw request is already pending")]
+    AlreadyPending,
+    #[msg("Withdraw request is not approved and its cooldown has not elapsed")]
+    NotReady,
+    #[msg("Withdraw amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Withdrawal does not match the pending withdraw request")]
+    RequestMismatch,
+}
+
 #[error_code]
 pub enum RepayOfferError {
     #[msg("Invalid mint asset of loan offer")]
@@ -108,4 +254,77 @@ pub enum LiquidateOfferError {
     InvalidMintAsset,
     #[msg("Invalid operator system account")]
     InvalidSystem,
-}
\ No newline at end of file
+    #[msg("Loan is neither past its liquidation threshold nor expired")]
+    LoanNotLiquidatable,
+    #[msg("Partial liquidation can not restore the loan health, liquidate it fully")]
+    PartialLiquidationNotPossible,
+    #[msg("Reported liquidation result is outside the slippage band of the oracle price")]
+    OutsideSlippageBand,
+}
+#[error_code]
+pub enum ReserveFundError {
+    #[msg("Reserve fee share exceeds the denominator")]
+    FeeShareTooHigh,
+    #[msg("Reserve fund balance is too low for this payout")]
+    InsufficientBalance,
+    #[msg("Payout exceeds the outstanding deficit of the loan")]
+    PayoutExceedsDeficit,
+    #[msg("Payout amount must be positive")]
+    ZeroPayout,
+    #[msg("Reserve fund and reserve vault must be passed together")]
+    IncompleteReserveAccounts,
+    #[msg("Collateral of the loan has to be auctioned before the reserve covers its deficit")]
+    CollateralNotSold,
+}
+
+#[error_code]
+pub enum SwapAdapterError {
+    #[msg("Swap program does not match the configured adapter")]
+    InvalidSwapProgram,
+    #[msg("Swap accounts do not match the configured account layout")]
+    AccountLayoutMismatch,
+    #[msg("Swap account layout is too long")]
+    TooManyAccounts,
+    #[msg("Swap returned less than the oracle derived minimum")]
+    MinimumOutNotMet,
+}
+
+#[error_code]
+pub enum BorrowRequestError {
+    #[msg("Max interest must be greater than 0")]
+    InterestGreaterThanZero,
+    #[msg("Interest exceeds the max interest of the borrow request")]
+    InterestExceedsMax,
+    #[msg("Duration does not match the tier duration")]
+    InvalidDuration,
+    #[msg("Collateral amount must be greater than 0")]
+    InvalidCollateralAmount,
+    #[msg("Borrow request status is invalid")]
+    InvalidRequestStatus,
+    #[msg("Borrow request is not initialized or not belong to borrower")]
+    InvalidBorrower,
+    #[msg("Invalid collateral mint asset")]
+    InvalidCollateralMintAsset,
+    #[msg("Invalid lend mint asset")]
+    InvalidLendMintAsset,
+    #[msg("Not enough amount")]
+    NotEnoughAmount,
+}
+
+#[error_code]
+pub enum OrderBookError {
+    #[msg("Order book of the tier is full")]
+    OrderBookFull,
+    #[msg("Lend offer is not listed in the order book")]
+    OfferNotListed,
+    #[msg("No lend offer matches the amount within the max interest")]
+    NoMatchingOffer,
+    #[msg("Lend offer is not the best match in the order book")]
+    NotBestOffer,
+    #[msg("Lend offer is below the minimum amount of the order book")]
+    OfferTooSmall,
+    #[msg("Order book of the tier is not set up")]
+    OrderBookRequired,
+    #[msg("Lend offer is already listed in the order book")]
+    OfferAlreadyListed,
+}
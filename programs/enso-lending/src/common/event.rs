@@ -3,19 +3,176 @@ use anchor_lang::{
     prelude::{borsh, AnchorDeserialize, AnchorSerialize, Pubkey},
 };
 
-use crate::LoanOfferStatus;
+use crate::{AuctionParameters, SwapAccountSlot, LoanOfferStatus, PauseFlags, ProposalAction, RiskParameters, Role};
+
+#[event]
+pub struct InitGlobalConfigEvent {
+    pub admin: Pubkey,
+    pub hot_wallet: Pubkey,
+    pub setting_change_delay: i64,
+    pub withdraw_request_cooldown: i64,
+    pub auction_parameters: AuctionParameters,
+    pub liquidation_slippage_bps: u64,
+}
+
+#[event]
+pub struct EditGlobalConfigEvent {
+    pub admin: Pubkey,
+    pub hot_wallet: Pubkey,
+    pub setting_change_delay: i64,
+    pub withdraw_request_cooldown: i64,
+    pub auction_parameters: AuctionParameters,
+    pub liquidation_slippage_bps: u64,
+}
+
+#[event]
+pub struct InitAssetConfigEvent {
+    pub mint: Pubkey,
+    pub price_feed_id: [u8; 32],
+    pub max_price_age: u64,
+    pub decimals: u8,
+    pub collateral_haircut_bps: u64,
+    pub liquidation_priority: u8,
+}
+
+#[event]
+pub struct EditAssetConfigEvent {
+    pub mint: Pubkey,
+    pub price_feed_id: [u8; 32],
+    pub max_price_age: u64,
+    pub enabled: bool,
+    pub collateral_haircut_bps: u64,
+    pub liquidation_priority: u8,
+}
+
+#[event]
+pub struct SetSwapAdapterEvent {
+    pub admin: Pubkey,
+    pub program_id: Pubkey,
+    pub instruction_discriminator: [u8; 8],
+    pub account_layout: Vec<SwapAccountSlot>,
+}
+
+#[event]
+pub struct InitReserveFundEvent {
+    pub lend_mint: Pubkey,
+    pub fee_share_bps: u64,
+}
+
+#[event]
+pub struct InitOrderBookEvent {
+    pub tier_id: String,
+    pub min_amount: u64,
+}
+
+#[event]
+pub struct LendOfferListedEvent {
+    pub tier_id: String,
+    pub lend_offer: Pubkey,
+    pub interest_bps: u64,
+    pub remaining_amount: u64,
+}
+
+#[event]
+pub struct OrderBookEntryEvictedEvent {
+    pub tier_id: String,
+    pub lend_offer: Pubkey,
+    pub interest_bps: u64,
+    pub remaining_amount: u64,
+}
+
+#[event]
+pub struct EditReserveFundEvent {
+    pub lend_mint: Pubkey,
+    pub fee_share_bps: u64,
+}
+
+#[event]
+pub struct ReserveFundInflowEvent {
+    pub lend_mint: Pubkey,
+    pub loan_offer_id: String,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct ReserveFundPayoutEvent {
+    pub lend_mint: Pubkey,
+    pub loan_offer_id: String,
+    pub lender: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub remaining_deficit: u64,
+}
+
+#[event]
+pub struct RoleGrantedEvent {
+    pub role: Role,
+    pub member: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RoleRevokedEvent {
+    pub role: Role,
+    pub member: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ApprovalConfigUpdatedEvent {
+    pub admin: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: String,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalApprovedEvent {
+    pub proposal_id: String,
+    pub approver: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: String,
+    pub executor: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct GlobalPauseUpdatedEvent {
+    pub pauser: Pubkey,
+    pub pause_flags: PauseFlags,
+}
+
+#[event]
+pub struct TierPauseUpdatedEvent {
+    pub pauser: Pubkey,
+    pub tier_id: String,
+    pub pause_flags: PauseFlags,
+}
 
 #[event]
 pub struct InitSettingAccountEvent {
     pub amount: u64,
+    pub min_amount: u64,
     pub duration: u64,
     pub owner: Pubkey,
     pub receiver: Pubkey,
     pub lend_mint_asset: Pubkey,
     pub collateral_mint_asset: Pubkey,
     pub tier_id: String,
-    pub lender_fee_percent: f64,
-    pub borrower_fee_percent: f64
+    pub lender_fee_bps: u64,
+    pub borrower_fee_bps: u64,
+    pub risk_parameters: RiskParameters,
 }
 
 #[event]
@@ -25,41 +182,70 @@ pub struct EditSettingAccountEvent {
     pub collateral_mint_asset: Pubkey,
     pub tier_id: String,
     pub amount: u64,
+    pub min_amount: u64,
     pub duration: u64,
-    pub lender_fee_percent: f64,
+    pub lender_fee_bps: u64,
+    pub borrower_fee_bps: u64,
+    pub risk_parameters: RiskParameters,
 }
 
 #[event]
-pub struct CloseSettingAccountEvent {
+pub struct SettingChangeQueuedEvent {
     pub tier_id: String,
+    pub amount: Option<u64>,
+    pub min_amount: Option<u64>,
+    pub duration: Option<u64>,
+    pub lender_fee_bps: Option<u64>,
+    pub borrower_fee_bps: Option<u64>,
+    pub risk_parameters: Option<RiskParameters>,
+    pub receiver: Pubkey,
+    pub lend_mint_asset: Pubkey,
+    pub collateral_mint_asset: Pubkey,
+    pub effective_at: i64,
 }
 
 #[event]
-pub struct CreateLendOfferEvent {
-    pub lender: Pubkey,
-    pub interest: f64,
-    pub lender_fee_percent: f64,
-    pub duration: u64,
-    pub amount: u64,
-    pub offer_id: String,
+pub struct SettingChangeCanceledEvent {
+    pub tier_id: String,
+    pub owner: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct TierOwnerProposedEvent {
     pub tier_id: String,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
 }
 
 #[event]
-pub struct EditLendOfferEvent {
+pub struct TierOwnerAcceptedEvent {
+    pub tier_id: String,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct CloseSettingAccountEvent {
+    pub tier_id: String,
+}
+
+#[event]
+pub struct CreateLendOfferEvent {
     pub lender: Pubkey,
-    pub interest: f64,
-    pub lender_fee_percent: f64,
+    pub interest_bps: u64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub amount: u64,
     pub offer_id: String,
+    pub tier_id: String,
 }
 
 #[event]
-pub struct LendOfferCancelRequestEvent {
+pub struct EditLendOfferEvent {
     pub lender: Pubkey,
-    pub interest: f64,
-    pub lender_fee_percent: f64,
+    pub interest_bps: u64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub amount: u64,
     pub offer_id: String,
@@ -68,8 +254,8 @@ pub struct LendOfferCancelRequestEvent {
 #[event]
 pub struct LendOfferCanceledEvent {
     pub lender: Pubkey,
-    pub interest: f64,
-    pub lender_fee_percent: f64,
+    pub interest_bps: u64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub amount: u64,
     pub offer_id: String,
@@ -79,9 +265,9 @@ pub struct LendOfferCanceledEvent {
 pub struct LoanOfferCreateRequestEvent {
     pub tier_id: String,
     pub lend_offer_id: String,
-    pub interest: f64,
+    pub interest_bps: u64,
     pub borrow_amount: u64,
-    pub lender_fee_percent: f64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub lend_mint_token: Pubkey,
     pub lender: Pubkey,
@@ -90,7 +276,7 @@ pub struct LoanOfferCreateRequestEvent {
     pub collateral_mint_token: Pubkey,
     pub collateral_amount: u64,
     pub status: LoanOfferStatus,
-    pub borrower_fee_percent: f64,
+    pub borrower_fee_bps: u64,
     pub started_at: i64,
 }
 
@@ -98,9 +284,9 @@ pub struct LoanOfferCreateRequestEvent {
 pub struct LoanOfferUpdateEvent {
     pub tier_id: String,
     pub lend_offer_id: String,
-    pub interest: f64,
+    pub interest_bps: u64,
     pub borrow_amount: u64,
-    pub lender_fee_percent: f64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub lend_mint_token: Pubkey,
     pub lender: Pubkey,
@@ -109,10 +295,20 @@ pub struct LoanOfferUpdateEvent {
     pub collateral_mint_token: Pubkey,
     pub collateral_amount: u64,
     pub status: LoanOfferStatus,
-    pub borrower_fee_percent: f64,
+    pub borrower_fee_bps: u64,
     pub started_at: i64,
 }
 
+#[event]
+pub struct MatchedLoanCanceledEvent {
+    pub loan_offer_id: String,
+    pub lend_offer_id: String,
+    pub borrower: Pubkey,
+    pub canceled_by: Pubkey,
+    pub borrow_amount: u64,
+    pub collateral_amount: u64,
+}
+
 #[event]
 pub struct WithdrawCollateralEvent {
     pub borrower: Pubkey,
@@ -121,6 +317,28 @@ pub struct WithdrawCollateralEvent {
     pub collateral_amount: u64,
 }
 
+#[event]
+pub struct WithdrawRequestedEvent {
+    pub borrower: Pubkey,
+    pub loan_offer_id: String,
+    pub withdraw_amount: u64,
+    pub requested_at: i64,
+}
+
+#[event]
+pub struct WithdrawRequestApprovedEvent {
+    pub system: Pubkey,
+    pub loan_offer_id: String,
+    pub withdraw_amount: u64,
+}
+
+#[event]
+pub struct WithdrawRequestCancelledEvent {
+    pub borrower: Pubkey,
+    pub loan_offer_id: String,
+    pub withdraw_amount: u64,
+}
+
 #[event]
 pub struct UnhealthyRatioDetectedEvent  {
     pub borrower: Pubkey,
@@ -141,9 +359,9 @@ pub struct LoanOfferExpiredEvent  {
 pub struct DepositCollateralLoanOfferEvent {
     pub tier_id: String,
     pub lend_offer_id: String,
-    pub interest: f64,
+    pub interest_bps: u64,
     pub borrow_amount: u64,
-    pub lender_fee_percent: f64,
+    pub lender_fee_bps: u64,
     pub duration: u64,
     pub lend_mint_token: Pubkey,
     pub lender: Pubkey,
@@ -152,7 +370,7 @@ pub struct DepositCollateralLoanOfferEvent {
     pub collateral_mint_token: Pubkey,
     pub collateral_amount: u64,
     pub status: LoanOfferStatus,
-    pub borrower_fee_percent: f64,
+    pub borrower_fee_bps: u64,
     pub started_at: i64,
 }
 
@@ -161,7 +379,7 @@ pub struct RepayLoanOfferEvent {
   pub borrower: Pubkey,
   pub loan_offer_id: String,
   pub repay_amount: u64,
-  pub borrower_fee_percent: f64,
+  pub borrower_fee_bps: u64,
   pub status: LoanOfferStatus,
 }
 
@@ -172,6 +390,58 @@ pub struct LiquidatingCollateralEvent {
     pub liquidating_at: u64,
 }
 
+#[event]
+pub struct PartialLiquidationEvent {
+    pub system: Pubkey,
+    pub offer_id: String,
+    pub seized_collateral_amount: u64,
+    pub debt_reduction_amount: u64,
+    pub lender_payout_amount: u64,
+    pub collateral_amount: u64,
+    pub borrow_amount: u64,
+}
+
+#[event]
+pub struct LoanLiquidatedEvent {
+    pub liquidator: Pubkey,
+    pub borrower: Pubkey,
+    pub loan_offer_id: String,
+    pub repay_amount: u64,
+    pub seized_collateral_amount: u64,
+    pub returned_collateral_amount: u64,
+}
+
+#[event]
+pub struct LiquidationAuctionStartedEvent {
+    pub initiator: Pubkey,
+    pub loan_offer_id: String,
+    pub collateral_amount: u64,
+    pub start_lot_price: u64,
+    pub floor_lot_price: u64,
+    pub debt_amount: u64,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct LiquidationAuctionBidEvent {
+    pub bidder: Pubkey,
+    pub loan_offer_id: String,
+    pub collateral_amount: u64,
+    pub paid_amount: u64,
+    pub remaining_collateral: u64,
+    pub proceeds: u64,
+}
+
+#[event]
+pub struct LiquidationAuctionSettledEvent {
+    pub loan_offer_id: String,
+    pub lender_amount: u64,
+    pub fee_amount: u64,
+    pub borrower_surplus: u64,
+    pub returned_collateral_amount: u64,
+    pub retained_collateral_amount: u64,
+}
+
 #[event]
 pub struct LiquidatedCollateralEvent {
     pub loan_offer_id: String,
@@ -185,11 +455,33 @@ pub struct LiquidatedCollateralEvent {
     pub status: LoanOfferStatus,
 }
 
+#[event]
+pub struct SwapLiquidationEvent {
+    pub liquidator: Pubkey,
+    pub loan_offer_id: String,
+    pub collateral_amount: u64,
+    pub minimum_out: u64,
+    pub proceeds: u64,
+    pub lender_amount: u64,
+    pub fee_amount: u64,
+    pub borrower_surplus: u64,
+}
+
+#[event]
+pub struct BadDebtEvent {
+    pub loan_offer_id: String,
+    pub tier_id: String,
+    pub lender: Pubkey,
+    pub deficit_amount: u64,
+    pub tier_bad_debt_amount: u64,
+    pub tier_defaulted_loan_count: u64,
+}
+
 #[event]
 pub struct SystemRepayLoanOfferNativeEvent {
     pub lender: Pubkey,
     pub borrower: Pubkey,
-    pub interest: f64,
+    pub interest_bps: u64,
     pub loan_amount: u64,
     pub collateral_amount: u64,
     pub loan_offer_id: String,
@@ -202,7 +494,7 @@ pub struct SystemFinishLoanOfferEvent {
     pub system: Pubkey,
     pub lender: Pubkey,
     pub borrower: Pubkey,
-    pub interest: f64,
+    pub interest_bps: u64,
     pub loan_amount: u64,
     pub loan_offer_id: String,
     pub tier_id: String,
@@ -213,4 +505,71 @@ pub struct SystemFinishLoanOfferEvent {
 pub struct SystemRevertEvent {
     pub offer_id: String,
     pub status: LoanOfferStatus,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct CollateralPositionDepositedEvent {
+    pub loan_offer_id: String,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub position_amount: u64,
+}
+
+#[event]
+pub struct CollateralPositionWithdrawRequestedEvent {
+    pub loan_offer_id: String,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub withdraw_amount: u64,
+    pub requested_at: i64,
+}
+
+#[event]
+pub struct CollateralPositionWithdrawnEvent {
+    pub loan_offer_id: String,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub position_amount: u64,
+}
+
+#[event]
+pub struct CollateralPositionLiquidatedEvent {
+    pub loan_offer_id: String,
+    pub system: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BorrowRequestCreatedEvent {
+    pub request_id: String,
+    pub tier_id: String,
+    pub borrower: Pubkey,
+    pub lend_mint_token: Pubkey,
+    pub collateral_mint_token: Pubkey,
+    pub collateral_amount: u64,
+    pub amount: u64,
+    pub max_interest_bps: u64,
+    pub duration: u64,
+}
+
+#[event]
+pub struct BorrowRequestFilledEvent {
+    pub request_id: String,
+    pub tier_id: String,
+    pub borrower: Pubkey,
+    pub lender: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+    pub interest_bps: u64,
+    pub duration: u64,
+}
+
+#[event]
+pub struct BorrowRequestCanceledEvent {
+    pub request_id: String,
+    pub borrower: Pubkey,
+    pub collateral_amount: u64,
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{SettingAccount, SettingAccountError, TierOwnerAcceptedEvent, common::{ENSO_SEED, SETTING_ACCOUNT_SEED}};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct AcceptTierOwner<'info> {
+  pub new_owner: Signer<'info>,
+  #[account(
+    mut,
+    constraint = setting_account.pending_owner == Some(new_owner.key()) @ SettingAccountError::PendingOwnerMismatch,
+    constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(), 
+      crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+}
+
+impl<'info> AcceptTierOwner<'info> {
+  pub fn accept_tier_owner(&mut self) -> Result<Pubkey> {
+    let setting_account = &mut self.setting_account;
+    let previous_owner = setting_account.owner;
+
+    setting_account.owner = self.new_owner.key();
+    setting_account.pending_owner = None;
+
+    Ok(previous_owner)
+  }
+
+  pub fn emit_event_accept_tier_owner(&self, label: String, previous_owner: Pubkey) -> Result<()> {
+    emit!(TierOwnerAcceptedEvent {
+      tier_id: self.setting_account.tier_id.clone(),
+      previous_owner,
+      owner: self.setting_account.owner,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn accept_accounts(signer_key: Pubkey, owner: Pubkey, pending_owner: Option<Pubkey>) -> Vec<AccountInfo<'static>> {
+    let mut setting_account = setting_account(owner, Pubkey::new_unique(), crate::NATIVE_MINT);
+    setting_account.pending_owner = pending_owner;
+
+    vec![
+      signer(signer_key),
+      state(setting_account_key(TIER_ID), &setting_account),
+    ]
+  }
+
+  #[test]
+  fn pending_owner_takes_over_tier() {
+    let owner = Pubkey::new_unique();
+    let new_owner = Pubkey::new_unique();
+    let accounts = accept_accounts(new_owner, owner, Some(new_owner));
+    let (mut context, _, _) = load::<AcceptTierOwner, _>(accounts, &args(TIER_ID.to_string())).unwrap();
+
+    assert_eq!(context.accept_tier_owner().unwrap(), owner);
+    assert_eq!(context.setting_account.owner, new_owner);
+    assert_eq!(context.setting_account.pending_owner, None);
+  }
+
+  #[test]
+  fn rejects_signer_that_is_not_pending_owner() {
+    let owner = Pubkey::new_unique();
+    let accounts = accept_accounts(owner, owner, Some(Pubkey::new_unique()));
+
+    assert_error(load::<AcceptTierOwner, _>(accounts, &args(TIER_ID.to_string())).map(|_| ()), SettingAccountError::PendingOwnerMismatch);
+  }
+
+  #[test]
+  fn rejects_accept_without_proposal() {
+    let new_owner = Pubkey::new_unique();
+    let accounts = accept_accounts(new_owner, Pubkey::new_unique(), None);
+
+    assert_error(load::<AcceptTierOwner, _>(accounts, &args(TIER_ID.to_string())).map(|_| ()), SettingAccountError::PendingOwnerMismatch);
+  }
+}
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  EditSettingAccountEvent, PendingSettingChange, SettingAccount, SettingAccountError,
+  common::{ENSO_SEED, PENDING_SETTING_CHANGE_SEED, SETTING_ACCOUNT_SEED}
+};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct ApplySettingChange<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+  #[account(
+    mut,
+    has_one = owner,
+    constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(), 
+      crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    mut,
+    close = owner,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PENDING_SETTING_CHANGE_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = pending_setting_change.bump
+  )]
+  pub pending_setting_change: Account<'info, PendingSettingChange>,
+}
+
+impl<'info> ApplySettingChange<'info> {
+  pub fn apply_setting_change(&mut self) -> Result<()> {
+    let pending_setting_change = &self.pending_setting_change;
+    if Clock::get()?.unix_timestamp < pending_setting_change.effective_at {
+      return err!(SettingAccountError::SettingChangeNotEffective);
+    }
+
+    let setting_account = &mut self.setting_account;
+    if let Some(amount) = pending_setting_change.amount {
+      setting_account.amount = amount;
+    }
+
+    if let Some(min_amount) = pending_setting_change.min_amount {
+      setting_account.min_amount = min_amount;
+    }
+
+    SettingAccount::validate_amount_bounds(setting_account.min_amount, setting_account.amount)?;
+
+    if let Some(duration) = pending_setting_change.duration {
+      setting_account.duration = duration;
+    }
+
+    if let Some(lender_fee_bps) = pending_setting_change.lender_fee_bps {
+      setting_account.lender_fee_bps = lender_fee_bps;
+    }
+
+    if let Some(borrower_fee_bps) = pending_setting_change.borrower_fee_bps {
+      setting_account.borrower_fee_bps = borrower_fee_bps;
+    }
+
+    if let Some(risk_parameters) = pending_setting_change.risk_parameters {
+      setting_account.risk_parameters = risk_parameters;
+    }
+
+    setting_account.receiver = pending_setting_change.receiver;
+    setting_account.lend_mint_asset = pending_setting_change.lend_mint_asset;
+    setting_account.collateral_mint_asset = pending_setting_change.collateral_mint_asset;
+
+    Ok(())
+  }
+
+  pub fn emit_event_apply_setting_change(&self, label: String) -> Result<()> {
+    emit!(EditSettingAccountEvent {
+      receiver: self.setting_account.receiver,
+      lend_mint_asset: self.setting_account.lend_mint_asset,
+      collateral_mint_asset: self.setting_account.collateral_mint_asset,
+      tier_id: self.setting_account.tier_id.clone(),
+      amount: self.setting_account.amount,
+      min_amount: self.setting_account.min_amount,
+      duration: self.setting_account.duration,
+      lender_fee_bps: self.setting_account.lender_fee_bps,
+      borrower_fee_bps: self.setting_account.borrower_fee_bps,
+      risk_parameters: self.setting_account.risk_parameters,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn apply_accounts(owner: Pubkey, pending_setting_change: &PendingSettingChange) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer(owner),
+      state(setting_account_key(TIER_ID), &setting_account(owner, Pubkey::new_unique(), crate::NATIVE_MINT)),
+      state(pda(&[ENSO_SEED, PENDING_SETTING_CHANGE_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()]), pending_setting_change),
+    ]
+  }
+
+  fn pending_setting_change(effective_at: i64, amount: Option<u64>, min_amount: Option<u64>) -> PendingSettingChange {
+    PendingSettingChange {
+      tier_id: TIER_ID.to_string(),
+      amount,
+      min_amount,
+      duration: None,
+      lender_fee_bps: Some(100),
+      borrower_fee_bps: None,
+      risk_parameters: None,
+      receiver: Pubkey::new_unique(),
+      lend_mint_asset: Pubkey::new_unique(),
+      collateral_mint_asset: crate::NATIVE_MINT,
+      queued_at: effective_at - 3_600,
+      effective_at,
+      bump: Pubkey::find_program_address(
+        &[ENSO_SEED, PENDING_SETTING_CHANGE_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()],
+        &crate::ID
+      ).1,
+    }
+  }
+
+  #[test]
+  fn rejects_change_before_it_is_effective() {
+    let owner = Pubkey::new_unique();
+    let accounts = apply_accounts(owner, &pending_setting_change(now() + 1, Some(2_000_000_000), None));
+    let (mut context, _, _) = load::<ApplySettingChange, _>(accounts, &args(TIER_ID.to_string())).unwrap();
+
+    assert_error(context.apply_setting_change(), SettingAccountError::SettingChangeNotEffective);
+  }
+
+  #[test]
+  fn applies_effective_change_and_closes_it() {
+    let owner = Pubkey::new_unique();
+    let pending_setting_change = pending_setting_change(now(), Some(2_000_000_000), None);
+    let accounts = apply_accounts(owner, &pending_setting_change);
+    let (mut context, _, _) = load::<ApplySettingChange, _>(accounts, &args(TIER_ID.to_string())).unwrap();
+    let owner_lamports = context.owner.lamports();
+    let pending_lamports = context.pending_setting_change.to_account_info().lamports();
+
+    context.apply_setting_change().unwrap();
+    context.exit(&crate::ID).unwrap();
+
+    let setting_account = read_state::<SettingAccount>(&context.setting_account.to_account_info());
+    assert_eq!(setting_account.amount, 2_000_000_000);
+    assert_eq!(setting_account.lender_fee_bps, 100);
+    assert_eq!(setting_account.borrower_fee_bps, 500);
+    assert_eq!(setting_account.receiver, pending_setting_change.receiver);
+    assert_eq!(context.owner.lamports(), owner_lamports + pending_lamports);
+    assert_eq!(context.pending_setting_change.to_account_info().lamports(), 0);
+  }
+
+  #[test]
+  fn rejects_change_that_inverts_amount_bounds() {
+    let owner = Pubkey::new_unique();
+    let accounts = apply_accounts(owner, &pending_setting_change(now(), None, Some(2_000_000_000)));
+    let (mut context, _, _) = load::<ApplySettingChange, _>(accounts, &args(TIER_ID.to_string())).unwrap();
+
+    assert_error(context.apply_setting_change(), SettingAccountError::InvalidAmountBounds);
+  }
+}
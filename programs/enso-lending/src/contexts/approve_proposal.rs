@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ApprovalError, APPROVAL_CONFIG_SEED, ENSO_SEED, MAX_APPROVERS, PROPOSAL_SEED},
+  ApprovalConfig, Proposal, ProposalApprovedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(proposal_id: String)]
+pub struct ApproveProposal<'info> {
+  pub approver: Signer<'info>,
+  #[account(
+    constraint = approval_config.is_approver(&approver.key()) @ ApprovalError::NotApprover,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    mut,
+    constraint = !proposal.executed @ ApprovalError::ProposalAlreadyExecuted,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = proposal.bump
+  )]
+  pub proposal: Account<'info, Proposal>,
+}
+
+impl<'info> ApproveProposal<'info> {
+  pub fn approve_proposal(&mut self) -> Result<()> {
+    let approver = self.approver.key();
+    let proposal = &mut self.proposal;
+
+    if proposal.approvals.contains(&approver) {
+      return err!(ApprovalError::AlreadyApproved);
+    }
+
+    if proposal.approvals.len() >= MAX_APPROVERS {
+      return err!(ApprovalError::ApproverLimit);
+    }
+
+    proposal.approvals.push(approver);
+
+    Ok(())
+  }
+
+  pub fn emit_event_approve_proposal(&self, label: String) -> Result<()> {
+    emit!(ProposalApprovedEvent {
+      proposal_id: self.proposal.proposal_id.clone(),
+      approver: self.approver.key(),
+      approvals: self.proposal.approval_count(&self.approval_config) as u8,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{test_utils::*, ProposalAction};
+
+  const PROPOSAL_ID: &str = "proposal";
+
+  fn approve_accounts(approver: Pubkey, approvers: Vec<Pubkey>, executed: bool) -> Vec<AccountInfo<'static>> {
+    let approval_config_key = pda(&[ENSO_SEED, APPROVAL_CONFIG_SEED, crate::ID.as_ref()]);
+    let proposal_seeds: &[&[u8]] = &[ENSO_SEED, PROPOSAL_SEED, PROPOSAL_ID.as_bytes(), crate::ID.as_ref()];
+    let (proposal_key, proposal_bump) = Pubkey::find_program_address(proposal_seeds, &crate::ID);
+
+    vec![
+      signer(approver),
+      state(approval_config_key, &ApprovalConfig {
+        approvers,
+        threshold: 2,
+        bump: Pubkey::find_program_address(&[ENSO_SEED, APPROVAL_CONFIG_SEED, crate::ID.as_ref()], &crate::ID).1,
+      }),
+      state(proposal_key, &Proposal {
+        proposal_id: PROPOSAL_ID.to_string(),
+        proposer: Pubkey::new_unique(),
+        action: ProposalAction::CloseSettingAccount { setting_account: Pubkey::new_unique() },
+        approvals: vec![],
+        executed,
+        created_at: now(),
+        bump: proposal_bump,
+      }),
+    ]
+  }
+
+  #[test]
+  fn approver_approves_once() {
+    let approver = Pubkey::new_unique();
+    let accounts = approve_accounts(approver, vec![approver], false);
+    let (mut context, _, _) = load::<ApproveProposal, _>(accounts, &args(PROPOSAL_ID.to_string())).unwrap();
+
+    context.approve_proposal().unwrap();
+    assert_eq!(context.proposal.approvals, vec![approver]);
+
+    assert_error(context.approve_proposal(), ApprovalError::AlreadyApproved);
+  }
+
+  #[test]
+  fn rejects_signer_that_is_not_approver() {
+    let accounts = approve_accounts(Pubkey::new_unique(), vec![Pubkey::new_unique()], false);
+
+    assert_error(load::<ApproveProposal, _>(accounts, &args(PROPOSAL_ID.to_string())).map(|_| ()), ApprovalError::NotApprover);
+  }
+
+  #[test]
+  fn rejects_approval_of_executed_proposal() {
+    let approver = Pubkey::new_unique();
+    let accounts = approve_accounts(approver, vec![approver], true);
+
+    assert_error(load::<ApproveProposal, _>(accounts, &args(PROPOSAL_ID.to_string())).map(|_| ()), ApprovalError::ProposalAlreadyExecuted);
+  }
+}
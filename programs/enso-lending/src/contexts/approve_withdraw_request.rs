@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{RoleError, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, ROLE_ACCOUNT_SEED},
+  LoanOfferAccount, LoanOfferError, LoanOfferStatus, Role, RoleAccount, WithdrawRequestApprovedEvent, WithdrawRequestError
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct ApproveWithdrawRequest<'info> {
+  pub system: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Matched
+    || loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::NotAvailableToWithdraw,
+    constraint = loan_offer.request_withdraw_amount.is_some() @ WithdrawRequestError::NoPendingRequest,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Settler as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+}
+
+impl<'info> ApproveWithdrawRequest<'info> {
+  pub fn approve_withdraw_request(&mut self) -> Result<()> {
+    self.loan_offer.request_withdraw_approved = true;
+
+    Ok(())
+  }
+
+  pub fn emit_event_approve_withdraw_request(&self, label: String) -> Result<()> {
+    emit!(WithdrawRequestApprovedEvent {
+      system: self.system.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      withdraw_amount: self.loan_offer.request_withdraw_amount.unwrap_or_default(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  fn approve_accounts(system: Pubkey, settlers: Vec<Pubkey>, pending: bool) -> Vec<AccountInfo<'static>> {
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT);
+    if pending {
+      loan_offer.request_withdraw_amount = Some(1_000);
+      loan_offer.request_withdraw_at = Some(now());
+    }
+
+    vec![
+      signer(system),
+      wallet(loan_offer.borrower),
+      state(loan_offer_key(&loan_offer.borrower, OFFER_ID), &loan_offer),
+      state(role_account_key(Role::Settler), &role_account(Role::Settler, settlers)),
+    ]
+  }
+
+  #[test]
+  fn settler_approves_pending_request() {
+    let system = Pubkey::new_unique();
+    let (mut context, _, _) = load::<ApproveWithdrawRequest, _>(approve_accounts(system, vec![system], true), &args(OFFER_ID.to_string())).unwrap();
+
+    context.approve_withdraw_request().unwrap();
+
+    assert!(context.loan_offer.request_withdraw_approved);
+    assert_eq!(context.loan_offer.executable_withdraw_amount(now(), 86_400, None).unwrap(), 1_000);
+  }
+
+  #[test]
+  fn rejects_approval_by_non_settler() {
+    let accounts = approve_accounts(Pubkey::new_unique(), vec![Pubkey::new_unique()], true);
+
+    assert_error(load::<ApproveWithdrawRequest, _>(accounts, &args(OFFER_ID.to_string())).map(|_| ()), RoleError::Unauthorized);
+  }
+
+  #[test]
+  fn rejects_approval_without_pending_request() {
+    let system = Pubkey::new_unique();
+    let accounts = approve_accounts(system, vec![system], false);
+
+    assert_error(load::<ApproveWithdrawRequest, _>(accounts, &args(OFFER_ID.to_string())).map(|_| ()), WithdrawRequestError::NoPendingRequest);
+  }
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::constant::{AUCTION_PROCEEDS_SEED, ENSO_SEED, LIQUIDATION_AUCTION_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED},
+  calculate_received_amount, native_vault_balance, transfer_from_native_vault, validate_vault_balance, AuctionError,
+  LiquidationAuction, LiquidationAuctionBidEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct BidLiquidationAuction<'info> {
+  #[account(mut)]
+  pub bidder: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Liquidating @ LoanOfferError::InvalidOfferStatus,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LIQUIDATION_AUCTION_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump = auction.bump
+  )]
+  pub auction: Account<'info, LiquidationAuction>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = bidder,
+    associated_token::token_program = token_program
+  )]
+  pub bidder_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      AUCTION_PROCEEDS_SEED.as_ref(),
+      auction.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub proceeds_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> BidLiquidationAuction<'info> {
+  pub fn bid_liquidation_auction(
+    &mut self,
+    bumps: &BidLiquidationAuctionBumps,
+    collateral_amount: u64,
+    max_lend_amount: u64
+  ) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    if current_timestamp > self.auction.ends_at() {
+      return err!(AuctionError::Ended);
+    }
+
+    let (fill_amount, cost) = self.auction.fill(collateral_amount, current_timestamp)?;
+    if fill_amount == 0 {
+      return err!(AuctionError::ZeroBidAmount);
+    }
+
+    if cost > max_lend_amount {
+      return err!(AuctionError::PriceAboveLimit);
+    }
+
+    self.pay_proceeds(cost)?;
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.bidder,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      fill_amount
+    )?;
+
+    let received_amount = calculate_received_amount(&self.lend_mint_asset.to_account_info(), cost)?;
+    let auction = &mut self.auction;
+    auction.remaining_collateral -= fill_amount;
+    auction.proceeds = auction.proceeds.checked_add(received_amount).ok_or(MathError::Overflow)?;
+    self.loan_offer.collateral_amount -= fill_amount;
+
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)?;
+
+    self.emit_event_bid_liquidation_auction(String::from("bid_liquidation_auction"), fill_amount, cost)
+  }
+
+  fn pay_proceeds(&self, amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.bidder_lend_ata.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.proceeds_vault.to_account_info(),
+      authority: self.bidder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, amount, self.lend_mint_asset.decimals)
+  }
+
+  fn emit_event_bid_liquidation_auction(&self, label: String, collateral_amount: u64, paid_amount: u64) -> Result<()> {
+    emit!(LiquidationAuctionBidEvent {
+      bidder: self.bidder.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      collateral_amount,
+      paid_amount,
+      remaining_collateral: self.auction.remaining_collateral,
+      proceeds: self.auction.proceeds,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const BIDDER_BALANCE: u64 = 200_000_000;
+
+  fn load_bid(elapsed: i64) -> (BidLiquidationAuction<'static>, BidLiquidationAuctionBumps) {
+    let bidder = Pubkey::new_unique();
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+    loan_offer.status = LoanOfferStatus::Liquidating;
+    let lend_mint = loan_offer.lend_mint_token;
+    let loan_offer_key = loan_offer_key(&loan_offer.borrower, OFFER_ID);
+    let auction_key = liquidation_auction_key(&loan_offer_key);
+    let mut auction = liquidation_auction(loan_offer_key, Pubkey::new_unique());
+    auction.started_at -= elapsed;
+    let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+    let proceeds_vault = pda(&[ENSO_SEED, AUCTION_PROCEEDS_SEED, auction_key.as_ref(), crate::ID.as_ref()]);
+
+    let accounts = vec![
+      signer(bidder),
+      wallet(loan_offer.borrower),
+      state(loan_offer_key, &loan_offer),
+      state(auction_key, &auction),
+      account(
+        collateral_vault,
+        System::id(),
+        Rent::default().minimum_balance(0) + loan_offer.collateral_amount,
+        vec![],
+        false,
+        false
+      ),
+      mint(lend_mint, spl_token::ID),
+      token_account(ata(&bidder, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, bidder, BIDDER_BALANCE),
+      token_account(proceeds_vault, spl_token::ID, lend_mint, auction_key, 0),
+      program(spl_token::ID),
+      program(System::id()),
+    ];
+    let (context, bumps, _) = load::<BidLiquidationAuction, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+    (context, bumps)
+  }
+
+  #[test]
+  fn bidder_buys_collateral_at_decayed_price() {
+    let (mut context, bumps) = load_bid(1_800);
+    let bidder_lamports = context.bidder.lamports();
+
+    context.bid_liquidation_auction(&bumps, 500_000_000, 57_000_000).unwrap();
+
+    assert_eq!(token_amount(&context.bidder_lend_ata.to_account_info()), BIDDER_BALANCE - 57_000_000);
+    assert_eq!(token_amount(&context.proceeds_vault.to_account_info()), 57_000_000);
+    assert_eq!(context.bidder.lamports(), bidder_lamports + 500_000_000);
+    assert_eq!(native_vault_balance(&context.collateral_vault).unwrap(), 500_000_000);
+    assert_eq!(context.auction.remaining_collateral, 500_000_000);
+    assert_eq!(context.auction.proceeds, 57_000_000);
+    assert_eq!(context.loan_offer.collateral_amount, 500_000_000);
+  }
+
+  #[test]
+  fn rejects_bid_above_bidder_limit() {
+    let (mut context, bumps) = load_bid(0);
+
+    assert_error(context.bid_liquidation_auction(&bumps, 500_000_000, 65_999_999), AuctionError::PriceAboveLimit);
+  }
+
+  #[test]
+  fn rejects_bid_after_auction_ended() {
+    let (mut context, bumps) = load_bid(3_601);
+
+    assert_error(context.bid_liquidation_auction(&bumps, 500_000_000, u64::MAX), AuctionError::Ended);
+  }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::constant::{AUCTION_PROCEEDS_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, LIQUIDATION_AUCTION_SEED, LOAN_OFFER_ACCOUNT_SEED},
+  calculate_received_amount, validate_vault_balance, AuctionError, LiquidationAuction, LiquidationAuctionBidEvent,
+  LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct BidLiquidationAuctionToken<'info> {
+  #[account(mut)]
+  pub bidder: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Liquidating @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LIQUIDATION_AUCTION_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump = auction.bump
+  )]
+  pub auction: Account<'info, LiquidationAuction>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = bidder,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub bidder_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = bidder,
+    associated_token::token_program = token_program
+  )]
+  pub bidder_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      AUCTION_PROCEEDS_SEED.as_ref(),
+      auction.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub proceeds_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> BidLiquidationAuctionToken<'info> {
+  pub fn bid_liquidation_auction(&mut self, collateral_amount: u64, max_lend_amount: u64) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    if current_timestamp > self.auction.ends_at() {
+      return err!(AuctionError::Ended);
+    }
+
+    let (fill_amount, cost) = self.auction.fill(collateral_amount, current_timestamp)?;
+    if fill_amount == 0 {
+      return err!(AuctionError::ZeroBidAmount);
+    }
+
+    if cost > max_lend_amount {
+      return err!(AuctionError::PriceAboveLimit);
+    }
+
+    self.pay_proceeds(cost)?;
+    self.transfer_collateral_to_bidder(fill_amount)?;
+
+    let received_amount = calculate_received_amount(&self.lend_mint_asset.to_account_info(), cost)?;
+    let auction = &mut self.auction;
+    auction.remaining_collateral -= fill_amount;
+    auction.proceeds = auction.proceeds.checked_add(received_amount).ok_or(MathError::Overflow)?;
+    self.loan_offer.collateral_amount -= fill_amount;
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)?;
+
+    self.emit_event_bid_liquidation_auction(String::from("bid_liquidation_auction_token"), fill_amount, cost)
+  }
+
+  fn pay_proceeds(&self, amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.bidder_lend_ata.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.proceeds_vault.to_account_info(),
+      authority: self.bidder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, amount, self.lend_mint_asset.decimals)
+  }
+
+  fn transfer_collateral_to_bidder(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.bidder_collateral_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), cpi_accounts, signer);
+
+    transfer_checked(cpi_ctx, amount, self.collateral_mint_asset.decimals)
+  }
+
+  fn emit_event_bid_liquidation_auction(&self, label: String, collateral_amount: u64, paid_amount: u64) -> Result<()> {
+    emit!(LiquidationAuctionBidEvent {
+      bidder: self.bidder.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      collateral_amount,
+      paid_amount,
+      remaining_collateral: self.auction.remaining_collateral,
+      proceeds: self.auction.proceeds,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
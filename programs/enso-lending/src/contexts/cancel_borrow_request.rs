@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+  common::{BORROW_REQUEST_ESCROW_SEED, BORROW_REQUEST_SEED, ENSO_SEED}, refund_escrow, BorrowRequestAccount, BorrowRequestCanceledEvent, BorrowRequestError, BorrowRequestStatus
+};
+
+#[derive(Accounts)]
+#[instruction(request_id: String)]
+pub struct CancelBorrowRequest<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    constraint = collateral_mint_asset.key() == borrow_request.collateral_mint_token @ BorrowRequestError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = borrow_request.status == BorrowRequestStatus::Created @ BorrowRequestError::InvalidRequestStatus,
+    constraint = borrow_request.borrower == borrower.key() @ BorrowRequestError::InvalidBorrower,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_SEED.as_ref(),
+      borrower.key().as_ref(),
+      request_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = borrow_request.bump
+  )]
+  pub borrow_request: Account<'info, BorrowRequestAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_ESCROW_SEED.as_ref(),
+      borrow_request.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrow_request_escrow: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelBorrowRequest<'info> {
+  pub fn cancel_borrow_request(&mut self) -> Result<()> {
+    let refund_amount = self.borrow_request_escrow.amount;
+    self.refund_escrow(refund_amount)?;
+
+    self.borrow_request.status = BorrowRequestStatus::Canceled;
+
+    self.emit_event_cancel_borrow_request(String::from("cancel_borrow_request"), refund_amount)
+  }
+
+  fn refund_escrow(&mut self, refund_amount: u64) -> Result<()> {
+    let borrower = self.borrow_request.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      BORROW_REQUEST_SEED,
+      borrower.as_ref(),
+      self.borrow_request.request_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.borrow_request.bump],
+    ]];
+
+    refund_escrow(
+      &mut self.borrow_request_escrow,
+      &self.collateral_mint_asset,
+      self.borrower_collateral_ata.to_account_info(),
+      self.borrow_request.to_account_info(),
+      self.borrower.to_account_info(),
+      self.token_program.to_account_info(),
+      signer,
+      refund_amount
+    )
+  }
+
+  fn emit_event_cancel_borrow_request(&self, label: String, refund_amount: u64) -> Result<()> {
+    emit!(BorrowRequestCanceledEvent {
+      request_id: self.borrow_request.request_id.clone(),
+      borrower: self.borrower.key(),
+      collateral_amount: refund_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const REQUEST_ID: &str = "request";
+
+  #[test]
+  fn refunds_escrowed_collateral_and_closes_escrow() {
+    let borrow_request = borrow_request(Pubkey::new_unique(), REQUEST_ID, Pubkey::new_unique(), Pubkey::new_unique());
+    let borrower = borrow_request.borrower;
+    let collateral_mint = borrow_request.collateral_mint_token;
+    let borrow_request_key = borrow_request_key(&borrower, REQUEST_ID);
+
+    let accounts = vec![
+      signer(borrower),
+      mint(collateral_mint, spl_token::ID),
+      token_account(ata(&borrower, &collateral_mint, &spl_token::ID), spl_token::ID, collateral_mint, borrower, 0),
+      state(borrow_request_key, &borrow_request),
+      token_account(
+        borrow_request_escrow_key(&borrow_request_key),
+        spl_token::ID,
+        collateral_mint,
+        borrow_request_key,
+        borrow_request.collateral_amount
+      ),
+      program(spl_token::ID),
+    ];
+    let mut context = load::<CancelBorrowRequest, _>(accounts, &args(REQUEST_ID.to_string())).unwrap().0;
+
+    context.cancel_borrow_request().unwrap();
+
+    assert_eq!(token_amount(&context.borrower_collateral_ata.to_account_info()), borrow_request.collateral_amount);
+    assert_eq!(context.borrow_request_escrow.to_account_info().lamports(), 0);
+    assert_eq!(context.borrow_request.status, BorrowRequestStatus::Canceled);
+  }
+}
@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::{
-  ENSO_SEED, LEND_OFFER_ACCOUNT_SEED,
+  refund_escrow, ENSO_SEED, LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED, OrderBook,
   common::{
-    LendOfferCancelRequestEvent, 
+    LendOfferCanceledEvent, 
     LendOfferStatus, 
     LendOfferError
   },
@@ -14,6 +15,17 @@ use crate::{
 pub struct CancelLendOffer<'info> {
 #[account(mut)]
   pub lender: Signer<'info>,
+  #[account(
+    mut,
+    associated_token::mint = mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = mint_asset.key() == lend_offer.lend_mint_token @ LendOfferError::InvalidMintAsset,
+  )]
+  pub mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     mut,
     constraint = lend_offer.status == LendOfferStatus::Created @ LendOfferError::InvalidOfferStatus,
@@ -27,22 +39,78 @@ pub struct CancelLendOffer<'info> {
     bump = lend_offer.bump
   )]
   pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_ESCROW_SEED.as_ref(),
+      lend_offer.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      lend_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
+  pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> CancelLendOffer<'info> {
   pub fn cancel_lend_offer(&mut self) -> Result<()>  {
-    self.lend_offer.status = LendOfferStatus::Canceling;
+    let refund_amount = self.lend_offer.remaining_amount;
+    self.refund_escrow(refund_amount)?;
 
-    Ok(())
+    self.lend_offer.remaining_amount = 0;
+    self.lend_offer.status = LendOfferStatus::Canceled;
+    let lend_offer = self.lend_offer.key();
+    OrderBook::update_if_initialized(&self.order_book, |order_book| Ok(order_book.remove(&lend_offer)))?;
+
+    self.emit_event_cancel_lend_offer(String::from("cancel_lend_offer"), refund_amount)
   }
 
-  pub fn emit_event_cancel_lend_offer(&mut self, label: String) -> Result<()> {
-    emit!(LendOfferCancelRequestEvent {
+  /// Loans already drawn from the offer are still funded from the escrow, so it is only
+  /// closed once nothing is left in it.
+  fn refund_escrow(&mut self, refund_amount: u64) -> Result<()> {
+    let lender = self.lend_offer.lender;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LEND_OFFER_ACCOUNT_SEED,
+      lender.as_ref(),
+      self.lend_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.lend_offer.bump],
+    ]];
+
+    refund_escrow(
+      &mut self.lend_escrow,
+      &self.mint_asset,
+      self.lender_ata_asset.to_account_info(),
+      self.lend_offer.to_account_info(),
+      self.lender.to_account_info(),
+      self.token_program.to_account_info(),
+      signer,
+      refund_amount
+    )
+  }
+
+  fn emit_event_cancel_lend_offer(&mut self, label: String, refund_amount: u64) -> Result<()> {
+    emit!(LendOfferCanceledEvent {
       lender: self.lender.key(),
-      amount: self.lend_offer.amount,
+      amount: refund_amount,
       duration: self.lend_offer.duration,
-      interest: self.lend_offer.interest,
-      lender_fee_percent: self.lend_offer.lender_fee_percent,
+      interest_bps: self.lend_offer.interest_bps,
+      lender_fee_bps: self.lend_offer.lender_fee_bps,
       offer_id: self.lend_offer.offer_id.clone()
     });
 
@@ -50,4 +118,66 @@ impl<'info> CancelLendOffer<'info> {
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "lend_offer";
+  const AMOUNT: u64 = 100_000_000;
+
+  fn load_context(lend_offer: &LendOfferAccount, escrow_amount: u64) -> CancelLendOffer<'static> {
+    let lender = lend_offer.lender;
+    let mint = lend_offer.lend_mint_token;
+    let lend_offer_key = lend_offer_key(&lender, OFFER_ID);
+
+    let accounts = vec![
+      signer(lender),
+      token_account(ata(&lender, &mint, &spl_token::ID), spl_token::ID, mint, lender, 0),
+      crate::test_utils::mint(mint, spl_token::ID),
+      state(lend_offer_key, lend_offer),
+      token_account(
+        pda(&[ENSO_SEED, LEND_ESCROW_SEED, lend_offer_key.as_ref(), crate::ID.as_ref()]),
+        spl_token::ID,
+        mint,
+        lend_offer_key,
+        escrow_amount
+      ),
+      empty(pda(&[ENSO_SEED, ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()])),
+      program(spl_token::ID),
+    ];
+
+    load::<CancelLendOffer, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+  }
+
+  #[test]
+  fn refunds_escrow_and_closes_it() {
+    let lend_offer = lend_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), AMOUNT);
+    let mut context = load_context(&lend_offer, AMOUNT);
+
+    context.cancel_lend_offer().unwrap();
+
+    assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), AMOUNT);
+    assert_eq!(context.lend_escrow.to_account_info().lamports(), 0);
+    assert_eq!(context.lend_offer.remaining_amount, 0);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Canceled);
+  }
+
+  #[test]
+  fn keeps_escrow_open_for_drawn_loans() {
+    let mut lend_offer = lend_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), AMOUNT);
+    lend_offer.remaining_amount = 60_000_000;
+    lend_offer.loan_count = 1;
+    let mut context = load_context(&lend_offer, AMOUNT);
+
+    context.cancel_lend_offer().unwrap();
+
+    assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), 60_000_000);
+    assert_eq!(token_amount(&context.lend_escrow.to_account_info()), 40_000_000);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Canceled);
+  }
+}
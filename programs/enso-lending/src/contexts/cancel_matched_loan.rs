@@ -0,0 +1,335 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+  common::{RoleError, ENSO_SEED, LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, ORDER_BOOK_SEED, ROLE_ACCOUNT_SEED},
+  refund_escrow, transfer_from_native_vault, LendOfferAccount, LendOfferStatus, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MatchedLoanCanceledEvent,
+  OrderBook, OrderBookEntryEvictedEvent, Role, RoleAccount, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct CancelMatchedLoan<'info> {
+  #[account(
+    constraint = authority.key() == loan_offer.borrower
+      || role_account.as_ref().is_some_and(|role_account| role_account.is_member(&authority.key())) @ RoleError::Unauthorized
+  )]
+  pub authority: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive back the collateral
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Matched @ LoanOfferError::InvalidOfferStatus,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
+  /// CHECK: This is the account used to make the lend offer seeds and receive the escrow refund
+  #[account(
+    mut,
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_OFFER_ACCOUNT_SEED.as_ref(),
+      lender.key().as_ref(),
+      loan_offer.lend_offer_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_offer.bump
+  )]
+  pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_ESCROW_SEED.as_ref(),
+      lend_offer.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      lend_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Funder as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump
+  )]
+  pub role_account: Option<Account<'info, RoleAccount>>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelMatchedLoan<'info> {
+  /// Cancels a loan the funder never paid out. The borrower or a funder can call it: the
+  /// collateral goes back to the borrower and the draw goes back to the lend offer, or to the
+  /// lender when the offer was canceled in the meantime.
+  pub fn cancel_matched_loan(&mut self, bumps: &CancelMatchedLoanBumps) -> Result<()> {
+    self.release_draw()?;
+
+    let collateral_amount = self.loan_offer.collateral_amount;
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.borrower,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      self.collateral_vault.lamports()
+    )?;
+
+    self.loan_offer.collateral_amount = 0;
+    self.loan_offer.status = LoanOfferStatus::Canceled;
+
+    self.emit_event_cancel_matched_loan(String::from("cancel_matched_loan"), collateral_amount)
+  }
+
+  fn release_draw(&mut self) -> Result<()> {
+    let draw_amount = self.loan_offer.borrow_amount;
+
+    if self.lend_offer.status != LendOfferStatus::Canceled {
+      self.lend_offer.undraw(draw_amount)?;
+      if self.lend_offer.status == LendOfferStatus::Created {
+        let (lend_offer, interest_bps, remaining_amount) =
+          (self.lend_offer.key(), self.lend_offer.interest_bps, self.lend_offer.remaining_amount);
+        let relisted = OrderBook::update_if_initialized(&self.order_book, |order_book| {
+          order_book.relist(lend_offer, interest_bps, remaining_amount)
+        })?;
+        if let Some(Some(evicted_entry)) = relisted {
+          emit!(OrderBookEntryEvictedEvent {
+            tier_id: self.lend_offer.tier_id.clone(),
+            lend_offer: evicted_entry.lend_offer,
+            interest_bps: evicted_entry.interest_bps,
+            remaining_amount: evicted_entry.remaining_amount,
+          });
+        }
+      }
+
+      return Ok(());
+    }
+
+    self.refund_escrow(draw_amount)
+  }
+
+  /// A canceled offer keeps its escrow open only for the loans drawn from it, so the draw is
+  /// refunded to the lender and the escrow closed once nothing is left in it.
+  fn refund_escrow(&mut self, refund_amount: u64) -> Result<()> {
+    let lender = self.lend_offer.lender;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LEND_OFFER_ACCOUNT_SEED,
+      lender.as_ref(),
+      self.lend_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.lend_offer.bump],
+    ]];
+
+    refund_escrow(
+      &mut self.lend_escrow,
+      &self.mint_asset,
+      self.lender_ata_asset.to_account_info(),
+      self.lend_offer.to_account_info(),
+      self.lender.to_account_info(),
+      self.token_program.to_account_info(),
+      signer,
+      refund_amount
+    )
+  }
+
+  fn emit_event_cancel_matched_loan(&self, label: String, collateral_amount: u64) -> Result<()> {
+    emit!(MatchedLoanCanceledEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      lend_offer_id: self.loan_offer.lend_offer_id.clone(),
+      borrower: self.loan_offer.borrower,
+      canceled_by: self.authority.key(),
+      borrow_amount: self.loan_offer.borrow_amount,
+      collateral_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const BORROW_AMOUNT: u64 = 100_000_000;
+
+  struct Fixture {
+    authority: Pubkey,
+    funder: Option<Pubkey>,
+    loan_offer: LoanOfferAccount,
+    lend_offer: LendOfferAccount,
+  }
+
+  impl Fixture {
+    /// Matched loan that drew the whole 100 lend tokens of its offer against one SOL.
+    fn new() -> Self {
+      let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+      loan_offer.status = LoanOfferStatus::Matched;
+      let mut lend_offer = lend_offer(loan_offer.lender, &loan_offer.lend_offer_id, loan_offer.lend_mint_token, BORROW_AMOUNT);
+      lend_offer.draw(BORROW_AMOUNT).unwrap();
+
+      Self {
+        authority: loan_offer.borrower,
+        funder: None,
+        loan_offer,
+        lend_offer,
+      }
+    }
+
+    fn collateral_vault_rent() -> u64 {
+      Rent::default().minimum_balance(0)
+    }
+
+    fn load(&self) -> Result<(CancelMatchedLoan<'static>, CancelMatchedLoanBumps)> {
+      let borrower = self.loan_offer.borrower;
+      let lender = self.loan_offer.lender;
+      let mint = self.loan_offer.lend_mint_token;
+      let loan_offer_key = loan_offer_key(&borrower, OFFER_ID);
+      let lend_offer_key = lend_offer_key(&lender, &self.loan_offer.lend_offer_id);
+
+      let accounts = vec![
+        signer(self.authority),
+        wallet(borrower),
+        state(loan_offer_key, &self.loan_offer),
+        account(
+          pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]),
+          System::id(),
+          Self::collateral_vault_rent() + self.loan_offer.collateral_amount,
+          vec![],
+          false,
+          false
+        ),
+        wallet(lender),
+        state(lend_offer_key, &self.lend_offer),
+        token_account(
+          pda(&[ENSO_SEED, LEND_ESCROW_SEED, lend_offer_key.as_ref(), crate::ID.as_ref()]),
+          spl_token::ID,
+          mint,
+          lend_offer_key,
+          BORROW_AMOUNT
+        ),
+        crate::test_utils::mint(mint, spl_token::ID),
+        token_account(ata(&lender, &mint, &spl_token::ID), spl_token::ID, mint, lender, 0),
+        empty(pda(&[ENSO_SEED, ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()])),
+        match self.funder {
+          Some(funder) => state(role_account_key(Role::Funder), &role_account(Role::Funder, vec![funder])),
+          None => program(crate::ID),
+        },
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+
+      load::<CancelMatchedLoan, _>(accounts, &args(OFFER_ID.to_string())).map(|(context, bumps, _)| (context, bumps))
+    }
+
+    fn cancel(&self) -> Result<CancelMatchedLoan<'static>> {
+      let (mut context, bumps) = self.load()?;
+      context.cancel_matched_loan(&bumps)?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn borrower_cancel_returns_collateral_and_draw() {
+    let fixture = Fixture::new();
+    let context = fixture.cancel().unwrap();
+
+    assert_eq!(context.borrower.lamports(), Fixture::collateral_vault_rent() + fixture.loan_offer.collateral_amount);
+    assert_eq!(context.collateral_vault.lamports(), 0);
+    assert_eq!(context.loan_offer.collateral_amount, 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Canceled);
+    assert_eq!(context.lend_offer.remaining_amount, BORROW_AMOUNT);
+    assert_eq!(context.lend_offer.loan_count, 0);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Created);
+    assert_eq!(token_amount(&context.lend_escrow.to_account_info()), BORROW_AMOUNT);
+  }
+
+  #[test]
+  fn refunds_draw_of_canceled_lend_offer_to_lender() {
+    let mut fixture = Fixture::new();
+    fixture.lend_offer.status = LendOfferStatus::Canceled;
+    let context = fixture.cancel().unwrap();
+
+    assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), BORROW_AMOUNT);
+    assert_eq!(context.lend_escrow.to_account_info().lamports(), 0);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Canceled);
+  }
+
+  #[test]
+  fn funder_can_cancel_for_borrower() {
+    let mut fixture = Fixture::new();
+    fixture.authority = Pubkey::new_unique();
+    fixture.funder = Some(fixture.authority);
+    let context = fixture.cancel().unwrap();
+
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Canceled);
+    assert_eq!(context.borrower.lamports(), Fixture::collateral_vault_rent() + fixture.loan_offer.collateral_amount);
+  }
+
+  #[test]
+  fn rejects_cancel_by_stranger() {
+    let mut fixture = Fixture::new();
+    fixture.authority = Pubkey::new_unique();
+
+    assert_error(fixture.load().map(|_| ()), RoleError::Unauthorized);
+  }
+}
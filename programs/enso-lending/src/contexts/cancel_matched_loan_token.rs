@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{RoleError, COLLATERAL_VAULT_SEED, ENSO_SEED, LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED, ROLE_ACCOUNT_SEED},
+  refund_escrow, LendOfferAccount, LendOfferStatus, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MatchedLoanCanceledEvent, OrderBook, OrderBookEntryEvictedEvent, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct CancelMatchedLoanToken<'info> {
+  #[account(
+    constraint = authority.key() == loan_offer.borrower
+      || role_account.as_ref().is_some_and(|role_account| role_account.is_member(&authority.key())) @ RoleError::Unauthorized
+  )]
+  pub authority: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive back the collateral
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Matched @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This is the account used to make the lend offer seeds and receive the escrow refund
+  #[account(
+    mut,
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_OFFER_ACCOUNT_SEED.as_ref(),
+      lender.key().as_ref(),
+      loan_offer.lend_offer_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_offer.bump
+  )]
+  pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_ESCROW_SEED.as_ref(),
+      lend_offer.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      lend_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Funder as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump
+  )]
+  pub role_account: Option<Account<'info, RoleAccount>>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelMatchedLoanToken<'info> {
+  /// Cancels a loan the funder never paid out. The borrower or a funder can call it: the
+  /// collateral goes back to the borrower and the draw goes back to the lend offer, or to the
+  /// lender when the offer was canceled in the meantime.
+  pub fn cancel_matched_loan(&mut self) -> Result<()> {
+    self.release_draw()?;
+
+    let collateral_amount = self.loan_offer.collateral_amount;
+    self.release_collateral()?;
+
+    self.loan_offer.collateral_amount = 0;
+    self.loan_offer.status = LoanOfferStatus::Canceled;
+
+    self.emit_event_cancel_matched_loan(String::from("cancel_matched_loan_token"), collateral_amount)
+  }
+
+  fn release_draw(&mut self) -> Result<()> {
+    let draw_amount = self.loan_offer.borrow_amount;
+
+    if self.lend_offer.status != LendOfferStatus::Canceled {
+      self.lend_offer.undraw(draw_amount)?;
+      if self.lend_offer.status == LendOfferStatus::Created {
+        let (lend_offer, interest_bps, remaining_amount) =
+          (self.lend_offer.key(), self.lend_offer.interest_bps, self.lend_offer.remaining_amount);
+        let relisted = OrderBook::update_if_initialized(&self.order_book, |order_book| {
+          order_book.relist(lend_offer, interest_bps, remaining_amount)
+        })?;
+        if let Some(Some(evicted_entry)) = relisted {
+          emit!(OrderBookEntryEvictedEvent {
+            tier_id: self.lend_offer.tier_id.clone(),
+            lend_offer: evicted_entry.lend_offer,
+            interest_bps: evicted_entry.interest_bps,
+            remaining_amount: evicted_entry.remaining_amount,
+          });
+        }
+      }
+
+      return Ok(());
+    }
+
+    self.refund_escrow(draw_amount)
+  }
+
+  /// A canceled offer keeps its escrow open only for the loans drawn from it, so the draw is
+  /// refunded to the lender and the escrow closed once nothing is left in it.
+  fn refund_escrow(&mut self, refund_amount: u64) -> Result<()> {
+    let lender = self.lend_offer.lender;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LEND_OFFER_ACCOUNT_SEED,
+      lender.as_ref(),
+      self.lend_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.lend_offer.bump],
+    ]];
+
+    refund_escrow(
+      &mut self.lend_escrow,
+      &self.mint_asset,
+      self.lender_ata_asset.to_account_info(),
+      self.lend_offer.to_account_info(),
+      self.lender.to_account_info(),
+      self.token_program.to_account_info(),
+      signer,
+      refund_amount
+    )
+  }
+
+  fn release_collateral(&self) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    if self.collateral_vault.amount > 0 {
+      let transfer_accounts = TransferChecked {
+        from: self.collateral_vault.to_account_info(),
+        mint: self.collateral_mint_asset.to_account_info(),
+        to: self.borrower_collateral_ata.to_account_info(),
+        authority: self.loan_offer.to_account_info(),
+      };
+      transfer_checked(
+        CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+        self.collateral_vault.amount,
+        self.collateral_mint_asset.decimals,
+      )?;
+    }
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  fn emit_event_cancel_matched_loan(&self, label: String, collateral_amount: u64) -> Result<()> {
+    emit!(MatchedLoanCanceledEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      lend_offer_id: self.loan_offer.lend_offer_id.clone(),
+      borrower: self.loan_offer.borrower,
+      canceled_by: self.authority.key(),
+      borrow_amount: self.loan_offer.borrow_amount,
+      collateral_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  PendingSettingChange, SettingAccount, SettingAccountError, SettingChangeCanceledEvent,
+  common::{ENSO_SEED, PENDING_SETTING_CHANGE_SEED, SETTING_ACCOUNT_SEED}
+};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct CancelSettingChange<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+  #[account(
+    has_one = owner,
+    constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(), 
+      crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    mut,
+    close = owner,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PENDING_SETTING_CHANGE_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = pending_setting_change.bump
+  )]
+  pub pending_setting_change: Account<'info, PendingSettingChange>,
+}
+
+impl<'info> CancelSettingChange<'info> {
+  pub fn emit_event_cancel_setting_change(&self, label: String) -> Result<()> {
+    emit!(SettingChangeCanceledEvent {
+      tier_id: self.setting_account.tier_id.clone(),
+      owner: self.owner.key(),
+      effective_at: self.pending_setting_change.effective_at,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
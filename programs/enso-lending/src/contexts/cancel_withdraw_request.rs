@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED},
+  LoanOfferAccount, WithdrawRequestCancelledEvent, WithdrawRequestError
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct CancelWithdrawRequest<'info> {
+  pub borrower: Signer<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.request_withdraw_amount.is_some() @ WithdrawRequestError::NoPendingRequest,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+}
+
+impl<'info> CancelWithdrawRequest<'info> {
+  pub fn cancel_withdraw_request(&mut self) -> Result<()> {
+    let withdraw_amount = self.loan_offer.request_withdraw_amount.unwrap_or_default();
+
+    self.loan_offer.clear_withdraw_request();
+
+    self.emit_event_cancel_withdraw_request(String::from("cancel_withdraw_request"), withdraw_amount)
+  }
+
+  fn emit_event_cancel_withdraw_request(&self, label: String, withdraw_amount: u64) -> Result<()> {
+    emit!(WithdrawRequestCancelledEvent {
+      borrower: self.borrower.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      withdraw_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  #[test]
+  fn borrower_cancels_pending_request() {
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT);
+    loan_offer.request_withdraw_amount = Some(1_000);
+    loan_offer.request_withdraw_at = Some(now());
+    loan_offer.request_withdraw_approved = true;
+    let accounts = vec![signer(loan_offer.borrower), state(loan_offer_key(&loan_offer.borrower, OFFER_ID), &loan_offer)];
+    let (mut context, _, _) = load::<CancelWithdrawRequest, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+    context.cancel_withdraw_request().unwrap();
+
+    assert_eq!(context.loan_offer.request_withdraw_amount, None);
+    assert_eq!(context.loan_offer.request_withdraw_at, None);
+    assert!(!context.loan_offer.request_withdraw_approved);
+  }
+}
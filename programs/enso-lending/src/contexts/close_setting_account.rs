@@ -3,8 +3,8 @@ use anchor_lang::__private::CLOSED_ACCOUNT_DISCRIMINATOR;
 use std::io::{Cursor, Write};
 use std::ops::DerefMut;
 
-use crate::common::{CloseSettingAccountEvent, constant::{ ENSO_SEED, SETTING_ACCOUNT_SEED }};
-use crate::{SettingAccount, SettingAccountError};
+use crate::common::{CloseSettingAccountEvent, constant::{ APPROVAL_CONFIG_SEED, ENSO_SEED, PROPOSAL_SEED, SETTING_ACCOUNT_SEED }};
+use crate::{ApprovalConfig, Proposal, ProposalAction, SettingAccount, SettingAccountError};
 
 #[derive(Accounts)]
 #[instruction(tier_id: String)]
@@ -25,11 +25,39 @@ pub struct CloseSettingAccount<'info> {
     bump
   )]
   pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal.proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = proposal.bump
+  )]
+  pub proposal: Account<'info, Proposal>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> CloseSettingAccount<'info> {
   pub fn close_setting_account(&mut self) -> Result<()>  {
+    self.proposal.execute(
+      &self.approval_config,
+      &ProposalAction::CloseSettingAccount {
+        setting_account: self.setting_account.key(),
+      },
+      self.owner.key(),
+    )?;
+
     let dest_starting_lamports = self.owner.lamports();
     let setting_account = self.setting_account.to_account_info();
 
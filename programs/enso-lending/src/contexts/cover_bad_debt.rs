@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{
+    APPROVAL_CONFIG_SEED, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, PROPOSAL_SEED, RESERVE_FUND_SEED, RESERVE_VAULT_SEED,
+    ROLE_ACCOUNT_SEED
+  },
+  ApprovalConfig, LoanOfferAccount, LoanOfferError, LoanOfferStatus, Proposal, ProposalAction, ReserveFund, ReserveFundError,
+  ReserveFundPayoutEvent, RoleError, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct CoverBadDebt<'info> {
+  pub system: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds
+  pub borrower: UncheckedAccount<'info>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Defaulted @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_FUND_SEED.as_ref(),
+      lend_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = reserve_fund.bump
+  )]
+  pub reserve_fund: Account<'info, ReserveFund>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_VAULT_SEED.as_ref(),
+      lend_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub reserve_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Settler as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal.proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = proposal.bump
+  )]
+  pub proposal: Account<'info, Proposal>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CoverBadDebt<'info> {
+  pub fn cover_bad_debt(&mut self, amount: u64) -> Result<()> {
+    self.proposal.execute(
+      &self.approval_config,
+      &ProposalAction::CoverBadDebt {
+        loan_offer: self.loan_offer.key(),
+        amount,
+      },
+      self.system.key(),
+    )?;
+
+    if amount == 0 {
+      return err!(ReserveFundError::ZeroPayout);
+    }
+
+    // The reserve only pays what the collateral of the loan could not recover.
+    if self.loan_offer.collateral_amount > 0 {
+      return err!(ReserveFundError::CollateralNotSold);
+    }
+
+    self.loan_offer.deficit_amount = self.loan_offer.deficit_amount
+      .checked_sub(amount)
+      .ok_or(ReserveFundError::PayoutExceedsDeficit)?;
+    self.reserve_fund.record_payout(amount)?;
+
+    self.transfer_to_lender(amount)?;
+
+    self.emit_event_cover_bad_debt(String::from("cover_bad_debt"), amount)
+  }
+
+  fn transfer_to_lender(&self, amount: u64) -> Result<()> {
+    let lend_mint = self.lend_mint_asset.key();
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      RESERVE_FUND_SEED,
+      lend_mint.as_ref(),
+      program_id.as_ref(),
+      &[self.reserve_fund.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+      from: self.reserve_vault.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.lender_lend_ata.to_account_info(),
+      authority: self.reserve_fund.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer),
+      amount,
+      self.lend_mint_asset.decimals,
+    )
+  }
+
+  fn emit_event_cover_bad_debt(&self, label: String, amount: u64) -> Result<()> {
+    emit!(ReserveFundPayoutEvent {
+      lend_mint: self.reserve_fund.lend_mint,
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      lender: self.loan_offer.lender,
+      amount,
+      balance: self.reserve_fund.balance,
+      remaining_deficit: self.loan_offer.deficit_amount,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const PROPOSAL_ID: &str = "proposal";
+  const RESERVE_BALANCE: u64 = 50_000_000;
+  const DEFICIT: u64 = 19_500_000;
+
+  struct Fixture {
+    system: Pubkey,
+    loan_offer: LoanOfferAccount,
+    proposal_amount: u64,
+  }
+
+  impl Fixture {
+    /// Defaulted loan with its collateral sold, 19.5 lend tokens of bad debt and a reserve holding 50.
+    fn new(proposal_amount: u64) -> Self {
+      let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+      loan_offer.status = LoanOfferStatus::Defaulted;
+      loan_offer.deficit_amount = DEFICIT;
+      loan_offer.collateral_amount = 0;
+
+      Self {
+        system: Pubkey::new_unique(),
+        loan_offer,
+        proposal_amount,
+      }
+    }
+
+    fn load(&self) -> CoverBadDebt<'static> {
+      let loan_offer_key = loan_offer_key(&self.loan_offer.borrower, OFFER_ID);
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let lender = self.loan_offer.lender;
+      let action = ProposalAction::CoverBadDebt {
+        loan_offer: loan_offer_key,
+        amount: self.proposal_amount,
+      };
+
+      let accounts = vec![
+        signer(self.system),
+        wallet(self.loan_offer.borrower),
+        wallet(lender),
+        state(loan_offer_key, &self.loan_offer),
+        mint(lend_mint, spl_token::ID),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        state(reserve_fund_key(&lend_mint), &reserve_fund(lend_mint, 2_000, RESERVE_BALANCE)),
+        token_account(reserve_vault_key(&lend_mint), spl_token::ID, lend_mint, reserve_fund_key(&lend_mint), RESERVE_BALANCE),
+        state(role_account_key(Role::Settler), &role_account(Role::Settler, vec![self.system])),
+        state(approval_config_key(), &approval_config(vec![self.system], 1)),
+        state(proposal_key(PROPOSAL_ID), &proposal(PROPOSAL_ID, action, vec![self.system])),
+        program(spl_token::ID),
+      ];
+
+      load::<CoverBadDebt, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+    }
+  }
+
+  #[test]
+  fn pays_lender_from_reserve_vault() {
+    let fixture = Fixture::new(DEFICIT);
+    let mut context = fixture.load();
+
+    context.cover_bad_debt(DEFICIT).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), DEFICIT);
+    assert_eq!(token_amount(&context.reserve_vault.to_account_info()), RESERVE_BALANCE - DEFICIT);
+    assert_eq!(context.reserve_fund.balance, RESERVE_BALANCE - DEFICIT);
+    assert_eq!(context.reserve_fund.total_payout, DEFICIT);
+    assert_eq!(context.loan_offer.deficit_amount, 0);
+    assert!(context.proposal.executed);
+  }
+
+  #[test]
+  fn partial_payout_leaves_remaining_deficit() {
+    let fixture = Fixture::new(5_000_000);
+    let mut context = fixture.load();
+
+    context.cover_bad_debt(5_000_000).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 5_000_000);
+    assert_eq!(context.loan_offer.deficit_amount, DEFICIT - 5_000_000);
+  }
+
+  #[test]
+  fn rejects_payout_above_deficit() {
+    let fixture = Fixture::new(DEFICIT + 1);
+    let mut context = fixture.load();
+
+    assert_error(context.cover_bad_debt(DEFICIT + 1), ReserveFundError::PayoutExceedsDeficit);
+  }
+
+  #[test]
+  fn rejects_payout_while_collateral_is_unsold() {
+    let mut fixture = Fixture::new(DEFICIT);
+    fixture.loan_offer.collateral_amount = 400_000_000;
+    let mut context = fixture.load();
+
+    assert_error(context.cover_bad_debt(DEFICIT), ReserveFundError::CollateralNotSold);
+  }
+
+  #[test]
+  fn rejects_amount_other_than_proposed() {
+    let fixture = Fixture::new(DEFICIT);
+    let mut context = fixture.load();
+
+    assert_error(context.cover_bad_debt(DEFICIT - 1), crate::ApprovalError::ProposalActionMismatch);
+  }
+}
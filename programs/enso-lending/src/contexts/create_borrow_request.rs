@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{ASSET_CONFIG_SEED, BORROW_REQUEST_ESCROW_SEED, BORROW_REQUEST_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, SETTING_ACCOUNT_SEED}, calculate_received_amount, validate_vault_balance, AssetConfig, AssetConfigError, BorrowRequestAccount, BorrowRequestCreatedEvent, BorrowRequestError, BorrowRequestStatus, GlobalConfig, PauseAction, PauseError, SettingAccount, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(request_id: String, tier_id: String)]
+pub struct CreateBorrowRequest<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    constraint = collateral_mint_asset.key() == setting_account.collateral_mint_asset @ BorrowRequestError::InvalidCollateralMintAsset,
+    constraint = collateral_mint_asset.key() != NATIVE_MINT @ BorrowRequestError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    constraint = lend_mint_asset.key() == setting_account.lend_mint_asset @ BorrowRequestError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    init,
+    payer = borrower,
+    space = BorrowRequestAccount::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_SEED.as_ref(),
+      borrower.key().as_ref(),
+      request_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrow_request: Account<'info, BorrowRequestAccount>,
+  #[account(
+    init,
+    payer = borrower,
+    token::mint = collateral_mint_asset,
+    token::authority = borrow_request,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_ESCROW_SEED.as_ref(),
+      borrow_request.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrow_request_escrow: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = collateral_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      collateral_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::NewOffers) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::NewOffers) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateBorrowRequest<'info> {
+  #[allow(clippy::too_many_arguments)]
+  pub fn create_borrow_request(
+    &mut self,
+    bumps: &CreateBorrowRequestBumps,
+    request_id: String,
+    tier_id: String,
+    collateral_amount: u64,
+    amount: u64,
+    max_interest_bps: u64,
+    duration: u64,
+  ) -> Result<()> {
+    if max_interest_bps == 0 {
+      return err!(BorrowRequestError::InterestGreaterThanZero);
+    }
+
+    if duration != self.setting_account.duration {
+      return err!(BorrowRequestError::InvalidDuration);
+    }
+
+    if collateral_amount == 0 {
+      return err!(BorrowRequestError::InvalidCollateralAmount);
+    }
+
+    if self.borrower_collateral_ata.amount < collateral_amount {
+      return err!(BorrowRequestError::NotEnoughAmount);
+    }
+
+    self.setting_account.validate_lend_amount(amount)?;
+
+    let received_collateral_amount = calculate_received_amount(
+      &self.collateral_mint_asset.to_account_info(),
+      collateral_amount
+    )?;
+
+    self.deposit_collateral(collateral_amount)?;
+
+    self.borrow_request.set_inner(BorrowRequestAccount {
+      request_id,
+      tier_id,
+      borrower: self.borrower.key(),
+      lend_mint_token: self.lend_mint_asset.key(),
+      collateral_mint_token: self.collateral_mint_asset.key(),
+      collateral_amount: received_collateral_amount,
+      amount,
+      max_interest_bps,
+      duration,
+      bump: bumps.borrow_request,
+      status: BorrowRequestStatus::Created,
+    });
+
+    self.borrow_request_escrow.reload()?;
+    validate_vault_balance(self.borrow_request_escrow.amount, self.borrow_request.collateral_amount)
+  }
+
+  fn deposit_collateral(&self, collateral_amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.borrower_collateral_ata.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.borrow_request_escrow.to_account_info(),
+      authority: self.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, collateral_amount, self.collateral_mint_asset.decimals)
+  }
+
+  pub fn emit_event_create_borrow_request(&self, label: String) -> Result<()> {
+    emit!(BorrowRequestCreatedEvent {
+      request_id: self.borrow_request.request_id.clone(),
+      tier_id: self.borrow_request.tier_id.clone(),
+      borrower: self.borrow_request.borrower,
+      lend_mint_token: self.borrow_request.lend_mint_token,
+      collateral_mint_token: self.borrow_request.collateral_mint_token,
+      collateral_amount: self.borrow_request.collateral_amount,
+      amount: self.borrow_request.amount,
+      max_interest_bps: self.borrow_request.max_interest_bps,
+      duration: self.borrow_request.duration,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const REQUEST_ID: &str = "request";
+  const COLLATERAL_BALANCE: u64 = 300_000_000;
+  const COLLATERAL_AMOUNT: u64 = 250_000_000;
+  const AMOUNT: u64 = 100_000_000;
+  const DURATION: u64 = 30 * 24 * 60 * 60;
+
+  struct Fixture {
+    borrower: Pubkey,
+    lend_mint: Pubkey,
+    collateral_mint: Pubkey,
+  }
+
+  impl Fixture {
+    fn new() -> Self {
+      Self {
+        borrower: Pubkey::new_unique(),
+        lend_mint: Pubkey::new_unique(),
+        collateral_mint: Pubkey::new_unique(),
+      }
+    }
+
+    fn load(&self) -> (CreateBorrowRequest<'static>, CreateBorrowRequestBumps) {
+      let borrow_request = borrow_request_key(&self.borrower, REQUEST_ID);
+
+      let accounts = vec![
+        signer(self.borrower),
+        mint(self.collateral_mint, spl_token::ID),
+        mint(self.lend_mint, spl_token::ID),
+        token_account(
+          ata(&self.borrower, &self.collateral_mint, &spl_token::ID),
+          spl_token::ID,
+          self.collateral_mint,
+          self.borrower,
+          COLLATERAL_BALANCE
+        ),
+        empty(borrow_request),
+        empty(borrow_request_escrow_key(&borrow_request)),
+        state(asset_config_key(&self.collateral_mint), &asset_config(self.collateral_mint, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), self.lend_mint, self.collateral_mint)),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+      let ix_data = args((REQUEST_ID.to_string(), TIER_ID.to_string()));
+
+      load::<CreateBorrowRequest, _>(accounts, &ix_data).map(|(context, bumps, _)| (context, bumps)).unwrap()
+    }
+
+    fn create(&self, collateral_amount: u64, amount: u64, duration: u64) -> Result<CreateBorrowRequest<'static>> {
+      let (mut context, bumps) = self.load();
+      context.create_borrow_request(
+        &bumps,
+        REQUEST_ID.to_string(),
+        TIER_ID.to_string(),
+        collateral_amount,
+        amount,
+        1_500,
+        duration
+      )?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn escrows_collateral_under_request() {
+    let fixture = Fixture::new();
+    let context = fixture.create(COLLATERAL_AMOUNT, AMOUNT, DURATION).unwrap();
+
+    assert_eq!(token_amount(&context.borrow_request_escrow.to_account_info()), COLLATERAL_AMOUNT);
+    assert_eq!(context.borrow_request_escrow.owner, context.borrow_request.key());
+    assert_eq!(token_amount(&context.borrower_collateral_ata.to_account_info()), COLLATERAL_BALANCE - COLLATERAL_AMOUNT);
+    assert_eq!(context.borrow_request.collateral_amount, COLLATERAL_AMOUNT);
+    assert_eq!(context.borrow_request.amount, AMOUNT);
+    assert_eq!(context.borrow_request.status, BorrowRequestStatus::Created);
+  }
+
+  #[test]
+  fn rejects_duration_of_other_tier() {
+    let fixture = Fixture::new();
+
+    assert_error(fixture.create(COLLATERAL_AMOUNT, AMOUNT, DURATION + 1).map(|_| ()), BorrowRequestError::InvalidDuration);
+  }
+
+  #[test]
+  fn rejects_collateral_above_balance() {
+    let fixture = Fixture::new();
+
+    assert_error(fixture.create(COLLATERAL_BALANCE + 1, AMOUNT, DURATION).map(|_| ()), BorrowRequestError::NotEnoughAmount);
+  }
+
+  #[test]
+  fn rejects_amount_outside_tier_bounds() {
+    let fixture = Fixture::new();
+
+    assert_error(fixture.create(COLLATERAL_AMOUNT, 1_000_000_001, DURATION).map(|_| ()), crate::LendOfferError::InvalidLendAmount);
+  }
+}
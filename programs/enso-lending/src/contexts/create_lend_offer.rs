@@ -1,25 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 
-use crate::{CreateLendOfferEvent, LendOfferAccount, LendOfferError, LendOfferStatus, SettingAccount, constant::{ENSO_SEED, SETTING_ACCOUNT_SEED, LEND_OFFER_ACCOUNT_SEED}};
+use crate::{calculate_received_amount, CreateLendOfferEvent, GlobalConfig, LendOfferAccount, LendOfferError, LendOfferStatus, OrderBook, OrderBookEntryEvictedEvent, PauseAction, PauseError, SettingAccount, constant::{ENSO_SEED, GLOBAL_CONFIG_SEED, ORDER_BOOK_SEED, SETTING_ACCOUNT_SEED, LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED}};
 
 #[derive(Accounts)]
-#[instruction(offer_id: String, tier_id: String, interest: f64)]
+#[instruction(offer_id: String, tier_id: String, interest_bps: u64, amount: u64)]
 pub struct CreateLendOffer<'info> {
     #[account(mut)]
     pub lender: Signer<'info>,
     #[account(
         constraint = mint_asset.key() == setting_account.lend_mint_asset @ LendOfferError::InvalidMintAsset,
     )]
-    pub mint_asset: Account<'info, Mint>,
+    pub mint_asset: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
-        constraint = lender_ata_asset.amount >= setting_account.amount @ LendOfferError::NotEnoughAmount,
+        constraint = lender_ata_asset.amount >= amount @ LendOfferError::NotEnoughAmount,
         associated_token::mint = mint_asset,
-        associated_token::authority = lender
+        associated_token::authority = lender,
+        associated_token::token_program = token_program
     )]
-    pub lender_ata_asset: Account<'info, TokenAccount>,
+    pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
     #[account(
+        constraint = !setting_account.pause_flags.is_paused(PauseAction::NewOffers) @ PauseError::TierPaused,
         seeds = [
             ENSO_SEED.as_ref(), 
             SETTING_ACCOUNT_SEED.as_ref(),
@@ -43,13 +45,44 @@ pub struct CreateLendOffer<'info> {
         bump
     )]
     pub lend_offer: Account<'info, LendOfferAccount>,
+    #[account(
+        init,
+        payer = lender,
+        token::mint = mint_asset,
+        token::authority = lend_offer,
+        token::token_program = token_program,
+        seeds = [
+            ENSO_SEED.as_ref(),
+            LEND_ESCROW_SEED.as_ref(),
+            lend_offer.key().as_ref(),
+            crate::ID.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = !global_config.pause_flags.is_paused(PauseAction::NewOffers) @ PauseError::ProtocolPaused,
+        seeds = [
+            ENSO_SEED.as_ref(),
+            GLOBAL_CONFIG_SEED.as_ref(),
+            crate::ID.key().as_ref(),
+        ],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    /// CHECK: This is the order book of the tier, only updated once it has been set up
     #[account(
         mut,
-        associated_token::mint = mint_asset,
-        associated_token::authority = setting_account.receiver
+        seeds = [
+            ENSO_SEED.as_ref(),
+            ORDER_BOOK_SEED.as_ref(),
+            tier_id.as_bytes(),
+            crate::ID.key().as_ref(),
+        ],
+        bump
     )]
-    pub hot_wallet_ata: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub order_book: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -58,33 +91,59 @@ impl<'info> CreateLendOffer<'info> {
         &mut self,
         bumps: &CreateLendOfferBumps,
         offer_id: String,
-        interest: f64,
+        interest_bps: u64,
+        amount: u64,
     ) -> Result<()> {
-            if interest <= (0 as f64) {
+            if interest_bps == 0 {
                 return err!(LendOfferError::InterestGreaterThanZero);
             }
 
-            let SettingAccount { amount, lender_fee_percent, duration, .. } = self.setting_account.clone().into_inner();
+            self.setting_account.validate_lend_amount(amount)?;
+
+            let SettingAccount { lender_fee_bps, duration, .. } = self.setting_account.clone().into_inner();
+            let received_amount = calculate_received_amount(&self.mint_asset.to_account_info(), amount)?;
 
             self.lend_offer.set_inner(LendOfferAccount {
-                amount,
+                amount: received_amount,
+                remaining_amount: received_amount,
+                loan_count: 0,
                 duration,
                 bump: bumps.lend_offer,
-                interest,
-                lender_fee_percent,
+                interest_bps,
+                lender_fee_bps,
                 lender: self.lender.key(),
                 lend_mint_token: self.mint_asset.key(),
                 offer_id: offer_id.clone(),
+                tier_id: self.setting_account.tier_id.clone(),
                 status: LendOfferStatus::Created,
             });
 
+            // An offer the book can not take stays unlisted and can be listed later with
+            // `list_lend_offer`.
+            let lend_offer = self.lend_offer.key();
+            let listed = OrderBook::update_if_initialized(&self.order_book, |order_book| {
+                if !order_book.accepts(interest_bps, received_amount) {
+                    return Ok(None);
+                }
+
+                order_book.insert(lend_offer, interest_bps, received_amount)
+            })?;
+            if let Some(Some(evicted_entry)) = listed {
+                emit!(OrderBookEntryEvictedEvent {
+                    tier_id: self.lend_offer.tier_id.clone(),
+                    lend_offer: evicted_entry.lend_offer,
+                    interest_bps: evicted_entry.interest_bps,
+                    remaining_amount: evicted_entry.remaining_amount,
+                });
+            }
+
         Ok(())
     }
 
-    pub fn deposit(&mut self) -> Result<()> {
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
         transfer_checked(
             self.into_deposit_context(),
-            self.setting_account.amount,
+            amount,
             self.mint_asset.decimals,
         )
     }
@@ -93,7 +152,7 @@ impl<'info> CreateLendOffer<'info> {
         let cpi_accounts = TransferChecked {
             from: self.lender_ata_asset.to_account_info(),
             mint: self.mint_asset.to_account_info(),
-            to: self.hot_wallet_ata.to_account_info(),
+            to: self.lend_escrow.to_account_info(),
             authority: self.lender.to_account_info(),
         };
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
@@ -102,8 +161,8 @@ impl<'info> CreateLendOffer<'info> {
     pub fn emit_event_create_lend_offer(&mut self, label: String) -> Result<()> {
         emit!(CreateLendOfferEvent {
             lender: self.lender.key(),
-            interest: self.lend_offer.interest,
-            lender_fee_percent: self.lend_offer.lender_fee_percent,
+            interest_bps: self.lend_offer.interest_bps,
+            lender_fee_bps: self.lend_offer.lender_fee_bps,
             amount: self.lend_offer.amount,
             duration: self.lend_offer.duration,
             offer_id: self.lend_offer.offer_id.clone(),
@@ -115,3 +174,144 @@ impl<'info> CreateLendOffer<'info> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anchor_spl::token::spl_token;
+
+    use super::*;
+    use crate::{test_utils::*, PauseFlags};
+
+    const OFFER_ID: &str = "lend_offer";
+    const AMOUNT: u64 = 100_000_000;
+
+    struct Fixture {
+        lender: Pubkey,
+        mint: Pubkey,
+        global_pause_flags: PauseFlags,
+        tier_pause_flags: PauseFlags,
+        order_book: Option<OrderBook>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self {
+                lender: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                global_pause_flags: PauseFlags::default(),
+                tier_pause_flags: PauseFlags::default(),
+                order_book: None,
+            }
+        }
+
+        fn accounts(&self) -> Vec<AccountInfo<'static>> {
+            let lend_offer = lend_offer_key(&self.lender, OFFER_ID);
+            let mut setting_account = setting_account(Pubkey::new_unique(), self.mint, crate::NATIVE_MINT);
+            setting_account.pause_flags = self.tier_pause_flags;
+            let mut global_config = global_config(Pubkey::new_unique(), Pubkey::new_unique());
+            global_config.pause_flags = self.global_pause_flags;
+
+            vec![
+                signer(self.lender),
+                mint(self.mint, spl_token::ID),
+                token_account(ata(&self.lender, &self.mint, &spl_token::ID), spl_token::ID, self.mint, self.lender, AMOUNT),
+                state(setting_account_key(TIER_ID), &setting_account),
+                empty(lend_offer),
+                empty(pda(&[ENSO_SEED, LEND_ESCROW_SEED, lend_offer.as_ref(), crate::ID.as_ref()])),
+                state(global_config_key(), &global_config),
+                match &self.order_book {
+                    Some(order_book) => state(order_book_key(), order_book),
+                    None => empty(order_book_key()),
+                },
+                program(spl_token::ID),
+                program(System::id()),
+            ]
+        }
+
+        fn load(&self) -> Result<(CreateLendOffer<'static>, CreateLendOfferBumps)> {
+            let ix_data = args((OFFER_ID.to_string(), TIER_ID.to_string(), 1_000u64, AMOUNT));
+            load::<CreateLendOffer, _>(self.accounts(), &ix_data).map(|(context, bumps, _)| (context, bumps))
+        }
+    }
+
+    fn new_offers_paused() -> PauseFlags {
+        PauseFlags {
+            new_offers: true,
+            ..PauseFlags::default()
+        }
+    }
+
+    #[test]
+    fn rejects_new_offer_while_protocol_paused() {
+        let mut fixture = Fixture::new();
+        fixture.global_pause_flags = new_offers_paused();
+
+        assert_error(fixture.load().map(|_| ()), PauseError::ProtocolPaused);
+    }
+
+    #[test]
+    fn rejects_new_offer_while_tier_paused() {
+        let mut fixture = Fixture::new();
+        fixture.tier_pause_flags = new_offers_paused();
+
+        assert_error(fixture.load().map(|_| ()), PauseError::TierPaused);
+    }
+
+    #[test]
+    fn escrows_deposit_in_lend_offer_vault() {
+        let fixture = Fixture::new();
+        let (mut context, bumps) = fixture.load().unwrap();
+
+        context.initialize_lend_offer(&bumps, OFFER_ID.to_string(), 1_000, AMOUNT).unwrap();
+        context.deposit(AMOUNT).unwrap();
+
+        assert_eq!(token_amount(&context.lend_escrow.to_account_info()), AMOUNT);
+        assert_eq!(context.lend_escrow.owner, context.lend_offer.key());
+        assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), 0);
+        assert_eq!(context.lend_offer.amount, AMOUNT);
+        assert_eq!(context.lend_offer.remaining_amount, AMOUNT);
+    }
+
+    #[test]
+    fn lists_offer_in_tier_order_book() {
+        let mut fixture = Fixture::new();
+        fixture.order_book = Some(order_book());
+        let (mut context, bumps) = fixture.load().unwrap();
+
+        context.initialize_lend_offer(&bumps, OFFER_ID.to_string(), 1_000, AMOUNT).unwrap();
+
+        let order_book: OrderBook = read_state(&context.order_book.to_account_info());
+        assert_eq!(order_book.entries.len(), 1);
+        assert_eq!(order_book.entries[0].lend_offer, context.lend_offer.key());
+        assert_eq!(order_book.entries[0].remaining_amount, AMOUNT);
+    }
+
+    #[test]
+    fn leaves_offer_unlisted_when_order_book_is_full() {
+        let mut fixture = Fixture::new();
+        fixture.order_book = Some(full_order_book());
+        let (mut context, bumps) = fixture.load().unwrap();
+
+        context.initialize_lend_offer(&bumps, OFFER_ID.to_string(), 1_000, AMOUNT).unwrap();
+        context.deposit(AMOUNT).unwrap();
+
+        let order_book: OrderBook = read_state(&context.order_book.to_account_info());
+        assert_eq!(order_book.entries.len(), crate::MAX_ORDER_BOOK_ENTRIES);
+        assert!(order_book.entries.iter().all(|entry| entry.lend_offer != context.lend_offer.key()));
+        assert_eq!(context.lend_offer.status, LendOfferStatus::Created);
+        assert_eq!(token_amount(&context.lend_escrow.to_account_info()), AMOUNT);
+    }
+
+    #[test]
+    fn accepts_new_offer_while_other_actions_paused() {
+        let mut fixture = Fixture::new();
+        fixture.global_pause_flags = PauseFlags {
+            new_loans: true,
+            withdrawals: true,
+            liquidations: true,
+            ..PauseFlags::default()
+        };
+
+        assert!(fixture.load().is_ok());
+    }
+}
@@ -1,11 +1,9 @@
-use std::str::FromStr;
-
 use anchor_lang::{prelude::*, solana_program::{program::invoke_signed, system_instruction}};
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 use crate::{
-  common::{ENSO_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, convert_to_usd_price, LendOfferAccount, LendOfferStatus, LoanOfferAccount, LoanOfferCreateRequestEvent, LoanOfferError, LoanOfferStatus, SettingAccount, MIN_BORROW_HEALTH_RATIO, NATIVE_MINT, SOL_USD_PRICE_FEED_ID, USDC_USD_PRICE_FEED_ID
+  common::{ASSET_CONFIG_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, ORDER_BOOK_SEED, SETTING_ACCOUNT_SEED}, calculate_ltv_bps, mul_div, to_u64, native_vault_balance, validate_vault_balance, AssetConfig, AssetConfigError, GlobalConfig, LendOfferAccount, LendOfferStatus, LoanOfferAccount, LoanOfferCreateRequestEvent, LoanOfferError, LoanOfferStatus, MathError, OrderBook, OrderBookError, PauseAction, PauseError, SettingAccount, Rounding, NATIVE_MINT
 };
 
 #[derive(Accounts)]
@@ -13,19 +11,21 @@ use crate::{
   offer_id: String, 
   lend_offer_id: String, 
   tier_id: String, 
-  collateral_amount: u64
+  collateral_amount: u64,
+  borrow_amount: u64
 )]
 pub struct CreateLoanOfferNative<'info> {
   #[account(mut)]
   pub borrower: Signer<'info>,
   #[account(
     constraint = collateral_mint_asset.key() == setting_account.collateral_mint_asset @ LoanOfferError::InvalidCollateralMintAsset,
+    constraint = collateral_mint_asset.key() == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
   )]
-  pub collateral_mint_asset: Account<'info, Mint>,
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     constraint = lend_mint_asset.key() == setting_account.lend_mint_asset @ LoanOfferError::InvalidLendMintAsset,
   )]
-  pub lend_mint_asset: Account<'info, Mint>,
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     init,
     payer = borrower,
@@ -40,6 +40,17 @@ pub struct CreateLoanOfferNative<'info> {
     bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
   /// CHECK: This account is used to check the validate of lend offer account
   pub lender: AccountInfo<'info>,
   #[account(
@@ -58,6 +69,29 @@ pub struct CreateLoanOfferNative<'info> {
   pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
   pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
   #[account(
+    constraint = lend_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      lend_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = collateral_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      collateral_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::TierPaused,
     seeds = [
         ENSO_SEED.as_ref(), 
         SETTING_ACCOUNT_SEED.as_ref(),
@@ -67,11 +101,34 @@ pub struct CreateLoanOfferNative<'info> {
     bump = setting_account.bump
   )]
   pub setting_account: Account<'info, SettingAccount>,
-  pub token_program: Program<'info, Token>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> CreateLoanOfferNative<'info> {
+  #[allow(clippy::too_many_arguments)]
   pub fn initialize_loan_offer(
     &mut self,
     bumps: &CreateLoanOfferNativeBumps,
@@ -79,28 +136,35 @@ impl<'info> CreateLoanOfferNative<'info> {
     lend_offer_id: String, 
     tier_id: String, 
     collateral_amount: u64,
-    interest: f64
+    borrow_amount: u64,
+    interest_bps: u64
   ) -> Result<()> {
-    self.validate_initialize_loan_offer(collateral_amount, interest)?;
+    self.validate_initialize_loan_offer(collateral_amount, borrow_amount, interest_bps)?;
 
     self.deposit_collateral(collateral_amount)?;
 
-    self.lend_offer.status = LendOfferStatus::Loaned;
+    self.lend_offer.draw(borrow_amount)?;
+    let (lend_offer, remaining_amount) = (self.lend_offer.key(), self.lend_offer.remaining_amount);
+    OrderBook::update_if_initialized(&self.order_book, |order_book| order_book.update_remaining(&lend_offer, remaining_amount))?;
     self.loan_offer.set_inner(LoanOfferAccount {
       tier_id,
-      borrow_amount: self.lend_offer.amount,
+      borrow_amount,
       borrower: self.borrower.key(),
-      borrower_fee_percent: self.setting_account.borrower_fee_percent,
+      borrower_fee_bps: self.setting_account.borrower_fee_bps,
       bump: bumps.loan_offer,
       collateral_amount,
       request_withdraw_amount: None,
-      collateral_mint_token: Pubkey::from_str(NATIVE_MINT).unwrap(),
+      request_withdraw_at: None,
+      request_withdraw_approved: false,
+      request_withdraw_mint: None,
+      collateral_positions: Vec::new(),
+      collateral_mint_token: NATIVE_MINT,
       duration: self.lend_offer.duration,
-      interest: self.lend_offer.interest,
+      interest_bps: self.lend_offer.interest_bps,
       lend_mint_token: self.lend_offer.lend_mint_token.key(),
       lend_offer_id,
       lender: self.lend_offer.lender,
-      lender_fee_percent: self.lend_offer.lender_fee_percent,
+      lender_fee_bps: self.lend_offer.lender_fee_bps,
       offer_id,
       started_at: Clock::get()?.unix_timestamp,
       status: LoanOfferStatus::Matched,
@@ -108,18 +172,58 @@ impl<'info> CreateLoanOfferNative<'info> {
       liquidating_price: None,
       liquidated_tx: None,
       liquidated_price: None,
+      deficit_amount: 0,
     });
 
-    Ok(())
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)
+  }
+
+  /// Takes `lend_offer` at its listed rate for the part of `borrow_amount` it fills once it is
+  /// confirmed to be the cheapest offer of the tier order book, which has to cover the whole
+  /// amount within `max_interest_bps`. The loan gets the same share of `collateral_amount`.
+  /// Larger amounts are filled across several offers in rate order by repeating the call with
+  /// what is left to borrow and to post, in the same transaction.
+  #[allow(clippy::too_many_arguments)]
+  pub fn borrow_best_offer(
+    &mut self,
+    bumps: &CreateLoanOfferNativeBumps,
+    offer_id: String,
+    lend_offer_id: String,
+    tier_id: String,
+    collateral_amount: u64,
+    borrow_amount: u64,
+    max_interest_bps: u64
+  ) -> Result<()> {
+    let lend_offer = self.lend_offer.key();
+    let fill_amount = OrderBook::update_if_initialized(&self.order_book, |order_book| {
+      order_book.next_fill(&lend_offer, borrow_amount, max_interest_bps)
+    })?.ok_or(OrderBookError::OrderBookRequired)?;
+    let fill_collateral_amount = to_u64(mul_div(
+      collateral_amount as u128,
+      fill_amount as u128,
+      borrow_amount as u128,
+      Rounding::Up
+    )?)?;
+
+    let interest_bps = self.lend_offer.interest_bps;
+    self.initialize_loan_offer(
+      bumps,
+      offer_id,
+      lend_offer_id,
+      tier_id,
+      fill_collateral_amount,
+      fill_amount,
+      interest_bps
+    )
   }
 
   pub fn emit_event_create_loan_offer(&self, label: String) -> Result<()> {
     emit!(LoanOfferCreateRequestEvent {
       tier_id: self.loan_offer.tier_id.clone(),
       lend_offer_id: self.loan_offer.lend_offer_id.clone(),
-      interest: self.loan_offer.interest,
+      interest_bps: self.loan_offer.interest_bps,
       borrow_amount: self.loan_offer.borrow_amount,
-      lender_fee_percent: self.loan_offer.lender_fee_percent,
+      lender_fee_bps: self.loan_offer.lender_fee_bps,
       duration: self.loan_offer.duration,
       lend_mint_token: self.loan_offer.lend_mint_token,
       lender: self.loan_offer.lender,
@@ -128,7 +232,7 @@ impl<'info> CreateLoanOfferNative<'info> {
       collateral_mint_token: self.loan_offer.collateral_mint_token,
       collateral_amount: self.loan_offer.collateral_amount,
       status: self.loan_offer.status,
-      borrower_fee_percent: self.loan_offer.borrower_fee_percent,
+      borrower_fee_bps: self.loan_offer.borrower_fee_bps,
       started_at: self.loan_offer.started_at,
     });
 
@@ -137,24 +241,23 @@ impl<'info> CreateLoanOfferNative<'info> {
     Ok(())
   }
 
-  fn validate_initialize_loan_offer(&self, collateral_amount: u64, interest: f64) -> Result<()> {
-    if self.lend_offer.interest != interest {
+  fn validate_initialize_loan_offer(&self, collateral_amount: u64, borrow_amount: u64, interest_bps: u64) -> Result<()> {
+    if self.lend_offer.interest_bps != interest_bps {
       return err!(LoanOfferError::CanNotCreateLoanCauseLendInterestUpdated);
     }
 
-    let convert_collateral_amount_to_usd = convert_to_usd_price(
+    let convert_collateral_amount_to_usd = self.collateral_asset_config.collateral_value(
       &self.collateral_price_feed_account, 
-      SOL_USD_PRICE_FEED_ID,
-      collateral_amount as f64 / 10f64.powf(self.collateral_mint_asset.decimals as f64)
-    ).unwrap();
-    let convert_lend_amount_to_usd = convert_to_usd_price(
+      collateral_amount
+    )?;
+    let convert_lend_amount_to_usd = self.lend_asset_config.usd_value(
       &self.lend_price_feed_account, 
-      USDC_USD_PRICE_FEED_ID,
-      self.setting_account.amount as f64 / 10f64.powf(self.lend_mint_asset.decimals as f64)
-    ).unwrap();
-    let health_ratio = convert_collateral_amount_to_usd / convert_lend_amount_to_usd;
+      borrow_amount,
+      Rounding::Up
+    )?;
+    let ltv_bps = calculate_ltv_bps(convert_lend_amount_to_usd, convert_collateral_amount_to_usd)?;
 
-    if health_ratio < MIN_BORROW_HEALTH_RATIO {
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
         return err!(LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid);
     }
 
@@ -162,17 +265,18 @@ impl<'info> CreateLoanOfferNative<'info> {
   }
 
   fn deposit_collateral(&self, collateral_amount: u64) -> Result<()> {
+    let rent_reserve = Rent::get()?.minimum_balance(0).saturating_sub(self.collateral_vault.lamports());
     let transfer_instruction = system_instruction::transfer(
       &self.borrower.key(),
-      &self.loan_offer.key(),
-      collateral_amount
+      &self.collateral_vault.key(),
+      collateral_amount.checked_add(rent_reserve).ok_or(MathError::Overflow)?
     );
     
     invoke_signed(
       &transfer_instruction,
       &[
         self.borrower.to_account_info(),
-        self.loan_offer.to_account_info(),
+        self.collateral_vault.to_account_info(),
         self.system_program.to_account_info()
       ],
       &[],  
@@ -180,4 +284,174 @@ impl<'info> CreateLoanOfferNative<'info> {
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const LEND_OFFER_ID: &str = "lend_offer";
+  const LEND_AMOUNT: u64 = 1_000_000_000;
+  /// One SOL, worth 100 USD at the fixture price.
+  const COLLATERAL_AMOUNT: u64 = 1_000_000_000;
+
+  struct Fixture {
+    borrower: Pubkey,
+    lender: Pubkey,
+    lend_mint: Pubkey,
+    collateral_price: i64,
+    collateral_asset_config: AssetConfig,
+    lend_offer: LendOfferAccount,
+    order_book: Option<OrderBook>,
+  }
+
+  impl Fixture {
+    fn new() -> Self {
+      let lender = Pubkey::new_unique();
+      let lend_mint = Pubkey::new_unique();
+
+      Self {
+        borrower: Pubkey::new_unique(),
+        lender,
+        lend_mint,
+        collateral_price: usd_price(100),
+        collateral_asset_config: asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9),
+        lend_offer: lend_offer(lender, LEND_OFFER_ID, lend_mint, LEND_AMOUNT),
+        order_book: None,
+      }
+    }
+
+    fn accounts(&self) -> Vec<AccountInfo<'static>> {
+      let loan_offer = loan_offer_key(&self.borrower, OFFER_ID);
+      let order_book_key = pda(&[ENSO_SEED, ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()]);
+
+      vec![
+        signer(self.borrower),
+        mint(NATIVE_MINT, spl_token::ID),
+        mint(self.lend_mint, spl_token::ID),
+        empty(loan_offer),
+        wallet(pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer.as_ref(), crate::ID.as_ref()])),
+        wallet(self.lender),
+        state(lend_offer_key(&self.lender, LEND_OFFER_ID), &self.lend_offer),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, self.collateral_price, PRICE_EXPONENT),
+        state(asset_config_key(&self.lend_mint), &asset_config(self.lend_mint, LEND_FEED_ID, 6)),
+        state(asset_config_key(&NATIVE_MINT), &self.collateral_asset_config),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), self.lend_mint, NATIVE_MINT)),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        match &self.order_book {
+          Some(order_book) => state(order_book_key, order_book),
+          None => empty(order_book_key),
+        },
+        program(spl_token::ID),
+        program(System::id()),
+      ]
+    }
+
+    fn load(&self, collateral_amount: u64, borrow_amount: u64) -> Result<(CreateLoanOfferNative<'static>, CreateLoanOfferNativeBumps)> {
+      let ix_data = args((OFFER_ID.to_string(), LEND_OFFER_ID.to_string(), TIER_ID.to_string(), collateral_amount, borrow_amount));
+      load::<CreateLoanOfferNative, _>(self.accounts(), &ix_data).map(|(context, bumps, _)| (context, bumps))
+    }
+
+    fn create(&self, collateral_amount: u64, borrow_amount: u64) -> Result<CreateLoanOfferNative<'static>> {
+      let (mut context, bumps) = self.load(collateral_amount, borrow_amount)?;
+      context.initialize_loan_offer(
+        &bumps,
+        OFFER_ID.to_string(),
+        LEND_OFFER_ID.to_string(),
+        TIER_ID.to_string(),
+        collateral_amount,
+        borrow_amount,
+        self.lend_offer.interest_bps
+      )?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn accepts_loan_up_to_initial_ltv() {
+    // 50 USD against 100 USD of collateral sits exactly at the 50% initial LTV of the tier.
+    let context = Fixture::new().create(COLLATERAL_AMOUNT, 50_000_000).unwrap();
+
+    assert_eq!(context.loan_offer.borrow_amount, 50_000_000);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Matched);
+  }
+
+  #[test]
+  fn rejects_loan_above_initial_ltv() {
+    assert_error(
+      Fixture::new().create(COLLATERAL_AMOUNT, 50_000_001).map(|_| ()),
+      LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid
+    );
+  }
+
+  #[test]
+  fn rejects_loan_once_lend_rate_changed() {
+    let fixture = Fixture::new();
+    let (mut context, bumps) = fixture.load(COLLATERAL_AMOUNT, 10_000_000).unwrap();
+
+    let result = context.initialize_loan_offer(
+      &bumps,
+      OFFER_ID.to_string(),
+      LEND_OFFER_ID.to_string(),
+      TIER_ID.to_string(),
+      COLLATERAL_AMOUNT,
+      10_000_000,
+      fixture.lend_offer.interest_bps + 1
+    );
+
+    assert_error(result, LoanOfferError::CanNotCreateLoanCauseLendInterestUpdated);
+  }
+
+  #[test]
+  fn rejects_collateral_disabled_in_asset_registry() {
+    let mut fixture = Fixture::new();
+    fixture.collateral_asset_config.enabled = false;
+
+    assert_error(fixture.load(COLLATERAL_AMOUNT, 10_000_000).map(|_| ()), AssetConfigError::AssetDisabled);
+  }
+
+  #[test]
+  fn prices_collateral_through_asset_registry() {
+    // A 20% haircut leaves 80 USD of collateral value, so 50 USD is now above the initial LTV.
+    let mut fixture = Fixture::new();
+    fixture.collateral_asset_config.collateral_haircut_bps = 2_000;
+
+    assert!(fixture.create(COLLATERAL_AMOUNT, 40_000_000).is_ok());
+    assert_error(
+      fixture.create(COLLATERAL_AMOUNT, 50_000_000).map(|_| ()),
+      LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid
+    );
+  }
+
+  #[test]
+  fn holds_collateral_in_native_vault_apart_from_loan_state() {
+    let fixture = Fixture::new();
+    let (mut context, bumps) = fixture.load(COLLATERAL_AMOUNT, 50_000_000).unwrap();
+    let borrower_lamports = context.borrower.lamports();
+    let loan_offer = context.loan_offer.to_account_info();
+    let loan_offer_rent = loan_offer.lamports();
+
+    context.initialize_loan_offer(
+      &bumps,
+      OFFER_ID.to_string(),
+      LEND_OFFER_ID.to_string(),
+      TIER_ID.to_string(),
+      COLLATERAL_AMOUNT,
+      50_000_000,
+      fixture.lend_offer.interest_bps
+    ).unwrap();
+
+    let vault_rent = Rent::default().minimum_balance(0);
+    assert_eq!(native_vault_balance(&context.collateral_vault).unwrap(), COLLATERAL_AMOUNT);
+    assert_eq!(context.collateral_vault.lamports(), COLLATERAL_AMOUNT + vault_rent);
+    assert_eq!(loan_offer.lamports(), loan_offer_rent);
+    assert_eq!(context.borrower.lamports(), borrower_lamports - COLLATERAL_AMOUNT - vault_rent);
+    assert_eq!(context.loan_offer.collateral_amount, COLLATERAL_AMOUNT);
+  }
+}
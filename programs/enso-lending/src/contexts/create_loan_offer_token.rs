@@ -0,0 +1,493 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED, SETTING_ACCOUNT_SEED}, calculate_ltv_bps, mul_div, to_u64, calculate_received_amount, validate_vault_balance, AssetConfig, AssetConfigError, GlobalConfig, LendOfferAccount, LendOfferStatus, LoanOfferAccount, LoanOfferCreateRequestEvent, LoanOfferError, LoanOfferStatus, OrderBook, OrderBookError, PauseAction, PauseError, SettingAccount, Rounding, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(
+  offer_id: String, 
+  lend_offer_id: String, 
+  tier_id: String, 
+  collateral_amount: u64,
+  borrow_amount: u64
+)]
+pub struct CreateLoanOfferToken<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    constraint = collateral_mint_asset.key() == setting_account.collateral_mint_asset @ LoanOfferError::InvalidCollateralMintAsset,
+    constraint = collateral_mint_asset.key() != NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    constraint = lend_mint_asset.key() == setting_account.lend_mint_asset @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    init,
+    payer = borrower,
+    space = LoanOfferAccount::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    init,
+    payer = borrower,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This account is used to check the validate of lend offer account
+  pub lender: AccountInfo<'info>,
+  #[account(
+    mut,
+    constraint = lend_offer.status == LendOfferStatus::Created @ LoanOfferError::LendOfferIsNotAvailable,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      LEND_OFFER_ACCOUNT_SEED.as_ref(), 
+      lender.key().as_ref(), 
+      lend_offer_id.as_bytes(),
+      crate::ID.key().as_ref(), 
+    ],
+    bump = lend_offer.bump
+  )]
+  pub lend_offer: Account<'info, LendOfferAccount>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    constraint = lend_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      lend_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = collateral_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      collateral_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::TierPaused,
+    seeds = [
+        ENSO_SEED.as_ref(), 
+        SETTING_ACCOUNT_SEED.as_ref(),
+        tier_id.as_bytes(), 
+        crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateLoanOfferToken<'info> {
+  #[allow(clippy::too_many_arguments)]
+  pub fn initialize_loan_offer(
+    &mut self,
+    bumps: &CreateLoanOfferTokenBumps,
+    offer_id: String, 
+    lend_offer_id: String, 
+    tier_id: String, 
+    collateral_amount: u64,
+    borrow_amount: u64,
+    interest_bps: u64
+  ) -> Result<()> {
+    let received_collateral_amount = calculate_received_amount(
+      &self.collateral_mint_asset.to_account_info(),
+      collateral_amount
+    )?;
+
+    self.validate_initialize_loan_offer(received_collateral_amount, borrow_amount, interest_bps)?;
+
+    self.deposit_collateral(collateral_amount)?;
+
+    self.lend_offer.draw(borrow_amount)?;
+    let (lend_offer, remaining_amount) = (self.lend_offer.key(), self.lend_offer.remaining_amount);
+    OrderBook::update_if_initialized(&self.order_book, |order_book| order_book.update_remaining(&lend_offer, remaining_amount))?;
+    self.loan_offer.set_inner(LoanOfferAccount {
+      tier_id,
+      borrow_amount,
+      borrower: self.borrower.key(),
+      borrower_fee_bps: self.setting_account.borrower_fee_bps,
+      bump: bumps.loan_offer,
+      collateral_amount: received_collateral_amount,
+      request_withdraw_amount: None,
+      request_withdraw_at: None,
+      request_withdraw_approved: false,
+      request_withdraw_mint: None,
+      collateral_positions: Vec::new(),
+      collateral_mint_token: self.collateral_mint_asset.key(),
+      duration: self.lend_offer.duration,
+      interest_bps: self.lend_offer.interest_bps,
+      lend_mint_token: self.lend_offer.lend_mint_token.key(),
+      lend_offer_id,
+      lender: self.lend_offer.lender,
+      lender_fee_bps: self.lend_offer.lender_fee_bps,
+      offer_id,
+      started_at: Clock::get()?.unix_timestamp,
+      status: LoanOfferStatus::Matched,
+      liquidating_at: None,
+      liquidating_price: None,
+      liquidated_tx: None,
+      liquidated_price: None,
+      deficit_amount: 0,
+    });
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)
+  }
+
+  /// Takes `lend_offer` at its listed rate for the part of `borrow_amount` it fills once it is
+  /// confirmed to be the cheapest offer of the tier order book, which has to cover the whole
+  /// amount within `max_interest_bps`. The loan gets the same share of `collateral_amount`.
+  /// Larger amounts are filled across several offers in rate order by repeating the call with
+  /// what is left to borrow and to post, in the same transaction.
+  #[allow(clippy::too_many_arguments)]
+  pub fn borrow_best_offer(
+    &mut self,
+    bumps: &CreateLoanOfferTokenBumps,
+    offer_id: String,
+    lend_offer_id: String,
+    tier_id: String,
+    collateral_amount: u64,
+    borrow_amount: u64,
+    max_interest_bps: u64
+  ) -> Result<()> {
+    let lend_offer = self.lend_offer.key();
+    let fill_amount = OrderBook::update_if_initialized(&self.order_book, |order_book| {
+      order_book.next_fill(&lend_offer, borrow_amount, max_interest_bps)
+    })?.ok_or(OrderBookError::OrderBookRequired)?;
+    let fill_collateral_amount = to_u64(mul_div(
+      collateral_amount as u128,
+      fill_amount as u128,
+      borrow_amount as u128,
+      Rounding::Up
+    )?)?;
+
+    let interest_bps = self.lend_offer.interest_bps;
+    self.initialize_loan_offer(
+      bumps,
+      offer_id,
+      lend_offer_id,
+      tier_id,
+      fill_collateral_amount,
+      fill_amount,
+      interest_bps
+    )
+  }
+
+  pub fn emit_event_create_loan_offer(&self, label: String) -> Result<()> {
+    emit!(LoanOfferCreateRequestEvent {
+      tier_id: self.loan_offer.tier_id.clone(),
+      lend_offer_id: self.loan_offer.lend_offer_id.clone(),
+      interest_bps: self.loan_offer.interest_bps,
+      borrow_amount: self.loan_offer.borrow_amount,
+      lender_fee_bps: self.loan_offer.lender_fee_bps,
+      duration: self.loan_offer.duration,
+      lend_mint_token: self.loan_offer.lend_mint_token,
+      lender: self.loan_offer.lender,
+      offer_id: self.loan_offer.offer_id.clone(),
+      borrower: self.loan_offer.borrower,
+      collateral_mint_token: self.loan_offer.collateral_mint_token,
+      collateral_amount: self.loan_offer.collateral_amount,
+      status: self.loan_offer.status,
+      borrower_fee_bps: self.loan_offer.borrower_fee_bps,
+      started_at: self.loan_offer.started_at,
+    });
+
+    msg!(&label.clone());
+    
+    Ok(())
+  }
+
+  fn validate_initialize_loan_offer(&self, collateral_amount: u64, borrow_amount: u64, interest_bps: u64) -> Result<()> {
+    if self.lend_offer.interest_bps != interest_bps {
+      return err!(LoanOfferError::CanNotCreateLoanCauseLendInterestUpdated);
+    }
+
+    let convert_collateral_amount_to_usd = self.collateral_asset_config.collateral_value(
+      &self.collateral_price_feed_account, 
+      collateral_amount
+    )?;
+    let convert_lend_amount_to_usd = self.lend_asset_config.usd_value(
+      &self.lend_price_feed_account, 
+      borrow_amount,
+      Rounding::Up
+    )?;
+    let ltv_bps = calculate_ltv_bps(convert_lend_amount_to_usd, convert_collateral_amount_to_usd)?;
+
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
+        return err!(LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid);
+    }
+
+    Ok(())
+  }
+
+  fn deposit_collateral(&self, collateral_amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.borrower_collateral_ata.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.collateral_vault.to_account_info(),
+      authority: self.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, collateral_amount, self.collateral_mint_asset.decimals)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const LEND_OFFER_ID: &str = "lend_offer";
+  const LEND_AMOUNT: u64 = 1_000_000_000;
+  /// 100 collateral tokens, worth 100 USD at the fixture price.
+  const COLLATERAL_AMOUNT: u64 = 100_000_000;
+
+  struct Fixture {
+    borrower: Pubkey,
+    lender: Pubkey,
+    lend_mint: Pubkey,
+    collateral_mint: Pubkey,
+    tier_collateral_mint: Pubkey,
+    lend_offer: LendOfferAccount,
+    order_book: Option<OrderBook>,
+  }
+
+  impl Fixture {
+    fn new() -> Self {
+      let lender = Pubkey::new_unique();
+      let lend_mint = Pubkey::new_unique();
+      let collateral_mint = Pubkey::new_unique();
+
+      Self {
+        borrower: Pubkey::new_unique(),
+        lender,
+        lend_mint,
+        collateral_mint,
+        tier_collateral_mint: collateral_mint,
+        lend_offer: lend_offer(lender, LEND_OFFER_ID, lend_mint, LEND_AMOUNT),
+        order_book: None,
+      }
+    }
+
+    fn collateral_vault(&self) -> Pubkey {
+      let loan_offer = loan_offer_key(&self.borrower, OFFER_ID);
+      pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer.as_ref(), crate::ID.as_ref()])
+    }
+
+    fn accounts(&self) -> Vec<AccountInfo<'static>> {
+      let order_book_key = pda(&[ENSO_SEED, ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()]);
+
+      vec![
+        signer(self.borrower),
+        mint(self.collateral_mint, spl_token::ID),
+        mint(self.lend_mint, spl_token::ID),
+        token_account(
+          ata(&self.borrower, &self.collateral_mint, &spl_token::ID),
+          spl_token::ID,
+          self.collateral_mint,
+          self.borrower,
+          COLLATERAL_AMOUNT
+        ),
+        empty(loan_offer_key(&self.borrower, OFFER_ID)),
+        empty(self.collateral_vault()),
+        wallet(self.lender),
+        state(lend_offer_key(&self.lender, LEND_OFFER_ID), &self.lend_offer),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        state(asset_config_key(&self.lend_mint), &asset_config(self.lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&self.collateral_mint), &asset_config(self.collateral_mint, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), self.lend_mint, self.tier_collateral_mint)),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        match &self.order_book {
+          Some(order_book) => state(order_book_key, order_book),
+          None => empty(order_book_key),
+        },
+        program(spl_token::ID),
+        program(System::id()),
+      ]
+    }
+
+    fn load(&self, collateral_amount: u64, borrow_amount: u64) -> Result<(CreateLoanOfferToken<'static>, CreateLoanOfferTokenBumps)> {
+      let ix_data = args((OFFER_ID.to_string(), LEND_OFFER_ID.to_string(), TIER_ID.to_string(), collateral_amount, borrow_amount));
+      load::<CreateLoanOfferToken, _>(self.accounts(), &ix_data).map(|(context, bumps, _)| (context, bumps))
+    }
+
+    fn create(&self, collateral_amount: u64, borrow_amount: u64) -> Result<CreateLoanOfferToken<'static>> {
+      let (mut context, bumps) = self.load(collateral_amount, borrow_amount)?;
+      context.initialize_loan_offer(
+        &bumps,
+        OFFER_ID.to_string(),
+        LEND_OFFER_ID.to_string(),
+        TIER_ID.to_string(),
+        collateral_amount,
+        borrow_amount,
+        self.lend_offer.interest_bps
+      )?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn moves_collateral_into_loan_vault() {
+    let fixture = Fixture::new();
+    let context = fixture.create(COLLATERAL_AMOUNT, 50_000_000).unwrap();
+
+    let collateral_vault = context.collateral_vault.to_account_info();
+    assert_eq!(collateral_vault.key(), fixture.collateral_vault());
+    assert_eq!(token_amount(&collateral_vault), COLLATERAL_AMOUNT);
+    assert_eq!(context.collateral_vault.owner, context.loan_offer.key());
+    assert_eq!(token_amount(&context.borrower_collateral_ata.to_account_info()), 0);
+    assert_eq!(context.loan_offer.collateral_mint_token, fixture.collateral_mint);
+    assert_eq!(context.loan_offer.collateral_amount, COLLATERAL_AMOUNT);
+  }
+
+  #[test]
+  fn draws_borrowed_part_of_lend_offer() {
+    let fixture = Fixture::new();
+    let context = fixture.create(COLLATERAL_AMOUNT, 50_000_000).unwrap();
+
+    assert_eq!(context.loan_offer.borrow_amount, 50_000_000);
+    assert_eq!(context.lend_offer.remaining_amount, LEND_AMOUNT - 50_000_000);
+    assert_eq!(context.lend_offer.loan_count, 1);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Created);
+  }
+
+  #[test]
+  fn rejects_borrow_above_remaining_amount() {
+    let mut fixture = Fixture::new();
+    fixture.lend_offer.remaining_amount = 40_000_000;
+
+    assert_error(fixture.create(COLLATERAL_AMOUNT, 50_000_000).map(|_| ()), crate::LendOfferError::InvalidDrawAmount);
+  }
+
+  #[test]
+  fn best_offer_fills_its_part_and_leaves_the_book() {
+    let mut fixture = Fixture::new();
+    fixture.lend_offer.remaining_amount = 30_000_000;
+    let lend_offer = lend_offer_key(&fixture.lender, LEND_OFFER_ID);
+    let mut order_book = order_book();
+    order_book.insert(lend_offer, fixture.lend_offer.interest_bps, 30_000_000).unwrap();
+    order_book.insert(Pubkey::new_unique(), 1_200, LEND_AMOUNT).unwrap();
+    fixture.order_book = Some(order_book);
+    let (mut context, bumps) = fixture.load(COLLATERAL_AMOUNT, 40_000_000).unwrap();
+
+    context.borrow_best_offer(
+      &bumps,
+      OFFER_ID.to_string(),
+      LEND_OFFER_ID.to_string(),
+      TIER_ID.to_string(),
+      COLLATERAL_AMOUNT,
+      40_000_000,
+      1_200
+    ).unwrap();
+
+    // The offer covers 30 of the 40 lend tokens, so it takes three quarters of the collateral.
+    assert_eq!(context.loan_offer.borrow_amount, 30_000_000);
+    assert_eq!(context.loan_offer.collateral_amount, 75_000_000);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), 75_000_000);
+    assert_eq!(context.lend_offer.status, LendOfferStatus::Loaned);
+    let order_book: OrderBook = read_state(&context.order_book.to_account_info());
+    assert_eq!(order_book.entries.len(), 1);
+    assert!(order_book.entries.iter().all(|entry| entry.lend_offer != lend_offer));
+  }
+
+  #[test]
+  fn best_offer_must_lead_the_book() {
+    let mut fixture = Fixture::new();
+    let mut order_book = order_book();
+    order_book.insert(Pubkey::new_unique(), 800, LEND_AMOUNT).unwrap();
+    order_book.insert(lend_offer_key(&fixture.lender, LEND_OFFER_ID), fixture.lend_offer.interest_bps, LEND_AMOUNT).unwrap();
+    fixture.order_book = Some(order_book);
+    let (mut context, bumps) = fixture.load(COLLATERAL_AMOUNT, 40_000_000).unwrap();
+
+    assert_error(
+      context.borrow_best_offer(&bumps, OFFER_ID.to_string(), LEND_OFFER_ID.to_string(), TIER_ID.to_string(), COLLATERAL_AMOUNT, 40_000_000, 1_200),
+      OrderBookError::NotBestOffer
+    );
+  }
+
+  #[test]
+  fn best_offer_requires_an_order_book() {
+    let fixture = Fixture::new();
+    let (mut context, bumps) = fixture.load(COLLATERAL_AMOUNT, 40_000_000).unwrap();
+
+    assert_error(
+      context.borrow_best_offer(&bumps, OFFER_ID.to_string(), LEND_OFFER_ID.to_string(), TIER_ID.to_string(), COLLATERAL_AMOUNT, 40_000_000, 1_200),
+      OrderBookError::OrderBookRequired
+    );
+  }
+
+  #[test]
+  fn rejects_collateral_mint_of_other_tier() {
+    let mut fixture = Fixture::new();
+    fixture.tier_collateral_mint = Pubkey::new_unique();
+
+    assert_error(fixture.load(COLLATERAL_AMOUNT, 50_000_000).map(|_| ()), LoanOfferError::InvalidCollateralMintAsset);
+  }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ApprovalError, APPROVAL_CONFIG_SEED, ENSO_SEED, PROPOSAL_SEED},
+  ApprovalConfig, Proposal, ProposalAction, ProposalCreatedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(proposal_id: String)]
+pub struct CreateProposal<'info> {
+  #[account(mut)]
+  pub proposer: Signer<'info>,
+  #[account(
+    constraint = approval_config.is_approver(&proposer.key()) @ ApprovalError::NotApprover,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    init,
+    payer = proposer,
+    space = Proposal::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub proposal: Account<'info, Proposal>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateProposal<'info> {
+  pub fn create_proposal(
+    &mut self,
+    bumps: &CreateProposalBumps,
+    proposal_id: String,
+    action: ProposalAction
+  ) -> Result<()> {
+    self.proposal.set_inner(Proposal {
+      proposal_id,
+      proposer: self.proposer.key(),
+      action,
+      approvals: vec![self.proposer.key()],
+      executed: false,
+      created_at: Clock::get()?.unix_timestamp,
+      bump: bumps.proposal,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_create_proposal(&self, label: String) -> Result<()> {
+    emit!(ProposalCreatedEvent {
+      proposal_id: self.proposal.proposal_id.clone(),
+      proposer: self.proposer.key(),
+      action: self.proposal.action.clone(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -1,9 +1,8 @@
-use std::ops::Add;
-
 use anchor_lang::{prelude::*, solana_program::{program::invoke_signed, system_instruction}};
 
 use crate::{
-  common::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, DepositCollateralLoanOfferEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, SettingAccount
+  common::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, SETTING_ACCOUNT_SEED}, native_vault_balance, validate_vault_balance,
+  DepositCollateralLoanOfferEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError, SettingAccount, NATIVE_MINT
 };
 
 #[derive(Accounts)]
@@ -20,6 +19,7 @@ pub struct DepositCollateralLoanOfferNative<'info> {
     constraint = 
       loan_offer.status == LoanOfferStatus::Matched || loan_offer.status == LoanOfferStatus::FundTransferred 
       @ LoanOfferError::CanNotDepositCollateralToContractThatNotAvailable,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
     seeds = [
       ENSO_SEED.as_ref(),
       LOAN_OFFER_ACCOUNT_SEED.as_ref(),
@@ -30,6 +30,17 @@ pub struct DepositCollateralLoanOfferNative<'info> {
     bump = loan_offer.bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
   #[account(
     seeds = [
         ENSO_SEED.as_ref(), 
@@ -47,19 +58,21 @@ impl<'info> DepositCollateralLoanOfferNative<'info> {
   pub fn deposit_collateral_loan_offer(&mut self, amount: u64) -> Result<()> {
     self.deposit_collateral(amount)?;
 
-    let before_collateral_amount = self.loan_offer.collateral_amount;
-    self.loan_offer.collateral_amount = before_collateral_amount.add(amount);
+    self.loan_offer.collateral_amount = self.loan_offer.collateral_amount
+      .checked_add(amount)
+      .ok_or(MathError::Overflow)?;
+    self.loan_offer.clear_withdraw_request();
 
-    Ok(())
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)
   }
 
   pub fn emit_event_deposit_collateral_loan_offer(&self, label: String) -> Result<()> {
     emit!(DepositCollateralLoanOfferEvent {
       tier_id: self.loan_offer.tier_id.clone(),
       lend_offer_id: self.loan_offer.lend_offer_id.clone(),
-      interest: self.loan_offer.interest,
+      interest_bps: self.loan_offer.interest_bps,
       borrow_amount: self.loan_offer.borrow_amount,
-      lender_fee_percent: self.loan_offer.lender_fee_percent,
+      lender_fee_bps: self.loan_offer.lender_fee_bps,
       duration: self.loan_offer.duration,
       lend_mint_token: self.loan_offer.lend_mint_token,
       lender: self.loan_offer.lender,
@@ -68,7 +81,7 @@ impl<'info> DepositCollateralLoanOfferNative<'info> {
       collateral_mint_token: self.loan_offer.collateral_mint_token,
       collateral_amount: self.loan_offer.collateral_amount,
       status: self.loan_offer.status,
-      borrower_fee_percent: self.loan_offer.borrower_fee_percent,
+      borrower_fee_bps: self.loan_offer.borrower_fee_bps,
       started_at: self.loan_offer.started_at,
     });
 
@@ -80,7 +93,7 @@ impl<'info> DepositCollateralLoanOfferNative<'info> {
   fn deposit_collateral(&self, amount: u64) -> Result<()> {
      let transfer_instruction = system_instruction::transfer(
       &self.borrower.key(), 
-      &self.loan_offer.key(), 
+      &self.collateral_vault.key(), 
       amount
     );
     
@@ -88,7 +101,7 @@ impl<'info> DepositCollateralLoanOfferNative<'info> {
        &transfer_instruction,
        &[
          self.borrower.to_account_info(),
-         self.loan_offer.to_account_info(),          
+         self.collateral_vault.to_account_info(),
          self.system_program.to_account_info()
        ],
        &[],  
@@ -96,4 +109,51 @@ impl<'info> DepositCollateralLoanOfferNative<'info> {
  
      Ok(())
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{test_utils::*, CollateralVaultError};
+
+  const OFFER_ID: &str = "loan";
+  const AMOUNT: u64 = 500_000_000;
+
+  fn load_deposit(vault_balance: u64) -> DepositCollateralLoanOfferNative<'static> {
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+    loan_offer.request_withdraw_amount = Some(AMOUNT);
+    loan_offer.request_withdraw_at = Some(now());
+    let loan_offer_key = loan_offer_key(&loan_offer.borrower, OFFER_ID);
+    let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+
+    let accounts = vec![
+      signer(loan_offer.borrower),
+      state(loan_offer_key, &loan_offer),
+      account(collateral_vault, System::id(), Rent::default().minimum_balance(0) + vault_balance, vec![], false, false),
+      state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT)),
+      program(System::id()),
+    ];
+    let ix_data = args((OFFER_ID.to_string(), TIER_ID.to_string(), AMOUNT));
+
+    load::<DepositCollateralLoanOfferNative, _>(accounts, &ix_data).unwrap().0
+  }
+
+  #[test]
+  fn adds_deposit_to_native_vault_and_clears_withdraw_request() {
+    let mut context = load_deposit(1_000_000_000);
+    let loan_offer_lamports = context.loan_offer.to_account_info().lamports();
+
+    context.deposit_collateral_loan_offer(AMOUNT).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_amount, 1_000_000_000 + AMOUNT);
+    assert_eq!(native_vault_balance(&context.collateral_vault).unwrap(), 1_000_000_000 + AMOUNT);
+    assert_eq!(context.loan_offer.to_account_info().lamports(), loan_offer_lamports);
+    assert_eq!(context.loan_offer.request_withdraw_amount, None);
+  }
+
+  #[test]
+  fn rejects_deposit_when_vault_drifted_below_collateral_amount() {
+    let mut context = load_deposit(1_000_000_000 - 1);
+
+    assert_error(context.deposit_collateral_loan_offer(AMOUNT), CollateralVaultError::InsufficientBalance);
+  }
+}
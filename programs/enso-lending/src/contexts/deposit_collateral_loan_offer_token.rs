@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{COLLATERAL_VAULT_SEED, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED}, calculate_received_amount, validate_vault_balance, DepositCollateralLoanOfferEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String, amount: u64)]
+pub struct DepositCollateralLoanOfferToken<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = 
+      loan_offer.status == LoanOfferStatus::Matched || loan_offer.status == LoanOfferStatus::FundTransferred 
+      @ LoanOfferError::CanNotDepositCollateralToContractThatNotAvailable,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> DepositCollateralLoanOfferToken<'info> {
+  pub fn deposit_collateral_loan_offer(&mut self, amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.borrower_collateral_ata.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.collateral_vault.to_account_info(),
+      authority: self.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, amount, self.collateral_mint_asset.decimals)?;
+
+    let received_amount = calculate_received_amount(&self.collateral_mint_asset.to_account_info(), amount)?;
+    self.loan_offer.collateral_amount = self.loan_offer.collateral_amount
+      .checked_add(received_amount)
+      .ok_or(MathError::Overflow)?;
+    self.loan_offer.clear_withdraw_request();
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)
+  }
+
+  pub fn emit_event_deposit_collateral_loan_offer(&self, label: String) -> Result<()> {
+    emit!(DepositCollateralLoanOfferEvent {
+      tier_id: self.loan_offer.tier_id.clone(),
+      lend_offer_id: self.loan_offer.lend_offer_id.clone(),
+      interest_bps: self.loan_offer.interest_bps,
+      borrow_amount: self.loan_offer.borrow_amount,
+      lender_fee_bps: self.loan_offer.lender_fee_bps,
+      duration: self.loan_offer.duration,
+      lend_mint_token: self.loan_offer.lend_mint_token,
+      lender: self.loan_offer.lender,
+      offer_id: self.loan_offer.offer_id.clone(),
+      borrower: self.loan_offer.borrower,
+      collateral_mint_token: self.loan_offer.collateral_mint_token,
+      collateral_amount: self.loan_offer.collateral_amount,
+      status: self.loan_offer.status,
+      borrower_fee_bps: self.loan_offer.borrower_fee_bps,
+      started_at: self.loan_offer.started_at,
+    });
+
+    msg!(&label.clone());
+    
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token_2022::spl_token_2022;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  #[test]
+  fn credits_collateral_received_after_transfer_fee() {
+    let borrower = Pubkey::new_unique();
+    let collateral_mint = Pubkey::new_unique();
+    let loan_offer_key = loan_offer_key(&borrower, OFFER_ID);
+    let loan_offer = loan_offer(borrower, OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), collateral_mint);
+    let collateral_vault = pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+
+    let accounts = vec![
+      signer(borrower),
+      transfer_fee_mint(collateral_mint, 100, u64::MAX),
+      transfer_fee_token_account(ata(&borrower, &collateral_mint, &spl_token_2022::ID), collateral_mint, borrower, 1_000_000),
+      state(loan_offer_key, &loan_offer),
+      transfer_fee_token_account(collateral_vault, collateral_mint, loan_offer_key, loan_offer.collateral_amount),
+      program(spl_token_2022::ID),
+    ];
+    let (mut context, _, _) = load::<DepositCollateralLoanOfferToken, _>(accounts, &args((OFFER_ID.to_string(), 1_000_000u64))).unwrap();
+
+    context.deposit_collateral_loan_offer(1_000_000).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_amount, loan_offer.collateral_amount + 990_000);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), context.loan_offer.collateral_amount);
+    assert_eq!(token_amount(&context.borrower_collateral_ata.to_account_info()), 0);
+  }
+}
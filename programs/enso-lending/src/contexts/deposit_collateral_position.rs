@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, MAX_COLLATERAL_POSITIONS},
+  calculate_received_amount, validate_vault_balance, AssetConfig, AssetConfigError, CollateralPosition, CollateralPositionDepositedEvent,
+  CollateralPositionError, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String, amount: u64)]
+pub struct DepositCollateralPosition<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    constraint = mint.key() != loan_offer.collateral_mint_token @ CollateralPositionError::PrimaryCollateralMint,
+  )]
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = 
+      loan_offer.status == LoanOfferStatus::Matched || loan_offer.status == LoanOfferStatus::FundTransferred 
+      @ LoanOfferError::CanNotDepositCollateralToContractThatNotAvailable,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    constraint = asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = asset_config.bump
+  )]
+  pub asset_config: Account<'info, AssetConfig>,
+  #[account(
+    init_if_needed,
+    payer = borrower,
+    token::mint = mint,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositCollateralPosition<'info> {
+  pub fn deposit_collateral_position(&mut self, amount: u64) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+      from: self.borrower_ata.to_account_info(),
+      mint: self.mint.to_account_info(),
+      to: self.collateral_vault.to_account_info(),
+      authority: self.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+    let received_amount = calculate_received_amount(&self.mint.to_account_info(), amount)?;
+    let mint = self.mint.key();
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.clear_withdraw_request();
+
+    match loan_offer.find_collateral_position(&mint) {
+      Some(index) => {
+        let position = &mut loan_offer.collateral_positions[index];
+        position.amount = position.amount.checked_add(received_amount).ok_or(MathError::Overflow)?;
+      }
+      None => {
+        if loan_offer.collateral_positions.len() >= MAX_COLLATERAL_POSITIONS {
+          return err!(CollateralPositionError::PositionLimit);
+        }
+
+        loan_offer.collateral_positions.push(CollateralPosition {
+          mint,
+          amount: received_amount,
+        });
+      }
+    }
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.position_amount())?;
+
+    self.emit_event_deposit_collateral_position(String::from("deposit_collateral_position"), received_amount)
+  }
+
+  fn position_amount(&self) -> u64 {
+    self.loan_offer
+      .find_collateral_position(&self.mint.key())
+      .map(|index| self.loan_offer.collateral_positions[index].amount)
+      .unwrap_or_default()
+  }
+
+  fn emit_event_deposit_collateral_position(&self, label: String, amount: u64) -> Result<()> {
+    emit!(CollateralPositionDepositedEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      borrower: self.borrower.key(),
+      mint: self.mint.key(),
+      amount,
+      position_amount: self.position_amount(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const AMOUNT: u64 = 100_000_000;
+
+  fn accounts(loan_offer: &LoanOfferAccount, mint_key: Pubkey) -> Vec<AccountInfo<'static>> {
+    let loan_offer_key = loan_offer_key(&loan_offer.borrower, OFFER_ID);
+
+    vec![
+      signer(loan_offer.borrower),
+      mint(mint_key, spl_token::ID),
+      token_account(ata(&loan_offer.borrower, &mint_key, &spl_token::ID), spl_token::ID, mint_key, loan_offer.borrower, AMOUNT),
+      state(loan_offer_key, loan_offer),
+      state(asset_config_key(&mint_key), &asset_config(mint_key, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+      empty(pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), mint_key.as_ref(), crate::ID.as_ref()])),
+      program(spl_token::ID),
+      program(System::id()),
+    ]
+  }
+
+  fn test_loan_offer() -> LoanOfferAccount {
+    loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique())
+  }
+
+  #[test]
+  fn opens_position_backed_by_its_own_vault() {
+    let loan_offer = test_loan_offer();
+    let mint = Pubkey::new_unique();
+    let (mut context, _, _) = load::<DepositCollateralPosition, _>(accounts(&loan_offer, mint), &args((OFFER_ID.to_string(), AMOUNT))).unwrap();
+
+    context.deposit_collateral_position(AMOUNT).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_positions, vec![CollateralPosition { mint, amount: AMOUNT }]);
+    assert_eq!(context.loan_offer.collateral_amount, loan_offer.collateral_amount);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), AMOUNT);
+    assert_eq!(token_amount(&context.borrower_ata.to_account_info()), 0);
+  }
+
+  #[test]
+  fn adds_to_existing_position() {
+    let mut loan_offer = test_loan_offer();
+    let mint = Pubkey::new_unique();
+    loan_offer.collateral_positions.push(CollateralPosition { mint, amount: AMOUNT });
+    let mut accounts = accounts(&loan_offer, mint);
+    let loan_offer_key = loan_offer_key(&loan_offer.borrower, OFFER_ID);
+    accounts[5] = token_account(accounts[5].key(), spl_token::ID, mint, loan_offer_key, AMOUNT);
+    let (mut context, _, _) = load::<DepositCollateralPosition, _>(accounts, &args((OFFER_ID.to_string(), AMOUNT))).unwrap();
+
+    context.deposit_collateral_position(AMOUNT).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_positions, vec![CollateralPosition { mint, amount: 2 * AMOUNT }]);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), 2 * AMOUNT);
+  }
+
+  #[test]
+  fn rejects_position_in_primary_collateral_mint() {
+    let loan_offer = test_loan_offer();
+    let accounts = accounts(&loan_offer, loan_offer.collateral_mint_token);
+
+    assert_error(
+      load::<DepositCollateralPosition, _>(accounts, &args((OFFER_ID.to_string(), AMOUNT))).map(|_| ()),
+      CollateralPositionError::PrimaryCollateralMint
+    );
+  }
+
+  #[test]
+  fn rejects_position_beyond_limit() {
+    let mut loan_offer = test_loan_offer();
+    for _ in 0..MAX_COLLATERAL_POSITIONS {
+      loan_offer.collateral_positions.push(CollateralPosition { mint: Pubkey::new_unique(), amount: AMOUNT });
+    }
+    let (mut context, _, _) = load::<DepositCollateralPosition, _>(
+      accounts(&loan_offer, Pubkey::new_unique()),
+      &args((OFFER_ID.to_string(), AMOUNT))
+    ).unwrap();
+
+    assert_error(context.deposit_collateral_position(AMOUNT), CollateralPositionError::PositionLimit);
+  }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::{
+  common::{AssetConfigError, RoleError, ASSET_CONFIG_SEED, ENSO_SEED, ROLE_ACCOUNT_SEED},
+  AssetConfig, EditAssetConfigEvent, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+pub struct EditAssetConfig<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      asset_config.mint.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = asset_config.bump
+  )]
+  pub asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&authority.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+}
+
+impl<'info> EditAssetConfig<'info> {
+  pub fn edit_asset_config(
+    &mut self,
+    price_feed_id: Option<String>,
+    max_price_age: Option<u64>,
+    enabled: Option<bool>,
+    collateral_haircut_bps: Option<u64>,
+    liquidation_priority: Option<u8>
+  ) -> Result<()> {
+    let asset_config = &mut self.asset_config;
+
+    if let Some(price_feed_id) = price_feed_id {
+      asset_config.price_feed_id = get_feed_id_from_hex(&price_feed_id)
+        .map_err(|_| error!(AssetConfigError::InvalidPriceFeedId))?;
+    }
+
+    if let Some(max_price_age) = max_price_age {
+      asset_config.max_price_age = max_price_age;
+    }
+
+    if let Some(enabled) = enabled {
+      asset_config.enabled = enabled;
+    }
+
+    if let Some(collateral_haircut_bps) = collateral_haircut_bps {
+      asset_config.collateral_haircut_bps = collateral_haircut_bps;
+    }
+
+    if let Some(liquidation_priority) = liquidation_priority {
+      asset_config.liquidation_priority = liquidation_priority;
+    }
+
+    AssetConfig::validate_parameters(asset_config.max_price_age, asset_config.collateral_haircut_bps)
+  }
+
+  pub fn emit_event_edit_asset_config(&self, label: String) -> Result<()> {
+    emit!(EditAssetConfigEvent {
+      mint: self.asset_config.mint,
+      price_feed_id: self.asset_config.price_feed_id,
+      max_price_age: self.asset_config.max_price_age,
+      enabled: self.asset_config.enabled,
+      collateral_haircut_bps: self.asset_config.collateral_haircut_bps,
+      liquidation_priority: self.asset_config.liquidation_priority,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{GlobalConfigError, ENSO_SEED, GLOBAL_CONFIG_SEED},
+  AuctionParameters, EditGlobalConfigEvent, GlobalConfig, BPS_DENOMINATOR
+};
+
+#[derive(Accounts)]
+pub struct EditGlobalConfig<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    mut,
+    has_one = admin @ GlobalConfigError::InvalidAdmin,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+}
+
+impl<'info> EditGlobalConfig<'info> {
+  pub fn edit_global_config(
+    &mut self,
+    admin: Option<Pubkey>,
+    hot_wallet: Option<Pubkey>,
+    setting_change_delay: Option<i64>,
+    withdraw_request_cooldown: Option<i64>,
+    auction_parameters: Option<AuctionParameters>,
+    liquidation_slippage_bps: Option<u64>
+  ) -> Result<()> {
+    let global_config = &mut self.global_config;
+    if let Some(admin) = admin {
+      global_config.admin = admin;
+    }
+
+    if let Some(hot_wallet) = hot_wallet {
+      global_config.hot_wallet = hot_wallet;
+    }
+
+    if let Some(setting_change_delay) = setting_change_delay {
+      if setting_change_delay < 0 {
+        return err!(GlobalConfigError::NegativeSettingChangeDelay);
+      }
+
+      global_config.setting_change_delay = setting_change_delay;
+    }
+
+    if let Some(withdraw_request_cooldown) = withdraw_request_cooldown {
+      if withdraw_request_cooldown < 0 {
+        return err!(GlobalConfigError::NegativeWithdrawRequestCooldown);
+      }
+
+      global_config.withdraw_request_cooldown = withdraw_request_cooldown;
+    }
+
+    if let Some(auction_parameters) = auction_parameters {
+      auction_parameters.validate()?;
+
+      global_config.auction_parameters = auction_parameters;
+    }
+
+    if let Some(liquidation_slippage_bps) = liquidation_slippage_bps {
+      if liquidation_slippage_bps > BPS_DENOMINATOR {
+        return err!(GlobalConfigError::SlippageTooHigh);
+      }
+
+      global_config.liquidation_slippage_bps = liquidation_slippage_bps;
+    }
+
+    Ok(())
+  }
+
+  pub fn emit_event_edit_global_config(&self, label: String) -> Result<()> {
+    emit!(EditGlobalConfigEvent {
+      admin: self.global_config.admin,
+      hot_wallet: self.global_config.hot_wallet,
+      setting_change_delay: self.global_config.setting_change_delay,
+      withdraw_request_cooldown: self.global_config.withdraw_request_cooldown,
+      auction_parameters: self.global_config.auction_parameters,
+      liquidation_slippage_bps: self.global_config.liquidation_slippage_bps,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn edit_accounts(signer_key: Pubkey, admin: Pubkey) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer(signer_key),
+      state(global_config_key(), &global_config(admin, Pubkey::new_unique())),
+    ]
+  }
+
+  #[test]
+  fn admin_rotates_hot_wallet_and_admin() {
+    let admin = Pubkey::new_unique();
+    let new_admin = Pubkey::new_unique();
+    let new_hot_wallet = Pubkey::new_unique();
+    let (mut context, _, _) = load::<EditGlobalConfig, _>(edit_accounts(admin, admin), &[]).unwrap();
+
+    context.edit_global_config(Some(new_admin), Some(new_hot_wallet), None, None, None, None).unwrap();
+    context.exit(&crate::ID).unwrap();
+
+    let global_config = read_state::<GlobalConfig>(&context.global_config.to_account_info());
+    assert_eq!(global_config.admin, new_admin);
+    assert_eq!(global_config.hot_wallet, new_hot_wallet);
+  }
+
+  #[test]
+  fn rejects_signer_that_is_not_admin() {
+    let result = load::<EditGlobalConfig, _>(edit_accounts(Pubkey::new_unique(), Pubkey::new_unique()), &[]);
+
+    assert_error(result.map(|_| ()), GlobalConfigError::InvalidAdmin);
+  }
+
+  #[test]
+  fn rejects_negative_cooldown() {
+    let admin = Pubkey::new_unique();
+    let (mut context, _, _) = load::<EditGlobalConfig, _>(edit_accounts(admin, admin), &[]).unwrap();
+
+    assert_error(
+      context.edit_global_config(None, None, None, Some(-1), None, None),
+      GlobalConfigError::NegativeWithdrawRequestCooldown
+    );
+  }
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::{EditLendOfferEvent, LendOfferAccount, LendOfferError, LendOfferStatus, common::{ENSO_SEED, LEND_OFFER_ACCOUNT_SEED}};
+use crate::{EditLendOfferEvent, LendOfferAccount, LendOfferError, LendOfferStatus, OrderBook, common::{ENSO_SEED, LEND_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED}};
 
 #[derive(Accounts)]
 #[instruction(offer_id: String)]
@@ -20,16 +20,31 @@ pub struct EditLendOffer<'info> {
     bump = lend_offer.bump
   )]
   pub lend_offer: Account<'info, LendOfferAccount>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      lend_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
 }
 
 impl<'info> EditLendOffer<'info> {
-    pub fn edit_lend_offer(&mut self, interest: f64) -> Result<()> {
-      if interest <= (0 as f64) {
+    pub fn edit_lend_offer(&mut self, interest_bps: u64) -> Result<()> {
+      if interest_bps == 0 {
         return err!(LendOfferError::InterestGreaterThanZero);
       }
 
       let lend_offer = &mut self.lend_offer;
-      lend_offer.interest = interest;
+      lend_offer.interest_bps = interest_bps;
+
+      let lend_offer = self.lend_offer.key();
+      OrderBook::update_if_initialized(&self.order_book, |order_book| order_book.reprice(&lend_offer, interest_bps))?;
 
       Ok(())
     }
@@ -37,8 +52,8 @@ impl<'info> EditLendOffer<'info> {
     pub fn emit_event_edit_lend_offer(&mut self, label: String) -> Result<()> {
       emit!(EditLendOfferEvent {
         lender: self.lender.key(),
-        interest: self.lend_offer.interest,
-        lender_fee_percent: self.lend_offer.lender_fee_percent,
+        interest_bps: self.lend_offer.interest_bps,
+        lender_fee_bps: self.lend_offer.lender_fee_bps,
         amount: self.lend_offer.amount,
         duration: self.lend_offer.duration,
         offer_id: self.lend_offer.offer_id.clone(),
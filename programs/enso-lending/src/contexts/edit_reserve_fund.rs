@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{RoleError, ENSO_SEED, RESERVE_FUND_SEED, ROLE_ACCOUNT_SEED},
+  EditReserveFundEvent, ReserveFund, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+pub struct EditReserveFund<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_FUND_SEED.as_ref(),
+      reserve_fund.lend_mint.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = reserve_fund.bump
+  )]
+  pub reserve_fund: Account<'info, ReserveFund>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&authority.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+}
+
+impl<'info> EditReserveFund<'info> {
+  pub fn edit_reserve_fund(&mut self, fee_share_bps: u64) -> Result<()> {
+    ReserveFund::validate_fee_share(fee_share_bps)?;
+    self.reserve_fund.fee_share_bps = fee_share_bps;
+
+    Ok(())
+  }
+
+  pub fn emit_event_edit_reserve_fund(&self, label: String) -> Result<()> {
+    emit!(EditReserveFundEvent {
+      lend_mint: self.reserve_fund.lend_mint,
+      fee_share_bps: self.reserve_fund.fee_share_bps,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -1,19 +1,21 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_interface::Mint;
 
-use crate::{SettingAccount, EditSettingAccountEvent, SettingAccountError, common::{ENSO_SEED, SETTING_ACCOUNT_SEED}};
+use crate::{
+  validate_mint_extensions, GlobalConfig, PendingSettingChange, RiskParameters, SettingAccount, SettingChangeQueuedEvent, SettingAccountError,
+  common::{BPS_DENOMINATOR, ENSO_SEED, GLOBAL_CONFIG_SEED, PENDING_SETTING_CHANGE_SEED, SETTING_ACCOUNT_SEED}
+};
 
 #[derive(Accounts)]
-#[instruction(tier_id: String, amount: Option<u64>, duration: Option<u64>)]
+#[instruction(tier_id: String, amount: Option<u64>, min_amount: Option<u64>, duration: Option<u64>)]
 pub struct EditSettingAccount<'info> {
   #[account(mut)]
   pub owner: Signer<'info>,
   /// CHECK: This is the account used to make a seeds to create ata account for transfer asset from lender to how wallet
   pub receiver: AccountInfo<'info>,
-  pub lend_mint_asset: Account<'info, Mint>,
-  pub collateral_mint_asset: Account<'info, Mint>,
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
-    mut,
     has_one = owner,
     constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
     seeds = [
@@ -22,40 +24,75 @@ pub struct EditSettingAccount<'info> {
       tier_id.as_bytes(), 
       crate::ID.key().as_ref(), 
     ],
-    bump
+    bump = setting_account.bump
   )]
   pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    init,
+    payer = owner,
+    space = PendingSettingChange::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PENDING_SETTING_CHANGE_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub pending_setting_change: Account<'info, PendingSettingChange>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> EditSettingAccount<'info> {
+  #[allow(clippy::too_many_arguments)]
   pub fn edit_setting_account(
     &mut self,
+    bumps: &EditSettingAccountBumps,
     amount: Option<u64>,
+    min_amount: Option<u64>,
     duration: Option<u64>,
-    lender_fee_percent: Option<f64>,
-    borrower_fee_percent: Option<f64>
+    lender_fee_bps: Option<u64>,
+    borrower_fee_bps: Option<u64>,
+    risk_parameters: Option<RiskParameters>
   ) -> Result<()>  {
-    let setting_account = &mut self.setting_account;
-    if let Some(amount) = amount {
-      setting_account.amount = amount;
+    if lender_fee_bps.unwrap_or_default() > BPS_DENOMINATOR || borrower_fee_bps.unwrap_or_default() > BPS_DENOMINATOR {
+      return err!(SettingAccountError::FeeTooHigh);
     }
 
-    if let Some(duration) = duration {
-      setting_account.duration = duration;
+    if let Some(risk_parameters) = &risk_parameters {
+      risk_parameters.validate()?;
     }
 
-    if let Some(lender_fee_percent) = lender_fee_percent {
-      setting_account.lender_fee_percent = lender_fee_percent;
-    }
+    validate_mint_extensions(&self.lend_mint_asset.to_account_info())?;
+    validate_mint_extensions(&self.collateral_mint_asset.to_account_info())?;
 
-    if let Some(borrower_fee_percent) = borrower_fee_percent {
-      setting_account.borrower_fee_percent = borrower_fee_percent;
-    }
+    let queued_at = Clock::get()?.unix_timestamp;
+
+    self.pending_setting_change.set_inner(PendingSettingChange {
+      tier_id: self.setting_account.tier_id.clone(),
+      amount,
+      min_amount,
+      duration,
+      lender_fee_bps,
+      borrower_fee_bps,
+      risk_parameters,
+      receiver: self.receiver.key(),
+      lend_mint_asset: self.lend_mint_asset.key(),
+      collateral_mint_asset: self.collateral_mint_asset.key(),
+      queued_at,
+      effective_at: queued_at + self.global_config.setting_change_delay,
+      bump: bumps.pending_setting_change,
+    });
 
-    setting_account.receiver = self.receiver.key();
-    setting_account.lend_mint_asset = self.lend_mint_asset.key();
-    setting_account.collateral_mint_asset = self.collateral_mint_asset.key();
     Ok(())
   }
 
@@ -63,18 +100,73 @@ impl<'info> EditSettingAccount<'info> {
     &mut self,
     label: String,
   ) -> Result<()> {
-    emit!(EditSettingAccountEvent {
-      receiver: self.receiver.key(),
-      lend_mint_asset: self.lend_mint_asset.key(),
-      collateral_mint_asset: self.collateral_mint_asset.key(),
-      tier_id: self.setting_account.tier_id.clone(),
-      amount: self.setting_account.amount,
-      duration: self.setting_account.duration,
-      lender_fee_percent: self.setting_account.lender_fee_percent,
+    emit!(SettingChangeQueuedEvent {
+      tier_id: self.pending_setting_change.tier_id.clone(),
+      amount: self.pending_setting_change.amount,
+      min_amount: self.pending_setting_change.min_amount,
+      duration: self.pending_setting_change.duration,
+      lender_fee_bps: self.pending_setting_change.lender_fee_bps,
+      borrower_fee_bps: self.pending_setting_change.borrower_fee_bps,
+      risk_parameters: self.pending_setting_change.risk_parameters,
+      receiver: self.pending_setting_change.receiver,
+      lend_mint_asset: self.pending_setting_change.lend_mint_asset,
+      collateral_mint_asset: self.pending_setting_change.collateral_mint_asset,
+      effective_at: self.pending_setting_change.effective_at,
     });
 
     msg!(&label.clone());
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  fn edit_accounts(setting_change_delay: i64) -> Vec<AccountInfo<'static>> {
+    let owner = Pubkey::new_unique();
+    let lend_mint = Pubkey::new_unique();
+    let mut global_config = global_config(Pubkey::new_unique(), Pubkey::new_unique());
+    global_config.setting_change_delay = setting_change_delay;
+
+    vec![
+      signer(owner),
+      wallet(Pubkey::new_unique()),
+      mint(lend_mint, spl_token::ID),
+      mint(crate::NATIVE_MINT, spl_token::ID),
+      state(setting_account_key(TIER_ID), &setting_account(owner, lend_mint, crate::NATIVE_MINT)),
+      empty(pda(&[ENSO_SEED, PENDING_SETTING_CHANGE_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()])),
+      state(global_config_key(), &global_config),
+      program(System::id()),
+    ]
+  }
+
+  #[test]
+  fn queues_change_behind_setting_change_delay() {
+    let ix_data = args((TIER_ID.to_string(), Some(2_000_000_000u64), None::<u64>, None::<u64>));
+    let (mut context, bumps, _) = load::<EditSettingAccount, _>(edit_accounts(3_600), &ix_data).unwrap();
+
+    context.edit_setting_account(&bumps, Some(2_000_000_000), None, None, Some(100), None, None).unwrap();
+
+    assert_eq!(context.pending_setting_change.amount, Some(2_000_000_000));
+    assert_eq!(context.pending_setting_change.lender_fee_bps, Some(100));
+    assert_eq!(context.pending_setting_change.effective_at, now() + 3_600);
+    assert_eq!(context.setting_account.amount, 1_000_000_000);
+    assert_eq!(context.setting_account.lender_fee_bps, 500);
+  }
+
+  #[test]
+  fn rejects_fee_above_denominator() {
+    let ix_data = args((TIER_ID.to_string(), None::<u64>, None::<u64>, None::<u64>));
+    let (mut context, bumps, _) = load::<EditSettingAccount, _>(edit_accounts(0), &ix_data).unwrap();
+
+    assert_error(
+      context.edit_setting_account(&bumps, None, None, None, None, Some(BPS_DENOMINATOR + 1), None),
+      SettingAccountError::FeeTooHigh
+    );
+  }
+}
@@ -0,0 +1,399 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{ASSET_CONFIG_SEED, BORROW_REQUEST_ESCROW_SEED, BORROW_REQUEST_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, calculate_amount_with_transfer_fee, calculate_ltv_bps, calculate_received_amount, validate_vault_balance, AssetConfig, AssetConfigError, BorrowRequestAccount, BorrowRequestError, BorrowRequestFilledEvent, BorrowRequestStatus, GlobalConfig, LoanOfferAccount, LoanOfferError, LoanOfferStatus, PauseAction, PauseError, Rounding, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(request_id: String)]
+pub struct FillBorrowRequest<'info> {
+  #[account(mut)]
+  pub lender: Signer<'info>,
+  /// CHECK: This account receives back the rent of the request escrow
+  #[account(
+    mut,
+    constraint = borrower.key() == borrow_request.borrower @ BorrowRequestError::InvalidBorrower
+  )]
+  pub borrower: AccountInfo<'info>,
+  #[account(
+    constraint = lend_mint_asset.key() == borrow_request.lend_mint_token @ BorrowRequestError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    constraint = collateral_mint_asset.key() == borrow_request.collateral_mint_token @ BorrowRequestError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_ata_asset: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_ata_asset: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    constraint = borrow_request.status == BorrowRequestStatus::Created @ BorrowRequestError::InvalidRequestStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_SEED.as_ref(),
+      borrower.key().as_ref(),
+      request_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = borrow_request.bump
+  )]
+  pub borrow_request: Box<Account<'info, BorrowRequestAccount>>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      BORROW_REQUEST_ESCROW_SEED.as_ref(),
+      borrow_request.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrow_request_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    init,
+    payer = lender,
+    space = LoanOfferAccount::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      request_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub loan_offer: Box<Account<'info, LoanOfferAccount>>,
+  #[account(
+    init,
+    payer = lender,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+  pub lend_price_feed_account: Box<Account<'info, PriceUpdateV2>>,
+  pub collateral_price_feed_account: Box<Account<'info, PriceUpdateV2>>,
+  #[account(
+    constraint = lend_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      lend_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Box<Account<'info, AssetConfig>>,
+  #[account(
+    constraint = collateral_asset_config.enabled @ AssetConfigError::AssetDisabled,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      collateral_mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Box<Account<'info, AssetConfig>>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      borrow_request.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Box<Account<'info, SettingAccount>>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::NewLoans) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Box<Account<'info, GlobalConfig>>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillBorrowRequest<'info> {
+  /// Funds the borrower straight from the lender, so the resulting loan skips `Matched` and
+  /// starts as `FundTransferred`.
+  pub fn fill_borrow_request(&mut self, bumps: &FillBorrowRequestBumps, interest_bps: u64) -> Result<()> {
+    self.borrow_request.validate_interest(interest_bps)?;
+    self.validate_health()?;
+
+    self.transfer_lend_asset_to_borrower()?;
+
+    let received_collateral_amount = calculate_received_amount(
+      &self.collateral_mint_asset.to_account_info(),
+      self.borrow_request.collateral_amount
+    )?;
+    self.move_collateral_to_vault(self.borrow_request_escrow.amount)?;
+
+    let borrow_request = &self.borrow_request;
+    self.loan_offer.set_inner(LoanOfferAccount {
+      tier_id: borrow_request.tier_id.clone(),
+      borrow_amount: borrow_request.amount,
+      borrower: borrow_request.borrower,
+      borrower_fee_bps: self.setting_account.borrower_fee_bps,
+      bump: bumps.loan_offer,
+      collateral_amount: received_collateral_amount,
+      request_withdraw_amount: None,
+      request_withdraw_at: None,
+      request_withdraw_approved: false,
+      request_withdraw_mint: None,
+      collateral_positions: Vec::new(),
+      collateral_mint_token: borrow_request.collateral_mint_token,
+      duration: borrow_request.duration,
+      interest_bps,
+      lend_mint_token: borrow_request.lend_mint_token,
+      lend_offer_id: borrow_request.request_id.clone(),
+      lender: self.lender.key(),
+      lender_fee_bps: self.setting_account.lender_fee_bps,
+      offer_id: borrow_request.request_id.clone(),
+      started_at: Clock::get()?.unix_timestamp,
+      status: LoanOfferStatus::FundTransferred,
+      liquidating_at: None,
+      liquidating_price: None,
+      liquidated_tx: None,
+      liquidated_price: None,
+      deficit_amount: 0,
+    });
+
+    self.borrow_request.status = BorrowRequestStatus::Filled;
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)
+  }
+
+  fn validate_health(&self) -> Result<()> {
+    let convert_collateral_amount_to_usd = self.collateral_asset_config.collateral_value(
+      &self.collateral_price_feed_account,
+      self.borrow_request.collateral_amount
+    )?;
+    let convert_lend_amount_to_usd = self.lend_asset_config.usd_value(
+      &self.lend_price_feed_account,
+      self.borrow_request.amount,
+      Rounding::Up
+    )?;
+    let ltv_bps = calculate_ltv_bps(convert_lend_amount_to_usd, convert_collateral_amount_to_usd)?;
+
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
+      return err!(LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid);
+    }
+
+    Ok(())
+  }
+
+  fn transfer_lend_asset_to_borrower(&self) -> Result<()> {
+    let transfer_amount = calculate_amount_with_transfer_fee(
+      &self.lend_mint_asset.to_account_info(),
+      self.borrow_request.amount
+    )?;
+
+    if self.lender_ata_asset.amount < transfer_amount {
+      return err!(BorrowRequestError::NotEnoughAmount);
+    }
+
+    let cpi_accounts = TransferChecked {
+      from: self.lender_ata_asset.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.borrower_ata_asset.to_account_info(),
+      authority: self.lender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, transfer_amount, self.lend_mint_asset.decimals)
+  }
+
+  fn move_collateral_to_vault(&self, collateral_amount: u64) -> Result<()> {
+    let borrower = self.borrow_request.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      BORROW_REQUEST_SEED,
+      borrower.as_ref(),
+      self.borrow_request.request_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.borrow_request.bump],
+    ]];
+
+    let transfer_accounts = TransferChecked {
+      from: self.borrow_request_escrow.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.collateral_vault.to_account_info(),
+      authority: self.borrow_request.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+      collateral_amount,
+      self.collateral_mint_asset.decimals,
+    )?;
+
+    let close_accounts = CloseAccount {
+      account: self.borrow_request_escrow.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.borrow_request.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  pub fn emit_event_fill_borrow_request(&self, label: String) -> Result<()> {
+    emit!(BorrowRequestFilledEvent {
+      request_id: self.borrow_request.request_id.clone(),
+      tier_id: self.borrow_request.tier_id.clone(),
+      borrower: self.borrow_request.borrower,
+      lender: self.lender.key(),
+      amount: self.loan_offer.borrow_amount,
+      collateral_amount: self.loan_offer.collateral_amount,
+      interest_bps: self.loan_offer.interest_bps,
+      duration: self.loan_offer.duration,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const REQUEST_ID: &str = "request";
+  const LENDER_BALANCE: u64 = 150_000_000;
+
+  struct Fixture {
+    lender: Pubkey,
+    lender_balance: u64,
+    borrow_request: BorrowRequestAccount,
+  }
+
+  impl Fixture {
+    /// Request for 100 lend tokens against 250 collateral tokens, both priced at 1 USD.
+    fn new() -> Self {
+      Self {
+        lender: Pubkey::new_unique(),
+        lender_balance: LENDER_BALANCE,
+        borrow_request: borrow_request(Pubkey::new_unique(), REQUEST_ID, Pubkey::new_unique(), Pubkey::new_unique()),
+      }
+    }
+
+    fn load(&self) -> (FillBorrowRequest<'static>, FillBorrowRequestBumps) {
+      let borrower = self.borrow_request.borrower;
+      let lend_mint = self.borrow_request.lend_mint_token;
+      let collateral_mint = self.borrow_request.collateral_mint_token;
+      let borrow_request_key = borrow_request_key(&borrower, REQUEST_ID);
+      let loan_offer_key = loan_offer_key(&borrower, REQUEST_ID);
+
+      let accounts = vec![
+        signer(self.lender),
+        wallet(borrower),
+        mint(lend_mint, spl_token::ID),
+        mint(collateral_mint, spl_token::ID),
+        token_account(ata(&self.lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.lender, self.lender_balance),
+        token_account(ata(&borrower, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, borrower, 0),
+        state(borrow_request_key, &self.borrow_request),
+        token_account(
+          borrow_request_escrow_key(&borrow_request_key),
+          spl_token::ID,
+          collateral_mint,
+          borrow_request_key,
+          self.borrow_request.collateral_amount
+        ),
+        empty(loan_offer_key),
+        empty(pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()])),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&collateral_mint), &asset_config(collateral_mint, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), lend_mint, collateral_mint)),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        program(spl_token::ID),
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+
+      load::<FillBorrowRequest, _>(accounts, &args(REQUEST_ID.to_string())).map(|(context, bumps, _)| (context, bumps)).unwrap()
+    }
+
+    fn fill(&self, interest_bps: u64) -> Result<FillBorrowRequest<'static>> {
+      let (mut context, bumps) = self.load();
+      context.fill_borrow_request(&bumps, interest_bps)?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn funds_borrower_and_locks_collateral_in_loan_vault() {
+    let fixture = Fixture::new();
+    let context = fixture.fill(1_200).unwrap();
+
+    assert_eq!(token_amount(&context.borrower_ata_asset.to_account_info()), 100_000_000);
+    assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), LENDER_BALANCE - 100_000_000);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), 250_000_000);
+    assert_eq!(context.collateral_vault.owner, context.loan_offer.key());
+    assert_eq!(context.borrow_request_escrow.to_account_info().lamports(), 0);
+    assert_eq!(context.borrow_request.status, BorrowRequestStatus::Filled);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::FundTransferred);
+    assert_eq!(context.loan_offer.lender, fixture.lender);
+    assert_eq!(context.loan_offer.borrow_amount, 100_000_000);
+    assert_eq!(context.loan_offer.collateral_amount, 250_000_000);
+    assert_eq!(context.loan_offer.interest_bps, 1_200);
+  }
+
+  #[test]
+  fn rejects_interest_above_borrower_max() {
+    let fixture = Fixture::new();
+
+    assert_error(fixture.fill(1_501).map(|_| ()), BorrowRequestError::InterestExceedsMax);
+  }
+
+  #[test]
+  fn rejects_fill_above_initial_ltv() {
+    let mut fixture = Fixture::new();
+    // 100 USD against 125 USD of collateral is an 80% LTV.
+    fixture.borrow_request.collateral_amount = 125_000_000;
+
+    assert_error(fixture.fill(1_200).map(|_| ()), LoanOfferError::CanNotTakeALoanBecauseHealthRatioIsNotValid);
+  }
+
+  #[test]
+  fn rejects_lender_without_enough_funds() {
+    let mut fixture = Fixture::new();
+    fixture.lender_balance = 99_999_999;
+
+    assert_error(fixture.fill(1_200).map(|_| ()), BorrowRequestError::NotEnoughAmount);
+  }
+}
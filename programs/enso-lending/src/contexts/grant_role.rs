@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{GlobalConfigError, RoleError, ENSO_SEED, GLOBAL_CONFIG_SEED, MAX_ROLE_MEMBERS, ROLE_ACCOUNT_SEED},
+  GlobalConfig, Role, RoleAccount, RoleGrantedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(role: Role)]
+pub struct GrantRole<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    has_one = admin @ GlobalConfigError::InvalidAdmin,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    init_if_needed,
+    payer = admin,
+    space = RoleAccount::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[role as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> GrantRole<'info> {
+  pub fn grant_role(&mut self, bumps: &GrantRoleBumps, role: Role, member: Pubkey) -> Result<()> {
+    let role_account = &mut self.role_account;
+    role_account.role = role;
+    role_account.bump = bumps.role_account;
+
+    if role_account.is_member(&member) {
+      return err!(RoleError::MemberAlreadyGranted);
+    }
+
+    if role_account.members.len() >= MAX_ROLE_MEMBERS {
+      return err!(RoleError::RoleMemberLimit);
+    }
+
+    role_account.members.push(member);
+
+    Ok(())
+  }
+
+  pub fn emit_event_grant_role(&self, label: String, member: Pubkey) -> Result<()> {
+    emit!(RoleGrantedEvent {
+      role: self.role_account.role,
+      member,
+      admin: self.admin.key(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn grant_accounts(signer_key: Pubkey, admin: Pubkey, role_account_info: AccountInfo<'static>) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer(signer_key),
+      state(global_config_key(), &global_config(admin, Pubkey::new_unique())),
+      role_account_info,
+      program(System::id()),
+    ]
+  }
+
+  #[test]
+  fn admin_grants_role_to_new_member() {
+    let admin = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+    let accounts = grant_accounts(admin, admin, empty(role_account_key(Role::Funder)));
+    let (mut context, bumps, _) = load::<GrantRole, _>(accounts, &args(Role::Funder)).unwrap();
+
+    context.grant_role(&bumps, Role::Funder, member).unwrap();
+    context.exit(&crate::ID).unwrap();
+
+    let role_account = read_state::<RoleAccount>(&context.role_account.to_account_info());
+    assert_eq!(role_account.role, Role::Funder);
+    assert_eq!(role_account.members, vec![member]);
+  }
+
+  #[test]
+  fn rejects_member_already_granted() {
+    let admin = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+    let role_account_info = state(role_account_key(Role::Funder), &role_account(Role::Funder, vec![member]));
+    let (mut context, bumps, _) = load::<GrantRole, _>(grant_accounts(admin, admin, role_account_info), &args(Role::Funder)).unwrap();
+
+    assert_error(context.grant_role(&bumps, Role::Funder, member), RoleError::MemberAlreadyGranted);
+  }
+
+  #[test]
+  fn rejects_full_role() {
+    let admin = Pubkey::new_unique();
+    let members = (0..MAX_ROLE_MEMBERS).map(|_| Pubkey::new_unique()).collect();
+    let role_account_info = state(role_account_key(Role::Funder), &role_account(Role::Funder, members));
+    let (mut context, bumps, _) = load::<GrantRole, _>(grant_accounts(admin, admin, role_account_info), &args(Role::Funder)).unwrap();
+
+    assert_error(context.grant_role(&bumps, Role::Funder, Pubkey::new_unique()), RoleError::RoleMemberLimit);
+  }
+
+  #[test]
+  fn only_admin_grants_roles() {
+    let accounts = grant_accounts(Pubkey::new_unique(), Pubkey::new_unique(), empty(role_account_key(Role::Funder)));
+
+    assert_error(load::<GrantRole, _>(accounts, &args(Role::Funder)).map(|_| ()), GlobalConfigError::InvalidAdmin);
+  }
+}
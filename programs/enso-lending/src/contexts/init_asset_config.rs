@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::{
+  common::{AssetConfigError, RoleError, ASSET_CONFIG_SEED, ENSO_SEED, ROLE_ACCOUNT_SEED},
+  validate_mint_extensions, AssetConfig, InitAssetConfigEvent, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+pub struct InitAssetConfig<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    init,
+    payer = authority,
+    space = AssetConfig::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&authority.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitAssetConfig<'info> {
+  pub fn init_asset_config(
+    &mut self,
+    bumps: &InitAssetConfigBumps,
+    price_feed_id: String,
+    max_price_age: u64,
+    collateral_haircut_bps: u64,
+    liquidation_priority: u8
+  ) -> Result<()> {
+    AssetConfig::validate_parameters(max_price_age, collateral_haircut_bps)?;
+    validate_mint_extensions(&self.mint.to_account_info())?;
+
+    let price_feed_id = get_feed_id_from_hex(&price_feed_id)
+      .map_err(|_| error!(AssetConfigError::InvalidPriceFeedId))?;
+
+    self.asset_config.set_inner(AssetConfig {
+      mint: self.mint.key(),
+      price_feed_id,
+      max_price_age,
+      decimals: self.mint.decimals,
+      enabled: true,
+      collateral_haircut_bps,
+      liquidation_priority,
+      bump: bumps.asset_config,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_init_asset_config(&self, label: String) -> Result<()> {
+    emit!(InitAssetConfigEvent {
+      mint: self.asset_config.mint,
+      price_feed_id: self.asset_config.price_feed_id,
+      max_price_age: self.asset_config.max_price_age,
+      decimals: self.asset_config.decimals,
+      collateral_haircut_bps: self.asset_config.collateral_haircut_bps,
+      liquidation_priority: self.asset_config.liquidation_priority,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
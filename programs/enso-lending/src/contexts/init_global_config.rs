@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{GlobalConfigError, ENSO_SEED, GLOBAL_CONFIG_SEED},
+  program::EnsoLending, AuctionParameters, GlobalConfig, InitGlobalConfigEvent, PauseFlags, BPS_DENOMINATOR
+};
+
+#[derive(Accounts)]
+pub struct InitGlobalConfig<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    init,
+    payer = admin,
+    space = GlobalConfig::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    constraint = program.programdata_address()? == Some(program_data.key()) @ GlobalConfigError::InvalidProgramData
+  )]
+  pub program: Program<'info, EnsoLending>,
+  #[account(
+    constraint = program_data.upgrade_authority_address == Some(admin.key()) @ GlobalConfigError::InvalidAdmin
+  )]
+  pub program_data: Account<'info, ProgramData>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitGlobalConfig<'info> {
+  pub fn init_global_config(
+    &mut self,
+    bumps: &InitGlobalConfigBumps,
+    hot_wallet: Pubkey,
+    setting_change_delay: i64,
+    withdraw_request_cooldown: i64,
+    auction_parameters: AuctionParameters,
+    liquidation_slippage_bps: u64
+  ) -> Result<()> {
+    if setting_change_delay < 0 {
+      return err!(GlobalConfigError::NegativeSettingChangeDelay);
+    }
+
+    if withdraw_request_cooldown < 0 {
+      return err!(GlobalConfigError::NegativeWithdrawRequestCooldown);
+    }
+
+    auction_parameters.validate()?;
+
+    if liquidation_slippage_bps > BPS_DENOMINATOR {
+      return err!(GlobalConfigError::SlippageTooHigh);
+    }
+
+    self.global_config.set_inner(GlobalConfig {
+      admin: self.admin.key(),
+      hot_wallet,
+      pause_flags: PauseFlags::default(),
+      setting_change_delay,
+      withdraw_request_cooldown,
+      auction_parameters,
+      liquidation_slippage_bps,
+      bump: bumps.global_config,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_init_global_config(&self, label: String) -> Result<()> {
+    emit!(InitGlobalConfigEvent {
+      admin: self.global_config.admin,
+      hot_wallet: self.global_config.hot_wallet,
+      setting_change_delay: self.global_config.setting_change_delay,
+      withdraw_request_cooldown: self.global_config.withdraw_request_cooldown,
+      auction_parameters: self.global_config.auction_parameters,
+      liquidation_slippage_bps: self.global_config.liquidation_slippage_bps,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  fn init_accounts(admin: Pubkey, upgrade_authority: Pubkey) -> Vec<AccountInfo<'static>> {
+    let program_data = Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::ID).0;
+
+    let mut program_account_data = 2u32.to_le_bytes().to_vec();
+    program_account_data.extend_from_slice(program_data.as_ref());
+    let mut program_data_account_data = 3u32.to_le_bytes().to_vec();
+    program_data_account_data.extend_from_slice(&0u64.to_le_bytes());
+    program_data_account_data.push(1);
+    program_data_account_data.extend_from_slice(upgrade_authority.as_ref());
+
+    vec![
+      signer(admin),
+      empty(global_config_key()),
+      account(crate::ID, bpf_loader_upgradeable::ID, 1, program_account_data, false, true),
+      account(program_data, bpf_loader_upgradeable::ID, 1, program_data_account_data, false, false),
+      program(System::id()),
+    ]
+  }
+
+  fn auction_parameters() -> AuctionParameters {
+    AuctionParameters {
+      start_premium_bps: 1_000,
+      floor_discount_bps: 2_000,
+      duration: 3_600,
+    }
+  }
+
+  #[test]
+  fn upgrade_authority_sets_up_global_config() {
+    let admin = Pubkey::new_unique();
+    let hot_wallet = Pubkey::new_unique();
+    let (mut context, bumps, _) = load::<InitGlobalConfig, _>(init_accounts(admin, admin), &[]).unwrap();
+
+    context.init_global_config(&bumps, hot_wallet, 60, 120, auction_parameters(), 100).unwrap();
+    context.exit(&crate::ID).unwrap();
+
+    let global_config = read_state::<GlobalConfig>(&context.global_config.to_account_info());
+    assert_eq!(global_config.admin, admin);
+    assert_eq!(global_config.hot_wallet, hot_wallet);
+    assert_eq!(global_config.setting_change_delay, 60);
+    assert_eq!(global_config.withdraw_request_cooldown, 120);
+    assert_eq!(global_config.pause_flags, PauseFlags::default());
+  }
+
+  #[test]
+  fn only_upgrade_authority_sets_up_global_config() {
+    let result = load::<InitGlobalConfig, _>(init_accounts(Pubkey::new_unique(), Pubkey::new_unique()), &[]);
+
+    assert_error(result.map(|_| ()), GlobalConfigError::InvalidAdmin);
+  }
+
+  #[test]
+  fn rejects_invalid_parameters() {
+    let admin = Pubkey::new_unique();
+    let (mut context, bumps, _) = load::<InitGlobalConfig, _>(init_accounts(admin, admin), &[]).unwrap();
+
+    assert_error(
+      context.init_global_config(&bumps, admin, -1, 0, auction_parameters(), 100),
+      GlobalConfigError::NegativeSettingChangeDelay
+    );
+    assert_error(
+      context.init_global_config(&bumps, admin, 0, 0, auction_parameters(), BPS_DENOMINATOR + 1),
+      GlobalConfigError::SlippageTooHigh
+    );
+  }
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{RoleError, ENSO_SEED, ORDER_BOOK_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED},
+  InitOrderBookEvent, OrderBook, Role, RoleAccount, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct InitOrderBook<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    init,
+    payer = authority,
+    space = OrderBook::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: Account<'info, OrderBook>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&authority.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitOrderBook<'info> {
+  pub fn init_order_book(&mut self, bumps: &InitOrderBookBumps) -> Result<()> {
+    self.order_book.set_inner(OrderBook {
+      tier_id: self.setting_account.tier_id.clone(),
+      min_amount: self.setting_account.min_amount,
+      entries: Vec::new(),
+      bump: bumps.order_book,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_init_order_book(&self, label: String) -> Result<()> {
+    emit!(InitOrderBookEvent {
+      tier_id: self.order_book.tier_id.clone(),
+      min_amount: self.order_book.min_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
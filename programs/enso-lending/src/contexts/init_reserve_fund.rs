@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+  common::{RoleError, ENSO_SEED, RESERVE_FUND_SEED, RESERVE_VAULT_SEED, ROLE_ACCOUNT_SEED},
+  InitReserveFundEvent, ReserveFund, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+pub struct InitReserveFund<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  pub lend_mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    init,
+    payer = authority,
+    space = ReserveFund::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_FUND_SEED.as_ref(),
+      lend_mint.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub reserve_fund: Account<'info, ReserveFund>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = lend_mint,
+    token::authority = reserve_fund,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_VAULT_SEED.as_ref(),
+      lend_mint.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub reserve_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&authority.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitReserveFund<'info> {
+  pub fn init_reserve_fund(&mut self, bumps: &InitReserveFundBumps, fee_share_bps: u64) -> Result<()> {
+    ReserveFund::validate_fee_share(fee_share_bps)?;
+
+    self.reserve_fund.set_inner(ReserveFund {
+      lend_mint: self.lend_mint.key(),
+      fee_share_bps,
+      balance: 0,
+      total_inflow: 0,
+      total_payout: 0,
+      bump: bumps.reserve_fund,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_init_reserve_fund(&self, label: String) -> Result<()> {
+    emit!(InitReserveFundEvent {
+      lend_mint: self.reserve_fund.lend_mint,
+      fee_share_bps: self.reserve_fund.fee_share_bps,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -1,19 +1,17 @@
-use std::str::FromStr;
-
 pub use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_interface::Mint;
 
-use crate::{InitSettingAccountEvent, SettingAccount, common::{constant::OPERATE_SYSTEM_PUBKEY, SettingAccountError, ENSO_SEED, SETTING_ACCOUNT_SEED}};
+use crate::{validate_mint_extensions, InitSettingAccountEvent, PauseFlags, RiskParameters, Role, RoleAccount, SettingAccount, common::{RoleError, SettingAccountError, BPS_DENOMINATOR, ENSO_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}};
 
 #[derive(Accounts)]
-#[instruction(tier_id: String, amount: u64, duration: u64)]
+#[instruction(tier_id: String, amount: u64, min_amount: u64, duration: u64)]
 pub struct InitSettingAccount<'info> {
   #[account(mut)]
   pub owner: Signer<'info>,
   /// CHECK: This is the account used to make a seeds to create ata account for transfer asset from lender to how wallet
   pub receiver: AccountInfo<'info>,
-  pub lend_mint_asset: Account<'info, Mint>,
-  pub collateral_mint_asset: Account<'info, Mint>,
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     init,
     payer = owner,
@@ -27,26 +25,49 @@ pub struct InitSettingAccount<'info> {
     bump
   )]
   pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::TierAdmin as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&owner.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitSettingAccount<'info> {
-    pub fn init_setting_account(&mut self, bumps: &InitSettingAccountBumps, tier_id: String, amount: u64, duration: u64, lender_fee_percent: f64, borrower_fee_percent: f64  ) -> Result<()> {
-      if self.owner.key() != Pubkey::from_str(OPERATE_SYSTEM_PUBKEY).unwrap() {
-        return err!(SettingAccountError::InvalidOwner)?;
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_setting_account(&mut self, bumps: &InitSettingAccountBumps, tier_id: String, amount: u64, min_amount: u64, duration: u64, lender_fee_bps: u64, borrower_fee_bps: u64, risk_parameters: RiskParameters) -> Result<()> {
+      if lender_fee_bps > BPS_DENOMINATOR || borrower_fee_bps > BPS_DENOMINATOR {
+        return err!(SettingAccountError::FeeTooHigh);
       }
 
+      risk_parameters.validate()?;
+      SettingAccount::validate_amount_bounds(min_amount, amount)?;
+      validate_mint_extensions(&self.lend_mint_asset.to_account_info())?;
+      validate_mint_extensions(&self.collateral_mint_asset.to_account_info())?;
+
       self.setting_account.set_inner(SettingAccount {
         amount,
+        min_amount,
         duration,
         owner: self.owner.key(),
+        pending_owner: None,
         receiver: self.receiver.key(),
         lend_mint_asset: self.lend_mint_asset.key(),
         collateral_mint_asset: self.collateral_mint_asset.key(),
         tier_id,
         bump: bumps.setting_account,
-        lender_fee_percent,
-        borrower_fee_percent
+        lender_fee_bps,
+        borrower_fee_bps,
+        risk_parameters,
+        pause_flags: PauseFlags::default(),
+        bad_debt_amount: 0,
+        defaulted_loan_count: 0,
       });
 
       msg!("Init Setting Account: {:?}", self.setting_account);
@@ -58,13 +79,15 @@ impl<'info> InitSettingAccount<'info> {
       emit!(InitSettingAccountEvent {
           tier_id: self.setting_account.tier_id.clone(),
           amount: self.setting_account.amount,
+          min_amount: self.setting_account.min_amount,
           duration: self.setting_account.duration,
           collateral_mint_asset: self.setting_account.collateral_mint_asset,
           lend_mint_asset: self.setting_account.lend_mint_asset,
           owner: self.setting_account.owner,
           receiver: self.setting_account.receiver,
-          lender_fee_percent: self.setting_account.lender_fee_percent,
-          borrower_fee_percent: self.setting_account.borrower_fee_percent
+          lender_fee_bps: self.setting_account.lender_fee_bps,
+          borrower_fee_bps: self.setting_account.borrower_fee_bps,
+          risk_parameters: self.setting_account.risk_parameters,
       });
       
       msg!(&label.clone());
@@ -0,0 +1,368 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{constant::{ASSET_CONFIG_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, SETTING_ACCOUNT_SEED}, PauseError},
+  calculate_amount_with_transfer_fee, seize_collateral_positions, native_vault_balance, transfer_from_native_vault, validate_vault_balance, AssetConfig, CollateralPositionLiquidatedEvent,
+  GlobalConfig, LoanLiquidatedEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, MathError, PauseAction, RepayOfferError, SettingAccount, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct Liquidate<'info> {
+  #[account(mut)]
+  pub liquidator: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the remaining collateral
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::InvalidOfferStatus,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = liquidator,
+    associated_token::token_program = token_program
+  )]
+  pub liquidator_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = setting_account.receiver,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> Liquidate<'info> {
+  /// `remaining_accounts` holds the asset config and price update account of each basket
+  /// position, followed by the mint, vault, receiving token account and token program of each
+  /// position, both in the order the positions are stored.
+  pub fn liquidate(&mut self, bumps: &LiquidateBumps, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    let (basket_accounts, position_accounts) = remaining_accounts.split_at(
+      remaining_accounts.len().min(self.loan_offer.collateral_positions.len() * 2)
+    );
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    self.loan_offer.validate_liquidatable(
+      current_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let repay_amount = self.loan_offer.total_repay_amount()?;
+    self.repay_debt(repay_amount)?;
+
+    let (seized_collateral, seized_position_amounts) = self.loan_offer.liquidator_collateral_amounts(
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      self.setting_account.risk_parameters.liquidation_penalty_bps,
+      basket_accounts
+    )?;
+    let returned_collateral = self.loan_offer.collateral_amount - seized_collateral;
+
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)?;
+
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.liquidator,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      seized_collateral
+    )?;
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.borrower,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      self.collateral_vault.lamports()
+    )?;
+
+    let seized_positions = seize_collateral_positions(
+      &mut self.loan_offer,
+      position_accounts,
+      &seized_position_amounts,
+      &self.borrower.to_account_info()
+    )?;
+
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.collateral_amount = 0;
+    loan_offer.liquidating_at = Some(current_timestamp as u64);
+    loan_offer.status = LoanOfferStatus::Finished;
+    loan_offer.clear_withdraw_request();
+
+    for position in seized_positions {
+      emit!(CollateralPositionLiquidatedEvent {
+        loan_offer_id: self.loan_offer.offer_id.clone(),
+        system: self.liquidator.key(),
+        mint: position.mint,
+        amount: position.amount,
+      });
+    }
+
+    self.emit_event_liquidate(String::from("liquidate"), repay_amount, seized_collateral, returned_collateral)
+  }
+
+  /// Pays the lender their principal and interest directly and the protocol fees to the hot
+  /// wallet, so the loan is settled without waiting on the operator.
+  fn repay_debt(&self, repay_amount: u64) -> Result<()> {
+    let lender_amount = self.loan_offer.lender_repay_amount()?;
+    let fee_amount = repay_amount.checked_sub(lender_amount).ok_or(MathError::Overflow)?;
+
+    let mut total_transfer_amount: u64 = 0;
+    for (to, amount) in [
+      (self.lender_lend_ata.to_account_info(), lender_amount),
+      (self.hot_wallet_ata.to_account_info(), fee_amount),
+    ] {
+      if amount == 0 {
+        continue;
+      }
+
+      let transfer_amount = calculate_amount_with_transfer_fee(&self.lend_mint_asset.to_account_info(), amount)?;
+      total_transfer_amount = total_transfer_amount.checked_add(transfer_amount).ok_or(MathError::Overflow)?;
+      if total_transfer_amount > self.liquidator_lend_ata.amount {
+        return err!(RepayOfferError::NotEnoughAmount);
+      }
+
+      let cpi_accounts = TransferChecked {
+        from: self.liquidator_lend_ata.to_account_info(),
+        mint: self.lend_mint_asset.to_account_info(),
+        to,
+        authority: self.liquidator.to_account_info(),
+      };
+      let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+      transfer_checked(cpi_ctx, transfer_amount, self.lend_mint_asset.decimals)?;
+    }
+
+    Ok(())
+  }
+
+  fn emit_event_liquidate(
+    &self,
+    label: String,
+    repay_amount: u64,
+    seized_collateral: u64,
+    returned_collateral: u64
+  ) -> Result<()> {
+    emit!(LoanLiquidatedEvent {
+      liquidator: self.liquidator.key(),
+      borrower: self.borrower.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      repay_amount,
+      seized_collateral_amount: seized_collateral,
+      returned_collateral_amount: returned_collateral,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::{test_utils::*, LiquidateOfferError};
+
+  const OFFER_ID: &str = "loan";
+
+  struct Fixture {
+    liquidator: Pubkey,
+    receiver: Pubkey,
+    loan_offer: LoanOfferAccount,
+    collateral_usd_price: i64,
+    liquidator_balance: u64,
+  }
+
+  impl Fixture {
+    /// Loan of 100 USD against one SOL priced at `collateral_usd_price`.
+    fn new(collateral_usd_price: i64) -> Self {
+      Self {
+        liquidator: Pubkey::new_unique(),
+        receiver: Pubkey::new_unique(),
+        loan_offer: loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT),
+        collateral_usd_price,
+        liquidator_balance: 200_000_000,
+      }
+    }
+
+    fn load(&self) -> (Liquidate<'static>, LiquidateBumps) {
+      let loan_offer_key = loan_offer_key(&self.loan_offer.borrower, OFFER_ID);
+      let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let lender = self.loan_offer.lender;
+      let mut setting_account = setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT);
+      setting_account.receiver = self.receiver;
+
+      let accounts = vec![
+        signer(self.liquidator),
+        wallet(self.loan_offer.borrower),
+        state(loan_offer_key, &self.loan_offer),
+        account(
+          collateral_vault,
+          System::id(),
+          Rent::default().minimum_balance(0) + self.loan_offer.collateral_amount,
+          vec![],
+          false,
+          false
+        ),
+        mint(lend_mint, spl_token::ID),
+        token_account(ata(&self.liquidator, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.liquidator, self.liquidator_balance),
+        token_account(ata(&self.receiver, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.receiver, 0),
+        wallet(lender),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        state(setting_account_key(TIER_ID), &setting_account),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(self.collateral_usd_price), PRICE_EXPONENT),
+        state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&NATIVE_MINT), &asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9)),
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+      let (context, bumps, _) = load::<Liquidate, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+      (context, bumps)
+    }
+  }
+
+  #[test]
+  fn liquidator_repays_debt_and_takes_collateral_with_bonus() {
+    // 100 USD of debt against 120 USD of collateral is past the 80% liquidation threshold.
+    let fixture = Fixture::new(120);
+    let (mut context, bumps) = fixture.load();
+    let liquidator_lamports = context.liquidator.lamports();
+    let vault_lamports = context.collateral_vault.lamports();
+
+    context.liquidate(&bumps, &[]).unwrap();
+
+    // 110.5 USD repaid, plus the 5% bonus, buys 0.966875 SOL.
+    assert_eq!(token_amount(&context.liquidator_lend_ata.to_account_info()), fixture.liquidator_balance - 110_500_000);
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), fixture.loan_offer.lender_repay_amount().unwrap());
+    assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 1_000_000);
+    assert_eq!(context.liquidator.lamports(), liquidator_lamports + 966_875_000);
+    assert_eq!(context.borrower.lamports(), vault_lamports - 966_875_000);
+    assert_eq!(context.collateral_vault.lamports(), 0);
+    assert_eq!(context.loan_offer.collateral_amount, 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Finished);
+  }
+
+  #[test]
+  fn rejects_liquidation_of_healthy_loan() {
+    let (mut context, bumps) = Fixture::new(200).load();
+
+    assert_error(context.liquidate(&bumps, &[]), LiquidateOfferError::LoanNotLiquidatable);
+  }
+
+  #[test]
+  fn liquidates_healthy_loan_past_expiry() {
+    let mut fixture = Fixture::new(200);
+    fixture.loan_offer.started_at = now() - fixture.loan_offer.duration as i64 - 1;
+    let (mut context, bumps) = fixture.load();
+
+    context.liquidate(&bumps, &[]).unwrap();
+
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Finished);
+  }
+
+  #[test]
+  fn rejects_liquidator_unable_to_repay_debt() {
+    let mut fixture = Fixture::new(120);
+    fixture.liquidator_balance = 110_000_000;
+    let (mut context, bumps) = fixture.load();
+
+    assert_error(context.liquidate(&bumps, &[]), RepayOfferError::NotEnoughAmount);
+  }
+}
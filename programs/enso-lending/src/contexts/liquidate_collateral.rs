@@ -1,10 +1,11 @@
-use std::str::FromStr;
-
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 use crate::{
-  common::constant::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, OPERATE_SYSTEM_PUBKEY}, 
-  LiquidatingCollateralEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus, HOT_WALLET_PUBKEY
+  common::{constant::{ASSET_CONFIG_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, LiquidateOfferError, PauseError, RoleError}, 
+  calculate_amount_with_transfer_fee, native_vault_balance, transfer_from_native_vault, validate_vault_balance, AssetConfig, GlobalConfig, LiquidatingCollateralEvent, LoanOfferAccount,
+  LoanOfferError, LoanOfferStatus, PartialLiquidationEvent, PauseAction, Role, RoleAccount, SettingAccount, NATIVE_MINT
 };
 
 #[derive(Accounts)]
@@ -12,10 +13,12 @@ use crate::{
 pub struct LiquidateCollateral<'info> {
   #[account(mut)]
   pub system: Signer<'info>,
-  /// CHECK: This is the account used to make a seeds
+  /// CHECK: This is the account used to make a seeds and receive the vault rent
+  #[account(mut)]
   pub borrower: UncheckedAccount<'info>,
   #[account(
     mut,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
     seeds = [
       ENSO_SEED.as_ref(),
       LOAN_OFFER_ACCOUNT_SEED.as_ref(),
@@ -26,36 +29,263 @@ pub struct LiquidateCollateral<'info> {
     bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
   #[account(mut)]
   /// CHECK: This is the account used to received the collateral for liquidate 
-  pub hot_wallet: UncheckedAccount<'info>
+  pub hot_wallet: UncheckedAccount<'info>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = system,
+    associated_token::token_program = token_program
+  )]
+  pub system_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Liquidator as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
 }
 
 impl<'info> LiquidateCollateral<'info> {
-  pub fn start_liquidate_contract(&mut self, liquidating_price: u64, liquidating_at: u64) -> Result<()> {
-    if self.system.key() != Pubkey::from_str(OPERATE_SYSTEM_PUBKEY).unwrap() {
-      return err!(LoanOfferError::InvalidSystem);
-    }
-
-    if self.hot_wallet.key() != Pubkey::from_str(HOT_WALLET_PUBKEY).unwrap() {
+  pub fn start_liquidate_contract(
+    &mut self,
+    bumps: &LiquidateCollateralBumps,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    if self.hot_wallet.key() != self.global_config.hot_wallet {
       return err!(LoanOfferError::InvalidHotWallet);
     }
 
-    let loan_offer = &mut self.loan_offer;
-    if loan_offer.status != LoanOfferStatus::FundTransferred {
+    if self.loan_offer.status != LoanOfferStatus::FundTransferred {
       return err!(LoanOfferError::InvalidOfferStatus);
     }
 
+    self.loan_offer.validate_liquidatable(
+      Clock::get()?.unix_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let liquidating_price = self.collateral_asset_config.unit_price(&self.collateral_price_feed_account)?;
+    let loan_offer = &mut self.loan_offer;
+
     loan_offer.liquidating_price = Some(liquidating_price);
-    loan_offer.liquidating_at = Some(liquidating_at);
+    loan_offer.liquidating_at = Some(Clock::get()?.unix_timestamp as u64);
     loan_offer.status = LoanOfferStatus::Liquidating;
+    loan_offer.clear_withdraw_request();
+
+    self.release_collateral_vault(bumps.collateral_vault)
+  }
+
+  /// Seizes only the collateral needed to bring the loan back to the initial LTV plus the
+  /// liquidation penalty, writes the repaid value off the debt and pays the lender what that
+  /// part of the debt was worth to them. The loan stays active.
+  pub fn partial_liquidate_contract(
+    &mut self,
+    bumps: &LiquidateCollateralBumps,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    if self.hot_wallet.key() != self.global_config.hot_wallet {
+      return err!(LoanOfferError::InvalidHotWallet);
+    }
+
+    if self.loan_offer.status != LoanOfferStatus::FundTransferred {
+      return err!(LoanOfferError::InvalidOfferStatus);
+    }
 
-    self.loan_offer.sub_lamports(self.loan_offer.collateral_amount)?;
-    self.hot_wallet.add_lamports(self.loan_offer.collateral_amount)?;
+    if Clock::get()?.unix_timestamp > self.loan_offer.end_at() {
+      return err!(LoanOfferError::LoanOfferExpired);
+    }
+
+    self.loan_offer.validate_liquidatable(
+      Clock::get()?.unix_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let (seized_collateral, debt_reduction) = self.loan_offer.partial_liquidation_amounts(
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.hot_wallet,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      seized_collateral
+    )?;
+
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.collateral_amount -= seized_collateral;
+    let lender_payout = loan_offer.write_off_debt(debt_reduction)?;
+    loan_offer.clear_withdraw_request();
+
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)?;
+
+    self.pay_lender(lender_payout)?;
+
+    self.emit_event_partial_liquidate_contract(
+      String::from("partial_liquidate_contract"),
+      seized_collateral,
+      debt_reduction,
+      lender_payout
+    )
+  }
+
+  fn pay_lender(&self, lender_payout: u64) -> Result<()> {
+    let transfer_amount = calculate_amount_with_transfer_fee(&self.lend_mint_asset.to_account_info(), lender_payout)?;
+    if transfer_amount > self.system_lend_ata.amount {
+      return err!(LiquidateOfferError::NotEnoughAmount);
+    }
+
+    let cpi_accounts = TransferChecked {
+      from: self.system_lend_ata.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.lender_lend_ata.to_account_info(),
+      authority: self.system.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, transfer_amount, self.lend_mint_asset.decimals)
+  }
 
+  fn emit_event_partial_liquidate_contract(
+    &self,
+    label: String,
+    seized_collateral: u64,
+    debt_reduction: u64,
+    lender_payout: u64
+  ) -> Result<()> {
+    emit!(PartialLiquidationEvent {
+      system: self.system.key(),
+      offer_id: self.loan_offer.offer_id.clone(),
+      seized_collateral_amount: seized_collateral,
+      debt_reduction_amount: debt_reduction,
+      lender_payout_amount: lender_payout,
+      collateral_amount: self.loan_offer.collateral_amount,
+      borrow_amount: self.loan_offer.borrow_amount,
+    });
+
+    msg!(&label.clone());
     Ok(())
   }
 
+  /// Sends the collateral to the hot wallet and refunds the vault rent to the borrower,
+  /// leaving the vault empty.
+  fn release_collateral_vault(&self, vault_bump: u8) -> Result<()> {
+    let loan_offer = self.loan_offer.key();
+
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.hot_wallet,
+      &self.system_program,
+      loan_offer,
+      vault_bump,
+      self.loan_offer.collateral_amount
+    )?;
+
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.borrower,
+      &self.system_program,
+      loan_offer,
+      vault_bump,
+      self.collateral_vault.lamports()
+    )
+  }
+
   pub fn emit_event_start_liquidate_contract(&self, label: String) -> Result<()> {
     emit!(LiquidatingCollateralEvent {
       offer_id: self.loan_offer.offer_id.clone(),
@@ -66,4 +296,148 @@ impl<'info> LiquidateCollateral<'info> {
     msg!(&label.clone());
     Ok(())
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::{calculate_ltv_bps, test_utils::*};
+
+  const OFFER_ID: &str = "loan";
+
+  struct Fixture {
+    system: Pubkey,
+    hot_wallet: Pubkey,
+    loan_offer: LoanOfferAccount,
+    collateral_usd_price: i64,
+    system_balance: u64,
+  }
+
+  impl Fixture {
+    /// Loan of 100 USD against one SOL priced at `collateral_usd_price`.
+    fn new(collateral_usd_price: i64) -> Self {
+      Self {
+        system: Pubkey::new_unique(),
+        hot_wallet: Pubkey::new_unique(),
+        loan_offer: loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT),
+        collateral_usd_price,
+        system_balance: 200_000_000,
+      }
+    }
+
+    fn load(&self) -> (LiquidateCollateral<'static>, LiquidateCollateralBumps) {
+      let loan_offer_key = loan_offer_key(&self.loan_offer.borrower, OFFER_ID);
+      let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let lender = self.loan_offer.lender;
+
+      let accounts = vec![
+        signer(self.system),
+        wallet(self.loan_offer.borrower),
+        state(loan_offer_key, &self.loan_offer),
+        account(
+          collateral_vault,
+          System::id(),
+          Rent::default().minimum_balance(0) + self.loan_offer.collateral_amount,
+          vec![],
+          false,
+          false
+        ),
+        wallet(self.hot_wallet),
+        mint(lend_mint, spl_token::ID),
+        token_account(ata(&self.system, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.system, self.system_balance),
+        wallet(lender),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), self.hot_wallet)),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT)),
+        state(role_account_key(Role::Liquidator), &role_account(Role::Liquidator, vec![self.system])),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(self.collateral_usd_price), PRICE_EXPONENT),
+        state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&NATIVE_MINT), &asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9)),
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+      let (context, bumps, _) = load::<LiquidateCollateral, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+      (context, bumps)
+    }
+  }
+
+  #[test]
+  fn partial_liquidation_restores_initial_ltv() {
+    // 100 USD of debt against 120 USD of collateral is past the 80% liquidation threshold.
+    let fixture = Fixture::new(120);
+    let (mut context, bumps) = fixture.load();
+
+    context.partial_liquidate_contract(&bumps, &[]).unwrap();
+
+    // 84.21 USD of debt repaid, plus the 5% penalty, takes 0.7368 SOL.
+    assert_eq!(context.loan_offer.collateral_amount, 263_157_883);
+    assert_eq!(context.loan_offer.borrow_amount, 15_789_473);
+    assert_eq!(context.hot_wallet.lamports(), fixture.loan_offer.collateral_amount - 263_157_883);
+    assert_eq!(native_vault_balance(&context.collateral_vault).unwrap(), context.loan_offer.collateral_amount);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::FundTransferred);
+
+    // Back at the 50% initial LTV, give or take a basis point of rounding.
+    let collateral_value = context.loan_offer.collateral_amount as u128 * 120 / 1_000;
+    assert!(calculate_ltv_bps(context.loan_offer.borrow_amount as u128, collateral_value).unwrap() <= 5_001);
+  }
+
+  #[test]
+  fn partial_liquidation_pays_lender_for_the_debt_written_off() {
+    let fixture = Fixture::new(120);
+    let (mut context, bumps) = fixture.load();
+
+    context.partial_liquidate_contract(&bumps, &[]).unwrap();
+
+    // The 84.21 USD written off carried 8.42 USD of interest, less the lender fee.
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 92_210_527);
+    assert_eq!(token_amount(&context.system_lend_ata.to_account_info()), fixture.system_balance - 92_210_527);
+    // The lender is still owed the rest of the original 109.5 USD payout.
+    assert_eq!(context.loan_offer.lender_repay_amount().unwrap(), 109_500_000 - 92_210_527);
+  }
+
+  #[test]
+  fn rejects_partial_liquidation_when_system_can_not_pay_lender() {
+    let mut fixture = Fixture::new(120);
+    fixture.system_balance = 92_210_526;
+    let (mut context, bumps) = fixture.load();
+
+    assert_error(context.partial_liquidate_contract(&bumps, &[]), LiquidateOfferError::NotEnoughAmount);
+  }
+
+  #[test]
+  fn full_liquidation_moves_all_collateral_to_hot_wallet() {
+    let fixture = Fixture::new(120);
+    let (mut context, bumps) = fixture.load();
+
+    context.start_liquidate_contract(&bumps, &[]).unwrap();
+
+    assert_eq!(context.hot_wallet.lamports(), fixture.loan_offer.collateral_amount);
+    assert_eq!(context.collateral_vault.lamports(), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Liquidating);
+    assert_eq!(context.loan_offer.liquidating_price, Some(120_000_000));
+    assert_eq!(context.loan_offer.liquidating_at, Some(now() as u64));
+  }
+
+  #[test]
+  fn starts_liquidation_of_healthy_loan_past_expiry() {
+    let mut fixture = Fixture::new(200);
+    fixture.loan_offer.started_at = now() - fixture.loan_offer.duration as i64 - 1;
+    let (mut context, bumps) = fixture.load();
+
+    context.start_liquidate_contract(&bumps, &[]).unwrap();
+
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Liquidating);
+    assert_eq!(context.loan_offer.liquidating_price, Some(200_000_000));
+  }
+
+  #[test]
+  fn rejects_liquidation_of_healthy_loan() {
+    let (mut context, bumps) = Fixture::new(200).load();
+
+    assert_error(context.partial_liquidate_contract(&bumps, &[]), LiquidateOfferError::LoanNotLiquidatable);
+  }
+}
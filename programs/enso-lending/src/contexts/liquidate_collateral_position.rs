@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{constant::{ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, PauseError, RoleError},
+  AssetConfig, CollateralPositionError, CollateralPositionLiquidatedEvent, GlobalConfig, LoanOfferAccount, LoanOfferError,
+  LoanOfferStatus, PauseAction, Role, RoleAccount, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct LiquidateCollateralPosition<'info> {
+  #[account(mut)]
+  pub system: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the vault rent
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Liquidating
+    || loan_offer.status == LoanOfferStatus::Defaulted @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = mint,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = global_config.hot_wallet,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = asset_config.bump
+  )]
+  pub asset_config: Account<'info, AssetConfig>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Liquidator as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> LiquidateCollateralPosition<'info> {
+  /// Positions left on a defaulted loan are seized as well, so they go towards the bad debt
+  /// instead of back to the borrower.
+  pub fn liquidate_collateral_position(&mut self, asset_config_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    let index = self.loan_offer
+      .find_collateral_position(&self.mint.key())
+      .ok_or(CollateralPositionError::PositionNotFound)?;
+
+    self.validate_liquidation_priority(index, asset_config_accounts)?;
+
+    let amount = self.collateral_vault.amount;
+    self.transfer_position_to_hot_wallet(amount)?;
+
+    self.loan_offer.collateral_positions.remove(index);
+
+    self.emit_event_liquidate_collateral_position(String::from("liquidate_collateral_position"), amount)
+  }
+
+  /// Positions are seized in ascending `liquidation_priority`. `asset_config_accounts` holds the
+  /// asset config of every other position, in the order the positions are stored.
+  fn validate_liquidation_priority(&self, index: usize, asset_config_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    let other_positions = self.loan_offer.collateral_positions
+      .iter()
+      .enumerate()
+      .filter(|(position_index, _)| *position_index != index)
+      .map(|(_, position)| position);
+
+    if asset_config_accounts.len() != self.loan_offer.collateral_positions.len() - 1 {
+      return err!(CollateralPositionError::PositionAccountsMismatch);
+    }
+
+    for (position, account) in other_positions.zip(asset_config_accounts.iter()) {
+      let asset_config = Account::<AssetConfig>::try_from(account)?;
+      if asset_config.mint != position.mint {
+        return err!(CollateralPositionError::PositionAccountsMismatch);
+      }
+
+      if position.amount > 0 && asset_config.liquidation_priority < self.asset_config.liquidation_priority {
+        return err!(CollateralPositionError::LiquidationPriorityViolated);
+      }
+    }
+
+    Ok(())
+  }
+
+  fn transfer_position_to_hot_wallet(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let transfer_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.mint.to_account_info(),
+      to: self.hot_wallet_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.token_program.to_account_info(), transfer_accounts, signer),
+      amount,
+      self.mint.decimals,
+    )?;
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer))
+  }
+
+  fn emit_event_liquidate_collateral_position(&self, label: String, amount: u64) -> Result<()> {
+    emit!(CollateralPositionLiquidatedEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      system: self.system.key(),
+      mint: self.mint.key(),
+      amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,322 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{constant::{ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, LiquidateOfferError, PauseError, RoleError}, 
+  calculate_amount_with_transfer_fee, validate_vault_balance, AssetConfig, GlobalConfig, LiquidatingCollateralEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus,
+  PartialLiquidationEvent, PauseAction, Role, RoleAccount, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct LiquidateCollateralToken<'info> {
+  #[account(mut)]
+  pub system: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the vault rent
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = global_config.hot_wallet,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub hot_wallet_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = system,
+    associated_token::token_program = token_program
+  )]
+  pub system_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Liquidator as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> LiquidateCollateralToken<'info> {
+  pub fn start_liquidate_contract(
+    &mut self,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    if self.loan_offer.status != LoanOfferStatus::FundTransferred {
+      return err!(LoanOfferError::InvalidOfferStatus);
+    }
+
+    self.loan_offer.validate_liquidatable(
+      Clock::get()?.unix_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let liquidating_price = self.collateral_asset_config.unit_price(&self.collateral_price_feed_account)?;
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.liquidating_price = Some(liquidating_price);
+    loan_offer.liquidating_at = Some(Clock::get()?.unix_timestamp as u64);
+    loan_offer.status = LoanOfferStatus::Liquidating;
+    loan_offer.clear_withdraw_request();
+
+    self.transfer_collateral_to_hot_wallet()
+  }
+
+  /// Seizes only the collateral needed to bring the loan back to the initial LTV plus the
+  /// liquidation penalty, writes the repaid value off the debt and pays the lender what that
+  /// part of the debt was worth to them. The loan stays active.
+  pub fn partial_liquidate_contract(&mut self, basket_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    if self.loan_offer.status != LoanOfferStatus::FundTransferred {
+      return err!(LoanOfferError::InvalidOfferStatus);
+    }
+
+    if Clock::get()?.unix_timestamp > self.loan_offer.end_at() {
+      return err!(LoanOfferError::LoanOfferExpired);
+    }
+
+    self.loan_offer.validate_liquidatable(
+      Clock::get()?.unix_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let (seized_collateral, debt_reduction) = self.loan_offer.partial_liquidation_amounts(
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    self.transfer_seized_collateral(seized_collateral)?;
+
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.collateral_amount -= seized_collateral;
+    let lender_payout = loan_offer.write_off_debt(debt_reduction)?;
+    loan_offer.clear_withdraw_request();
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)?;
+
+    self.pay_lender(lender_payout)?;
+
+    self.emit_event_partial_liquidate_contract(
+      String::from("partial_liquidate_contract_token"),
+      seized_collateral,
+      debt_reduction,
+      lender_payout
+    )
+  }
+
+  fn pay_lender(&self, lender_payout: u64) -> Result<()> {
+    let transfer_amount = calculate_amount_with_transfer_fee(&self.lend_mint_asset.to_account_info(), lender_payout)?;
+    if transfer_amount > self.system_lend_ata.amount {
+      return err!(LiquidateOfferError::NotEnoughAmount);
+    }
+
+    let cpi_accounts = TransferChecked {
+      from: self.system_lend_ata.to_account_info(),
+      mint: self.lend_mint_asset.to_account_info(),
+      to: self.lender_lend_ata.to_account_info(),
+      authority: self.system.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+    transfer_checked(cpi_ctx, transfer_amount, self.lend_mint_asset.decimals)
+  }
+
+  fn transfer_seized_collateral(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let transfer_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.hot_wallet_collateral_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+      amount,
+      self.collateral_mint_asset.decimals,
+    )
+  }
+
+  fn emit_event_partial_liquidate_contract(
+    &self,
+    label: String,
+    seized_collateral: u64,
+    debt_reduction: u64,
+    lender_payout: u64
+  ) -> Result<()> {
+    emit!(PartialLiquidationEvent {
+      system: self.system.key(),
+      offer_id: self.loan_offer.offer_id.clone(),
+      seized_collateral_amount: seized_collateral,
+      debt_reduction_amount: debt_reduction,
+      lender_payout_amount: lender_payout,
+      collateral_amount: self.loan_offer.collateral_amount,
+      borrow_amount: self.loan_offer.borrow_amount,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+
+  fn transfer_collateral_to_hot_wallet(&self) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let transfer_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.hot_wallet_collateral_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+      self.collateral_vault.amount,
+      self.collateral_mint_asset.decimals,
+    )?;
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  pub fn emit_event_start_liquidate_contract(&self, label: String) -> Result<()> {
+    emit!(LiquidatingCollateralEvent {
+      offer_id: self.loan_offer.offer_id.clone(),
+      liquidating_at: self.loan_offer.liquidating_at.unwrap(),
+      liquidating_price: self.loan_offer.liquidating_price.unwrap(),
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
@@ -0,0 +1,294 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{constant::{ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED}, PauseError},
+  calculate_amount_with_transfer_fee, seize_collateral_positions, validate_vault_balance, AssetConfig, CollateralPositionLiquidatedEvent, GlobalConfig, LoanLiquidatedEvent, LoanOfferAccount,
+  LoanOfferError, LoanOfferStatus, MathError, PauseAction, RepayOfferError, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct LiquidateToken<'info> {
+  #[account(mut)]
+  pub liquidator: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the vault rent
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = liquidator,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub liquidator_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = liquidator,
+    associated_token::token_program = token_program
+  )]
+  pub liquidator_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = setting_account.receiver,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> LiquidateToken<'info> {
+  /// `remaining_accounts` holds the asset config and price update account of each basket
+  /// position, followed by the mint, vault, receiving token account and token program of each
+  /// position, both in the order the positions are stored.
+  pub fn liquidate(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    let (basket_accounts, position_accounts) = remaining_accounts.split_at(
+      remaining_accounts.len().min(self.loan_offer.collateral_positions.len() * 2)
+    );
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    self.loan_offer.validate_liquidatable(
+      current_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let repay_amount = self.loan_offer.total_repay_amount()?;
+    self.repay_debt(repay_amount)?;
+
+    let (seized_collateral, seized_position_amounts) = self.loan_offer.liquidator_collateral_amounts(
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      self.setting_account.risk_parameters.liquidation_penalty_bps,
+      basket_accounts
+    )?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)?;
+    let returned_collateral = self.collateral_vault.amount - seized_collateral;
+    self.release_collateral(seized_collateral, returned_collateral)?;
+
+    let seized_positions = seize_collateral_positions(
+      &mut self.loan_offer,
+      position_accounts,
+      &seized_position_amounts,
+      &self.borrower.to_account_info()
+    )?;
+
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.collateral_amount = 0;
+    loan_offer.liquidating_at = Some(current_timestamp as u64);
+    loan_offer.status = LoanOfferStatus::Finished;
+    loan_offer.clear_withdraw_request();
+
+    for position in seized_positions {
+      emit!(CollateralPositionLiquidatedEvent {
+        loan_offer_id: self.loan_offer.offer_id.clone(),
+        system: self.liquidator.key(),
+        mint: position.mint,
+        amount: position.amount,
+      });
+    }
+
+    self.emit_event_liquidate(String::from("liquidate_token"), repay_amount, seized_collateral, returned_collateral)
+  }
+
+  /// Pays the lender their principal and interest directly and the protocol fees to the hot
+  /// wallet, so the loan is settled without waiting on the operator.
+  fn repay_debt(&self, repay_amount: u64) -> Result<()> {
+    let lender_amount = self.loan_offer.lender_repay_amount()?;
+    let fee_amount = repay_amount.checked_sub(lender_amount).ok_or(MathError::Overflow)?;
+
+    let mut total_transfer_amount: u64 = 0;
+    for (to, amount) in [
+      (self.lender_lend_ata.to_account_info(), lender_amount),
+      (self.hot_wallet_ata.to_account_info(), fee_amount),
+    ] {
+      if amount == 0 {
+        continue;
+      }
+
+      let transfer_amount = calculate_amount_with_transfer_fee(&self.lend_mint_asset.to_account_info(), amount)?;
+      total_transfer_amount = total_transfer_amount.checked_add(transfer_amount).ok_or(MathError::Overflow)?;
+      if total_transfer_amount > self.liquidator_lend_ata.amount {
+        return err!(RepayOfferError::NotEnoughAmount);
+      }
+
+      let cpi_accounts = TransferChecked {
+        from: self.liquidator_lend_ata.to_account_info(),
+        mint: self.lend_mint_asset.to_account_info(),
+        to,
+        authority: self.liquidator.to_account_info(),
+      };
+      let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+      transfer_checked(cpi_ctx, transfer_amount, self.lend_mint_asset.decimals)?;
+    }
+
+    Ok(())
+  }
+
+  fn release_collateral(&self, seized_collateral: u64, returned_collateral: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    for (to, amount) in [
+      (self.liquidator_collateral_ata.to_account_info(), seized_collateral),
+      (self.borrower_collateral_ata.to_account_info(), returned_collateral),
+    ] {
+      if amount == 0 {
+        continue;
+      }
+
+      let transfer_accounts = TransferChecked {
+        from: self.collateral_vault.to_account_info(),
+        mint: self.collateral_mint_asset.to_account_info(),
+        to,
+        authority: self.loan_offer.to_account_info(),
+      };
+      transfer_checked(
+        CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+        amount,
+        self.collateral_mint_asset.decimals,
+      )?;
+    }
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  fn emit_event_liquidate(
+    &self,
+    label: String,
+    repay_amount: u64,
+    seized_collateral: u64,
+    returned_collateral: u64
+  ) -> Result<()> {
+    emit!(LoanLiquidatedEvent {
+      liquidator: self.liquidator.key(),
+      borrower: self.borrower.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      repay_amount,
+      seized_collateral_amount: seized_collateral,
+      returned_collateral_amount: returned_collateral,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
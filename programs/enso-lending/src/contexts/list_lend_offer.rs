@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ENSO_SEED, LEND_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED},
+  LendOfferAccount, LendOfferError, LendOfferListedEvent, LendOfferStatus, OrderBook, OrderBookEntryEvictedEvent, OrderBookError
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct ListLendOffer<'info> {
+  pub signer: Signer<'info>,
+  /// CHECK: This is the account used to make the lend offer seeds
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    constraint = lend_offer.status == LendOfferStatus::Created @ LendOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_OFFER_ACCOUNT_SEED.as_ref(),
+      lender.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_offer.bump
+  )]
+  pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      lend_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = order_book.bump
+  )]
+  pub order_book: Account<'info, OrderBook>,
+}
+
+impl<'info> ListLendOffer<'info> {
+  /// Lists an open offer that is missing from the book of its tier, such as offers created
+  /// before the book was set up or evicted from it while it was full. Anyone can call it.
+  pub fn list_lend_offer(&mut self) -> Result<()> {
+    let lend_offer = self.lend_offer.key();
+    if self.order_book.entries.iter().any(|entry| entry.lend_offer == lend_offer) {
+      return err!(OrderBookError::OfferAlreadyListed);
+    }
+
+    let evicted = self.order_book.insert(lend_offer, self.lend_offer.interest_bps, self.lend_offer.remaining_amount)?;
+    if let Some(evicted_entry) = evicted {
+      emit!(OrderBookEntryEvictedEvent {
+        tier_id: self.order_book.tier_id.clone(),
+        lend_offer: evicted_entry.lend_offer,
+        interest_bps: evicted_entry.interest_bps,
+        remaining_amount: evicted_entry.remaining_amount,
+      });
+    }
+
+    Ok(())
+  }
+
+  pub fn emit_event_list_lend_offer(&self, label: String) -> Result<()> {
+    emit!(LendOfferListedEvent {
+      tier_id: self.order_book.tier_id.clone(),
+      lend_offer: self.lend_offer.key(),
+      interest_bps: self.lend_offer.interest_bps,
+      remaining_amount: self.lend_offer.remaining_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "lend_offer";
+
+  fn load_context(order_book: &OrderBook) -> ListLendOffer<'static> {
+    let lender = Pubkey::new_unique();
+    let lend_offer = lend_offer(lender, OFFER_ID, Pubkey::new_unique(), 100_000_000);
+
+    let accounts = vec![
+      signer(Pubkey::new_unique()),
+      wallet(lender),
+      state(lend_offer_key(&lender, OFFER_ID), &lend_offer),
+      state(order_book_key(), order_book),
+    ];
+
+    load::<ListLendOffer, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+  }
+
+  #[test]
+  fn lists_missing_offer_at_its_rate() {
+    let mut order_book = order_book();
+    order_book.insert(Pubkey::new_unique(), 800, 100_000_000).unwrap();
+    let mut context = load_context(&order_book);
+
+    context.list_lend_offer().unwrap();
+
+    let entry = context.order_book.entries[1];
+    assert_eq!(entry.lend_offer, context.lend_offer.key());
+    assert_eq!(entry.interest_bps, 1_000);
+    assert_eq!(entry.remaining_amount, 100_000_000);
+  }
+
+  #[test]
+  fn rejects_offer_already_listed() {
+    let mut context = load_context(&order_book());
+    context.list_lend_offer().unwrap();
+
+    assert_error(context.list_lend_offer(), OrderBookError::OfferAlreadyListed);
+  }
+}
@@ -1,9 +1,50 @@
+pub mod init_global_config;
+pub use init_global_config::*;
+pub mod edit_global_config;
+pub use edit_global_config::*;
+pub mod grant_role;
+pub use grant_role::*;
+pub mod revoke_role;
+pub use revoke_role::*;
+pub mod set_approval_config;
+pub use set_approval_config::*;
+pub mod create_proposal;
+pub use create_proposal::*;
+pub mod approve_proposal;
+pub use approve_proposal::*;
+pub mod set_swap_adapter;
+pub use set_swap_adapter::*;
+pub mod set_global_pause;
+pub use set_global_pause::*;
+pub mod set_tier_pause;
+pub use set_tier_pause::*;
+pub mod init_asset_config;
+pub use init_asset_config::*;
+pub mod edit_asset_config;
+pub use edit_asset_config::*;
+pub mod init_reserve_fund;
+pub use init_reserve_fund::*;
+pub mod edit_reserve_fund;
+pub use edit_reserve_fund::*;
+
 pub mod init_setting_account;
 pub use init_setting_account::*;
 pub mod edit_setting_account;
 pub use edit_setting_account::*;
+pub mod apply_setting_change;
+pub use apply_setting_change::*;
+pub mod cancel_setting_change;
+pub use cancel_setting_change::*;
 pub mod close_setting_account;
 pub use close_setting_account::*;
+pub mod propose_tier_owner;
+pub use propose_tier_owner::*;
+pub mod accept_tier_owner;
+pub use accept_tier_owner::*;
+pub mod init_order_book;
+pub use init_order_book::*;
+pub mod list_lend_offer;
+pub use list_lend_offer::*;
 
 pub mod create_lend_offer;
 pub use create_lend_offer::*;
@@ -18,17 +59,75 @@ pub mod create_loan_offer_native;
 pub use create_loan_offer_native::*;
 pub mod deposit_collateral_loan_offer_native;
 pub use deposit_collateral_loan_offer_native::*;
+pub mod create_loan_offer_token;
+pub use create_loan_offer_token::*;
+pub mod deposit_collateral_loan_offer_token;
+pub use deposit_collateral_loan_offer_token::*;
+pub mod deposit_collateral_position;
+pub use deposit_collateral_position::*;
 pub mod system_update_loan_offer;
 pub use system_update_loan_offer::*;
+pub mod cancel_matched_loan;
+pub use cancel_matched_loan::*;
+pub mod cancel_matched_loan_token;
+pub use cancel_matched_loan_token::*;
+
+pub mod create_borrow_request;
+pub use create_borrow_request::*;
+pub mod fill_borrow_request;
+pub use fill_borrow_request::*;
+pub mod cancel_borrow_request;
+pub use cancel_borrow_request::*;
+
+pub mod request_withdraw_collateral;
+pub use request_withdraw_collateral::*;
 
+pub mod request_withdraw_collateral_position;
+pub use request_withdraw_collateral_position::*;
+pub mod approve_withdraw_request;
+pub use approve_withdraw_request::*;
+pub mod cancel_withdraw_request;
+pub use cancel_withdraw_request::*;
 pub mod withdraw_collateral;
 pub use withdraw_collateral::*;
+pub mod withdraw_collateral_token;
+pub use withdraw_collateral_token::*;
+pub mod withdraw_collateral_position;
+pub use withdraw_collateral_position::*;
 
 pub mod repay_loan_offer;
 pub use repay_loan_offer::*;
+pub mod repay_loan_offer_token;
+pub use repay_loan_offer_token::*;
 
 pub mod liquidate_collateral;
 pub use liquidate_collateral::*;
+pub mod liquidate_collateral_token;
+pub use liquidate_collateral_token::*;
+pub mod liquidate_collateral_position;
+pub use liquidate_collateral_position::*;
+
+pub mod liquidate;
+pub use liquidate::*;
+pub mod liquidate_token;
+pub use liquidate_token::*;
+
+pub mod start_liquidation_auction;
+pub use start_liquidation_auction::*;
+pub mod bid_liquidation_auction;
+pub use bid_liquidation_auction::*;
+pub mod bid_liquidation_auction_token;
+pub use bid_liquidation_auction_token::*;
+pub mod settle_liquidation_auction;
+pub use settle_liquidation_auction::*;
+pub mod settle_liquidation_auction_token;
+pub use settle_liquidation_auction_token::*;
+pub mod swap_liquidate;
+pub use swap_liquidate::*;
+pub mod swap_liquidate_token;
+pub use swap_liquidate_token::*;
+pub mod cover_bad_debt;
+pub use cover_bad_debt::*;
 
 pub mod system_liquidate_loan_offer;
 pub use system_liquidate_loan_offer::*;
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{SettingAccount, SettingAccountError, TierOwnerProposedEvent, common::{ENSO_SEED, SETTING_ACCOUNT_SEED}};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct ProposeTierOwner<'info> {
+  pub owner: Signer<'info>,
+  #[account(
+    mut,
+    has_one = owner,
+    constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(), 
+      crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+}
+
+impl<'info> ProposeTierOwner<'info> {
+  pub fn propose_tier_owner(&mut self, new_owner: Pubkey) -> Result<()> {
+    self.setting_account.pending_owner = Some(new_owner);
+
+    Ok(())
+  }
+
+  pub fn emit_event_propose_tier_owner(&self, label: String, new_owner: Pubkey) -> Result<()> {
+    emit!(TierOwnerProposedEvent {
+      tier_id: self.setting_account.tier_id.clone(),
+      owner: self.owner.key(),
+      pending_owner: new_owner,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_lang::error::ErrorCode;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  fn propose_accounts(signer_key: Pubkey, owner: Pubkey) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer(signer_key),
+      state(setting_account_key(TIER_ID), &setting_account(owner, Pubkey::new_unique(), crate::NATIVE_MINT)),
+    ]
+  }
+
+  #[test]
+  fn owner_proposes_pending_owner_without_handing_over() {
+    let owner = Pubkey::new_unique();
+    let new_owner = Pubkey::new_unique();
+    let (mut context, _, _) = load::<ProposeTierOwner, _>(propose_accounts(owner, owner), &args(TIER_ID.to_string())).unwrap();
+
+    context.propose_tier_owner(new_owner).unwrap();
+
+    assert_eq!(context.setting_account.owner, owner);
+    assert_eq!(context.setting_account.pending_owner, Some(new_owner));
+  }
+
+  #[test]
+  fn rejects_signer_that_is_not_owner() {
+    let accounts = propose_accounts(Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_error(load::<ProposeTierOwner, _>(accounts, &args(TIER_ID.to_string())).map(|_| ()), ErrorCode::ConstraintHasOne);
+  }
+}
@@ -1,16 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 use crate::{
   common::{
-    ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
-  }, duration_to_year, states::{
+    ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, RESERVE_FUND_SEED, RESERVE_VAULT_SEED, SETTING_ACCOUNT_SEED
+  }, states::{
     loan_offer::LoanOfferAccount,
     setting_account::SettingAccount
-  }, LoanOfferError, LoanOfferStatus, RepayOfferError, SystemRepayLoanOfferNativeEvent
+  }, calculate_amount_with_transfer_fee, deposit_reserve, native_vault_balance, reserve_amount, transfer_from_native_vault, validate_vault_balance, LoanOfferError, LoanOfferStatus, RepayOfferError, ReserveFund, SystemRepayLoanOfferNativeEvent, NATIVE_MINT
 };
 
-
 #[derive(Accounts)]
 #[instruction(loan_offer_id: String)]
 pub struct RepayLoanOffer<'info> {
@@ -19,13 +18,14 @@ pub struct RepayLoanOffer<'info> {
     #[account(
         constraint = mint_asset.key() == setting_account.lend_mint_asset @ RepayOfferError::InvalidMintAsset,
     )]
-    pub mint_asset: Account<'info, Mint>,
+    pub mint_asset: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         associated_token::mint = mint_asset,
-        associated_token::authority = borrower
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
     )]
-    pub loan_ata_asset: Account<'info, TokenAccount>,
+    pub loan_ata_asset: InterfaceAccount<'info, TokenAccount>,
     #[account(
       seeds = [
           ENSO_SEED.as_ref(), 
@@ -39,6 +39,7 @@ pub struct RepayLoanOffer<'info> {
     #[account(
       mut,
       constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ RepayOfferError::LoanOfferIsNotAvailable,
+      constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
       seeds = [
         ENSO_SEED.as_ref(),
         LOAN_OFFER_ACCOUNT_SEED.as_ref(),
@@ -49,30 +50,84 @@ pub struct RepayLoanOffer<'info> {
       bump
     )]
     pub loan_offer: Account<'info, LoanOfferAccount>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+        loan_offer.key().as_ref(),
+        crate::ID.key().as_ref()
+      ],
+      bump
+    )]
+    pub collateral_vault: SystemAccount<'info>,
     #[account(
         mut,
         associated_token::mint = mint_asset,
-        associated_token::authority = setting_account.receiver
+        associated_token::authority = setting_account.receiver,
+        associated_token::token_program = token_program
+    )]
+    pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        RESERVE_FUND_SEED.as_ref(),
+        mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = reserve_fund.bump
     )]
-    pub hot_wallet_ata: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub reserve_fund: Option<Account<'info, ReserveFund>>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        RESERVE_VAULT_SEED.as_ref(),
+        mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump
+    )]
+    pub reserve_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> RepayLoanOffer<'info> {
-    pub fn repay_loan_offer(&mut self) -> Result<()> {
+    pub fn repay_loan_offer(&mut self, bumps: &RepayLoanOfferBumps) -> Result<()> {
       self.validate_loan_offer()?;
       
-      let total_amount = self.get_total_repay();
+      let total_amount = self.loan_offer.total_repay_amount()?;
+      let reserve_amount = reserve_amount(&self.reserve_fund, &self.reserve_vault, self.loan_offer.borrower_fee_amount()?)?;
+      let transfer_amount = calculate_amount_with_transfer_fee(&self.mint_asset.to_account_info(), total_amount - reserve_amount)?;
+      let reserve_transfer_amount = calculate_amount_with_transfer_fee(&self.mint_asset.to_account_info(), reserve_amount)?;
 
-      if total_amount > self.loan_ata_asset.amount {
+      if transfer_amount + reserve_transfer_amount > self.loan_ata_asset.amount {
         return err!(RepayOfferError::NotEnoughAmount);
       }
 
-      self.deposit(total_amount)?;
-
-      self.loan_offer.sub_lamports(self.loan_offer.collateral_amount)?;
-      self.borrower.add_lamports(self.loan_offer.collateral_amount)?;
+      self.deposit(transfer_amount)?;
+      deposit_reserve(
+        &mut self.reserve_fund,
+        &self.reserve_vault,
+        self.loan_ata_asset.to_account_info(),
+        self.borrower.to_account_info(),
+        &self.mint_asset,
+        self.token_program.to_account_info(),
+        &self.loan_offer.offer_id,
+        reserve_amount
+      )?;
+
+      validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)?;
+      transfer_from_native_vault(
+        &self.collateral_vault,
+        &self.borrower,
+        &self.system_program,
+        self.loan_offer.key(),
+        bumps.collateral_vault,
+        self.collateral_vault.lamports()
+      )?;
       self.loan_offer.status = LoanOfferStatus::BorrowerPaid;
 
       self.emit_event_repay_loan_offer( "repay_loan_offer".to_string(), self.loan_offer.collateral_amount)?;
@@ -112,7 +167,7 @@ impl<'info> RepayLoanOffer<'info> {
       emit!(SystemRepayLoanOfferNativeEvent {
         lender: self.loan_offer.lender.key(),
         borrower: self.borrower.key(),
-        interest: self.loan_offer.interest,
+        interest_bps: self.loan_offer.interest_bps,
         loan_amount: self.loan_offer.collateral_amount,
         loan_offer_id: self.loan_offer.offer_id.clone(),
         tier_id: self.loan_offer.tier_id.clone(),
@@ -124,18 +179,4 @@ impl<'info> RepayLoanOffer<'info> {
       
       Ok(())
     }
-
-    fn get_total_repay(&self) -> u64 {
-      let borrower_fee_percent = self.setting_account.borrower_fee_percent / 100.0;
-
-      let loan_interest_percent = self.loan_offer.interest / 100.0;
-
-      let time_borrowed = duration_to_year(self.loan_offer.duration);
-
-      let interest_amount = (self.loan_offer.borrow_amount as f64) * loan_interest_percent * time_borrowed;
-
-      let borrower_fee_amount = borrower_fee_percent * interest_amount;
-      
-      return (self.setting_account.amount as f64 + interest_amount + borrower_fee_amount) as u64;
-    }
 }
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::{
+    COLLATERAL_VAULT_SEED, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, RESERVE_FUND_SEED, RESERVE_VAULT_SEED, SETTING_ACCOUNT_SEED
+  }, states::{
+    loan_offer::LoanOfferAccount,
+    setting_account::SettingAccount
+  }, calculate_amount_with_transfer_fee, deposit_reserve, reserve_amount, LoanOfferError, LoanOfferStatus, RepayLoanOfferEvent, RepayOfferError, ReserveFund
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct RepayLoanOfferToken<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    #[account(
+        constraint = mint_asset.key() == setting_account.lend_mint_asset @ RepayOfferError::InvalidMintAsset,
+    )]
+    pub mint_asset: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_asset,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
+    )]
+    pub loan_ata_asset: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+    )]
+    pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint_asset,
+        associated_token::authority = borrower,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+      seeds = [
+          ENSO_SEED.as_ref(), 
+          SETTING_ACCOUNT_SEED.as_ref(),
+          loan_offer.tier_id.as_bytes(), 
+          crate::ID.key().as_ref(), 
+      ],
+      bump = setting_account.bump
+    )]
+    pub setting_account: Account<'info, SettingAccount>,
+    #[account(
+      mut,
+      constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ RepayOfferError::LoanOfferIsNotAvailable,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+        borrower.key().as_ref(),
+        loan_offer_id.as_bytes(),
+        crate::ID.key().as_ref()
+      ],
+      bump = loan_offer.bump
+    )]
+    pub loan_offer: Account<'info, LoanOfferAccount>,
+    #[account(
+      mut,
+      token::mint = collateral_mint_asset,
+      token::authority = loan_offer,
+      token::token_program = collateral_token_program,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        COLLATERAL_VAULT_SEED.as_ref(),
+        loan_offer.key().as_ref(),
+        crate::ID.key().as_ref()
+      ],
+      bump
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_asset,
+        associated_token::authority = setting_account.receiver,
+        associated_token::token_program = token_program
+    )]
+    pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        RESERVE_FUND_SEED.as_ref(),
+        mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = reserve_fund.bump
+    )]
+    pub reserve_fund: Option<Account<'info, ReserveFund>>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        RESERVE_VAULT_SEED.as_ref(),
+        mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump
+    )]
+    pub reserve_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RepayLoanOfferToken<'info> {
+    pub fn repay_loan_offer(&mut self) -> Result<()> {
+      if Clock::get()?.unix_timestamp > self.loan_offer.end_at() {
+        return err!(LoanOfferError::LoanOfferExpired);
+      }
+
+      let total_amount = self.loan_offer.total_repay_amount()?;
+      let reserve_amount = reserve_amount(&self.reserve_fund, &self.reserve_vault, self.loan_offer.borrower_fee_amount()?)?;
+      let transfer_amount = calculate_amount_with_transfer_fee(&self.mint_asset.to_account_info(), total_amount - reserve_amount)?;
+      let reserve_transfer_amount = calculate_amount_with_transfer_fee(&self.mint_asset.to_account_info(), reserve_amount)?;
+
+      if transfer_amount + reserve_transfer_amount > self.loan_ata_asset.amount {
+        return err!(RepayOfferError::NotEnoughAmount);
+      }
+
+      self.deposit(transfer_amount)?;
+      deposit_reserve(
+        &mut self.reserve_fund,
+        &self.reserve_vault,
+        self.loan_ata_asset.to_account_info(),
+        self.borrower.to_account_info(),
+        &self.mint_asset,
+        self.token_program.to_account_info(),
+        &self.loan_offer.offer_id,
+        reserve_amount
+      )?;
+      self.release_collateral()?;
+
+      self.loan_offer.status = LoanOfferStatus::BorrowerPaid;
+
+      self.emit_event_repay_loan_offer(String::from("repay_loan_offer_token"), total_amount)
+    }
+
+    fn deposit(&self, repay_amount: u64) -> Result<()> {
+      let cpi_accounts = TransferChecked {
+        from: self.loan_ata_asset.to_account_info(),
+        mint: self.mint_asset.to_account_info(),
+        to: self.hot_wallet_ata.to_account_info(),
+        authority: self.borrower.to_account_info(),
+      };
+      let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+      transfer_checked(cpi_ctx, repay_amount, self.mint_asset.decimals)
+    }
+
+    fn release_collateral(&self) -> Result<()> {
+      let borrower = self.loan_offer.borrower;
+      let program_id = crate::ID;
+      let signer: &[&[&[u8]]] = &[&[
+        ENSO_SEED,
+        LOAN_OFFER_ACCOUNT_SEED,
+        borrower.as_ref(),
+        self.loan_offer.offer_id.as_bytes(),
+        program_id.as_ref(),
+        &[self.loan_offer.bump],
+      ]];
+
+      let transfer_accounts = TransferChecked {
+        from: self.collateral_vault.to_account_info(),
+        mint: self.collateral_mint_asset.to_account_info(),
+        to: self.borrower_collateral_ata.to_account_info(),
+        authority: self.loan_offer.to_account_info(),
+      };
+      transfer_checked(
+        CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+        self.collateral_vault.amount,
+        self.collateral_mint_asset.decimals,
+      )?;
+
+      let close_accounts = CloseAccount {
+        account: self.collateral_vault.to_account_info(),
+        destination: self.borrower.to_account_info(),
+        authority: self.loan_offer.to_account_info(),
+      };
+      close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+    }
+
+    fn emit_event_repay_loan_offer(&self, label: String, repay_amount: u64) -> Result<()> {
+      emit!(RepayLoanOfferEvent {
+        borrower: self.borrower.key(),
+        loan_offer_id: self.loan_offer.offer_id.clone(),
+        repay_amount,
+        borrower_fee_bps: self.loan_offer.borrower_fee_bps,
+        status: self.loan_offer.status,
+      });
+
+      msg!(&label.clone());
+
+      Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_spl::token::spl_token;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    const OFFER_ID: &str = "loan";
+    const BORROWER_BALANCE: u64 = 200_000_000;
+
+    struct Fixture {
+        receiver: Pubkey,
+        loan_offer: LoanOfferAccount,
+        reserve_fund: Option<ReserveFund>,
+        reserve_vault: bool,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+            Self {
+                receiver: Pubkey::new_unique(),
+                reserve_fund: Some(reserve_fund(loan_offer.lend_mint_token, 2_000, 0)),
+                reserve_vault: true,
+                loan_offer,
+            }
+        }
+
+        fn load(&self) -> RepayLoanOfferToken<'static> {
+            let borrower = self.loan_offer.borrower;
+            let lend_mint = self.loan_offer.lend_mint_token;
+            let collateral_mint = self.loan_offer.collateral_mint_token;
+            let loan_offer_key = loan_offer_key(&borrower, OFFER_ID);
+            let reserve_fund_key = reserve_fund_key(&lend_mint);
+            let mut setting_account = setting_account(Pubkey::new_unique(), lend_mint, collateral_mint);
+            setting_account.receiver = self.receiver;
+
+            let accounts = vec![
+                signer(borrower),
+                mint(lend_mint, spl_token::ID),
+                token_account(ata(&borrower, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, borrower, BORROWER_BALANCE),
+                mint(collateral_mint, spl_token::ID),
+                token_account(ata(&borrower, &collateral_mint, &spl_token::ID), spl_token::ID, collateral_mint, borrower, 0),
+                state(setting_account_key(TIER_ID), &setting_account),
+                state(loan_offer_key, &self.loan_offer),
+                token_account(
+                    pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]),
+                    spl_token::ID,
+                    collateral_mint,
+                    loan_offer_key,
+                    self.loan_offer.collateral_amount
+                ),
+                token_account(ata(&self.receiver, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.receiver, 0),
+                match &self.reserve_fund {
+                    Some(reserve_fund) => state(reserve_fund_key, reserve_fund),
+                    None => program(crate::ID),
+                },
+                match self.reserve_vault {
+                    true => token_account(reserve_vault_key(&lend_mint), spl_token::ID, lend_mint, reserve_fund_key, 0),
+                    false => program(crate::ID),
+                },
+                program(spl_token::ID),
+                program(spl_token::ID),
+            ];
+
+            load::<RepayLoanOfferToken, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+        }
+    }
+
+    #[test]
+    fn repayment_routes_fee_share_into_reserve() {
+        let fixture = Fixture::new();
+        let mut context = fixture.load();
+
+        context.repay_loan_offer().unwrap();
+
+        // 20% of the 0.5 lend token borrower fee goes to the reserve.
+        let reserve_fund = context.reserve_fund.as_ref().unwrap();
+        assert_eq!(token_amount(&context.reserve_vault.as_ref().unwrap().to_account_info()), 100_000);
+        assert_eq!(reserve_fund.balance, 100_000);
+        assert_eq!(reserve_fund.total_inflow, 100_000);
+        assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 110_400_000);
+        assert_eq!(token_amount(&context.loan_ata_asset.to_account_info()), BORROWER_BALANCE - 110_500_000);
+        assert_eq!(token_amount(&context.borrower_collateral_ata.to_account_info()), fixture.loan_offer.collateral_amount);
+        assert_eq!(context.collateral_vault.to_account_info().lamports(), 0);
+        assert_eq!(context.loan_offer.status, LoanOfferStatus::BorrowerPaid);
+    }
+
+    #[test]
+    fn repayment_without_reserve_fund_pays_hot_wallet_in_full() {
+        let mut fixture = Fixture::new();
+        fixture.reserve_fund = None;
+        fixture.reserve_vault = false;
+        let mut context = fixture.load();
+
+        context.repay_loan_offer().unwrap();
+
+        assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 110_500_000);
+    }
+
+    #[test]
+    fn rejects_reserve_fund_without_its_vault() {
+        let mut fixture = Fixture::new();
+        fixture.reserve_vault = false;
+        let mut context = fixture.load();
+
+        assert_error(context.repay_loan_offer(), crate::ReserveFundError::IncompleteReserveAccounts);
+    }
+}
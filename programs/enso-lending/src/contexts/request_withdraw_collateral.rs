@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED},
+  LoanOfferAccount, LoanOfferError, LoanOfferStatus, WithdrawRequestError, WithdrawRequestedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct RequestWithdrawCollateral<'info> {
+  pub borrower: Signer<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::NotAvailableToWithdraw,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+}
+
+impl<'info> RequestWithdrawCollateral<'info> {
+  pub fn request_withdraw_collateral(&mut self, withdraw_amount: u64) -> Result<()> {
+    let loan_offer = &mut self.loan_offer;
+
+    if loan_offer.request_withdraw_amount.is_some() {
+      return err!(WithdrawRequestError::AlreadyPending);
+    }
+
+    if withdraw_amount == 0 {
+      return err!(WithdrawRequestError::ZeroAmount);
+    }
+
+    if withdraw_amount > loan_offer.collateral_amount {
+      return err!(LoanOfferError::NotEnoughAmount);
+    }
+
+    loan_offer.request_withdraw_amount = Some(withdraw_amount);
+    loan_offer.request_withdraw_at = Some(Clock::get()?.unix_timestamp);
+    loan_offer.request_withdraw_approved = false;
+    loan_offer.request_withdraw_mint = None;
+
+    Ok(())
+  }
+
+  pub fn emit_event_request_withdraw_collateral(&self, label: String) -> Result<()> {
+    emit!(WithdrawRequestedEvent {
+      borrower: self.borrower.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      withdraw_amount: self.loan_offer.request_withdraw_amount.unwrap_or_default(),
+      requested_at: self.loan_offer.request_withdraw_at.unwrap_or_default(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  fn load_request(loan_offer: &LoanOfferAccount) -> RequestWithdrawCollateral<'static> {
+    let accounts = vec![signer(loan_offer.borrower), state(loan_offer_key(&loan_offer.borrower, OFFER_ID), loan_offer)];
+
+    load::<RequestWithdrawCollateral, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+  }
+
+  fn test_loan_offer() -> LoanOfferAccount {
+    loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT)
+  }
+
+  #[test]
+  fn records_request_with_timestamp() {
+    let mut context = load_request(&test_loan_offer());
+
+    context.request_withdraw_collateral(1_000).unwrap();
+
+    assert_eq!(context.loan_offer.request_withdraw_amount, Some(1_000));
+    assert_eq!(context.loan_offer.request_withdraw_at, Some(now()));
+    assert!(!context.loan_offer.request_withdraw_approved);
+  }
+
+  #[test]
+  fn rejects_second_pending_request() {
+    let mut loan_offer = test_loan_offer();
+    loan_offer.request_withdraw_amount = Some(1_000);
+    loan_offer.request_withdraw_at = Some(now());
+    let mut context = load_request(&loan_offer);
+
+    assert_error(context.request_withdraw_collateral(1_000), WithdrawRequestError::AlreadyPending);
+  }
+
+  #[test]
+  fn rejects_request_above_collateral_amount() {
+    let loan_offer = test_loan_offer();
+    let mut context = load_request(&loan_offer);
+
+    assert_error(context.request_withdraw_collateral(loan_offer.collateral_amount + 1), LoanOfferError::NotEnoughAmount);
+    assert_error(context.request_withdraw_collateral(0), WithdrawRequestError::ZeroAmount);
+  }
+}
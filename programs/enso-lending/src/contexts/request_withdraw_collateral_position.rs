@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED},
+  CollateralPositionError, CollateralPositionWithdrawRequestedEvent, LoanOfferAccount, LoanOfferError, LoanOfferStatus,
+  WithdrawRequestError
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct RequestWithdrawCollateralPosition<'info> {
+  pub borrower: Signer<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Matched
+    || loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::NotAvailableToWithdraw,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+}
+
+impl<'info> RequestWithdrawCollateralPosition<'info> {
+  /// Files a withdraw request against the basket position of `mint`. It shares the single
+  /// pending request slot with the primary collateral.
+  pub fn request_withdraw_collateral_position(&mut self, mint: Pubkey, withdraw_amount: u64) -> Result<()> {
+    let loan_offer = &mut self.loan_offer;
+
+    if loan_offer.request_withdraw_amount.is_some() {
+      return err!(WithdrawRequestError::AlreadyPending);
+    }
+
+    if withdraw_amount == 0 {
+      return err!(WithdrawRequestError::ZeroAmount);
+    }
+
+    let index = loan_offer
+      .find_collateral_position(&mint)
+      .ok_or(CollateralPositionError::PositionNotFound)?;
+    if withdraw_amount > loan_offer.collateral_positions[index].amount {
+      return err!(LoanOfferError::NotEnoughAmount);
+    }
+
+    loan_offer.request_withdraw_amount = Some(withdraw_amount);
+    loan_offer.request_withdraw_at = Some(Clock::get()?.unix_timestamp);
+    loan_offer.request_withdraw_approved = false;
+    loan_offer.request_withdraw_mint = Some(mint);
+
+    Ok(())
+  }
+
+  pub fn emit_event_request_withdraw_collateral_position(&self, label: String) -> Result<()> {
+    emit!(CollateralPositionWithdrawRequestedEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      borrower: self.borrower.key(),
+      mint: self.loan_offer.request_withdraw_mint.unwrap_or_default(),
+      withdraw_amount: self.loan_offer.request_withdraw_amount.unwrap_or_default(),
+      requested_at: self.loan_offer.request_withdraw_at.unwrap_or_default(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{test_utils::*, CollateralPosition};
+
+  const OFFER_ID: &str = "loan";
+  const AMOUNT: u64 = 100_000_000;
+
+  fn load_request(loan_offer: &LoanOfferAccount) -> RequestWithdrawCollateralPosition<'static> {
+    let accounts = vec![signer(loan_offer.borrower), state(loan_offer_key(&loan_offer.borrower, OFFER_ID), loan_offer)];
+
+    load::<RequestWithdrawCollateralPosition, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+  }
+
+  fn test_loan_offer(mint: Pubkey) -> LoanOfferAccount {
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT);
+    loan_offer.collateral_positions.push(CollateralPosition { mint, amount: AMOUNT });
+    loan_offer
+  }
+
+  #[test]
+  fn records_request_for_position_mint() {
+    let mint = Pubkey::new_unique();
+    let mut context = load_request(&test_loan_offer(mint));
+
+    context.request_withdraw_collateral_position(mint, AMOUNT / 4).unwrap();
+
+    assert_eq!(context.loan_offer.request_withdraw_amount, Some(AMOUNT / 4));
+    assert_eq!(context.loan_offer.request_withdraw_at, Some(now()));
+    assert_eq!(context.loan_offer.request_withdraw_mint, Some(mint));
+    assert!(!context.loan_offer.request_withdraw_approved);
+  }
+
+  #[test]
+  fn shares_pending_slot_with_primary_collateral() {
+    let mint = Pubkey::new_unique();
+    let mut loan_offer = test_loan_offer(mint);
+    loan_offer.request_withdraw_amount = Some(1_000);
+    loan_offer.request_withdraw_at = Some(now());
+    let mut context = load_request(&loan_offer);
+
+    assert_error(context.request_withdraw_collateral_position(mint, 1_000), WithdrawRequestError::AlreadyPending);
+  }
+
+  #[test]
+  fn rejects_request_outside_position() {
+    let mint = Pubkey::new_unique();
+    let mut context = load_request(&test_loan_offer(mint));
+
+    assert_error(
+      context.request_withdraw_collateral_position(Pubkey::new_unique(), 1_000),
+      CollateralPositionError::PositionNotFound
+    );
+    assert_error(context.request_withdraw_collateral_position(mint, AMOUNT + 1), LoanOfferError::NotEnoughAmount);
+    assert_error(context.request_withdraw_collateral_position(mint, 0), WithdrawRequestError::ZeroAmount);
+  }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{GlobalConfigError, RoleError, ENSO_SEED, GLOBAL_CONFIG_SEED, ROLE_ACCOUNT_SEED},
+  GlobalConfig, Role, RoleAccount, RoleRevokedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(role: Role)]
+pub struct RevokeRole<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    has_one = admin @ GlobalConfigError::InvalidAdmin,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[role as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+}
+
+impl<'info> RevokeRole<'info> {
+  pub fn revoke_role(&mut self, member: Pubkey) -> Result<()> {
+    let role_account = &mut self.role_account;
+
+    if !role_account.is_member(&member) {
+      return err!(RoleError::MemberNotFound);
+    }
+
+    role_account.members.retain(|key| key != &member);
+
+    Ok(())
+  }
+
+  pub fn emit_event_revoke_role(&self, label: String, member: Pubkey) -> Result<()> {
+    emit!(RoleRevokedEvent {
+      role: self.role_account.role,
+      member,
+      admin: self.admin.key(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  #[test]
+  fn admin_revokes_member() {
+    let admin = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+    let other_member = Pubkey::new_unique();
+    let accounts = vec![
+      signer(admin),
+      state(global_config_key(), &global_config(admin, Pubkey::new_unique())),
+      state(role_account_key(Role::Liquidator), &role_account(Role::Liquidator, vec![member, other_member])),
+    ];
+    let (mut context, _, _) = load::<RevokeRole, _>(accounts, &args(Role::Liquidator)).unwrap();
+
+    context.revoke_role(member).unwrap();
+    assert_eq!(context.role_account.members, vec![other_member]);
+
+    assert_error(context.revoke_role(member), RoleError::MemberNotFound);
+  }
+}
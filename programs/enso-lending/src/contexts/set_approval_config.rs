@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{ApprovalError, GlobalConfigError, APPROVAL_CONFIG_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, MAX_APPROVERS},
+  ApprovalConfig, ApprovalConfigUpdatedEvent, GlobalConfig
+};
+
+#[derive(Accounts)]
+pub struct SetApprovalConfig<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    has_one = admin @ GlobalConfigError::InvalidAdmin,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    init_if_needed,
+    payer = admin,
+    space = ApprovalConfig::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetApprovalConfig<'info> {
+  pub fn set_approval_config(
+    &mut self,
+    bumps: &SetApprovalConfigBumps,
+    approvers: Vec<Pubkey>,
+    threshold: u8
+  ) -> Result<()> {
+    if approvers.len() > MAX_APPROVERS {
+      return err!(ApprovalError::ApproverLimit);
+    }
+
+    if threshold == 0 || threshold as usize > approvers.len() {
+      return err!(ApprovalError::InvalidThreshold);
+    }
+
+    for (index, approver) in approvers.iter().enumerate() {
+      if approvers[index + 1..].contains(approver) {
+        return err!(ApprovalError::DuplicateApprover);
+      }
+    }
+
+    self.approval_config.set_inner(ApprovalConfig {
+      approvers,
+      threshold,
+      bump: bumps.approval_config,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_set_approval_config(&self, label: String) -> Result<()> {
+    emit!(ApprovalConfigUpdatedEvent {
+      admin: self.admin.key(),
+      approvers: self.approval_config.approvers.clone(),
+      threshold: self.approval_config.threshold,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{RoleError, ENSO_SEED, GLOBAL_CONFIG_SEED, ROLE_ACCOUNT_SEED},
+  GlobalConfig, GlobalPauseUpdatedEvent, PauseFlags, Role, RoleAccount
+};
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+  pub pauser: Signer<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Pauser as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&pauser.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+}
+
+impl<'info> SetGlobalPause<'info> {
+  pub fn set_global_pause(&mut self, pause_flags: PauseFlags) -> Result<()> {
+    self.global_config.pause_flags = pause_flags;
+
+    Ok(())
+  }
+
+  pub fn emit_event_set_global_pause(&self, label: String) -> Result<()> {
+    emit!(GlobalPauseUpdatedEvent {
+      pauser: self.pauser.key(),
+      pause_flags: self.global_config.pause_flags,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{test_utils::*, PauseAction};
+
+  fn pause_accounts(pauser: Pubkey, members: Vec<Pubkey>) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer(pauser),
+      state(role_account_key(Role::Pauser), &role_account(Role::Pauser, members)),
+      state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+    ]
+  }
+
+  #[test]
+  fn pauser_pauses_selected_actions() {
+    let pauser = Pubkey::new_unique();
+    let (mut context, _, _) = load::<SetGlobalPause, _>(pause_accounts(pauser, vec![pauser]), &[]).unwrap();
+    let pause_flags = PauseFlags {
+      new_loans: true,
+      liquidations: true,
+      ..PauseFlags::default()
+    };
+
+    context.set_global_pause(pause_flags).unwrap();
+
+    let pause_flags = context.global_config.pause_flags;
+    assert!(pause_flags.is_paused(PauseAction::NewLoans));
+    assert!(pause_flags.is_paused(PauseAction::Liquidations));
+    assert!(!pause_flags.is_paused(PauseAction::NewOffers));
+    assert!(!pause_flags.is_paused(PauseAction::Withdrawals));
+  }
+
+  #[test]
+  fn rejects_signer_without_pauser_role() {
+    let accounts = pause_accounts(Pubkey::new_unique(), vec![Pubkey::new_unique()]);
+
+    assert_error(load::<SetGlobalPause, _>(accounts, &[]).map(|_| ()), RoleError::Unauthorized);
+  }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{GlobalConfigError, ENSO_SEED, GLOBAL_CONFIG_SEED, SWAP_ADAPTER_SEED},
+  GlobalConfig, SetSwapAdapterEvent, SwapAccountSlot, SwapAdapter
+};
+
+#[derive(Accounts)]
+pub struct SetSwapAdapter<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    has_one = admin @ GlobalConfigError::InvalidAdmin,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  #[account(
+    init_if_needed,
+    payer = admin,
+    space = SwapAdapter::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SWAP_ADAPTER_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub swap_adapter: Account<'info, SwapAdapter>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetSwapAdapter<'info> {
+  pub fn set_swap_adapter(
+    &mut self,
+    bumps: &SetSwapAdapterBumps,
+    program_id: Pubkey,
+    instruction_discriminator: [u8; 8],
+    account_layout: Vec<SwapAccountSlot>
+  ) -> Result<()> {
+    SwapAdapter::validate_account_layout(&account_layout)?;
+
+    self.swap_adapter.set_inner(SwapAdapter {
+      program_id,
+      instruction_discriminator,
+      account_layout,
+      bump: bumps.swap_adapter,
+    });
+
+    Ok(())
+  }
+
+  pub fn emit_event_set_swap_adapter(&self, label: String) -> Result<()> {
+    emit!(SetSwapAdapterEvent {
+      admin: self.admin.key(),
+      program_id: self.swap_adapter.program_id,
+      instruction_discriminator: self.swap_adapter.instruction_discriminator,
+      account_layout: self.swap_adapter.account_layout.clone(),
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
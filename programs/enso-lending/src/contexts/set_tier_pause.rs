@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  common::{RoleError, SettingAccountError, ENSO_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED},
+  PauseFlags, Role, RoleAccount, SettingAccount, TierPauseUpdatedEvent
+};
+
+#[derive(Accounts)]
+#[instruction(tier_id: String)]
+pub struct SetTierPause<'info> {
+  pub pauser: Signer<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Pauser as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&pauser.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  #[account(
+    mut,
+    constraint = setting_account.tier_id == tier_id @ SettingAccountError::InvalidTierId,
+    seeds = [
+      ENSO_SEED.as_ref(), 
+      SETTING_ACCOUNT_SEED.as_ref(),
+      tier_id.as_bytes(), 
+      crate::ID.key().as_ref(), 
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+}
+
+impl<'info> SetTierPause<'info> {
+  pub fn set_tier_pause(&mut self, pause_flags: PauseFlags) -> Result<()> {
+    self.setting_account.pause_flags = pause_flags;
+
+    Ok(())
+  }
+
+  pub fn emit_event_set_tier_pause(&self, label: String) -> Result<()> {
+    emit!(TierPauseUpdatedEvent {
+      pauser: self.pauser.key(),
+      tier_id: self.setting_account.tier_id.clone(),
+      pause_flags: self.setting_account.pause_flags,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,369 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+  common::constant::{AUCTION_PROCEEDS_SEED, ENSO_SEED, LIQUIDATION_AUCTION_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, SETTING_ACCOUNT_SEED},
+  distribute_proceeds, emit_event_bad_debt, transfer_from_native_vault, AuctionError, LiquidationAuction, LiquidationAuctionSettledEvent, LoanOfferAccount, LoanOfferError,
+  LoanOfferStatus, SettingAccount, NATIVE_MINT
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct SettleLiquidationAuction<'info> {
+  pub caller: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the remaining collateral
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  /// CHECK: This is the account that paid for the auction accounts and receives their rent
+  #[account(mut)]
+  pub initiator: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Liquidating @ LoanOfferError::InvalidOfferStatus,
+    constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    has_one = initiator,
+    close = initiator,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LIQUIDATION_AUCTION_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump = auction.bump
+  )]
+  pub auction: Account<'info, LiquidationAuction>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: SystemAccount<'info>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      AUCTION_PROCEEDS_SEED.as_ref(),
+      auction.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub proceeds_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = setting_account.receiver,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleLiquidationAuction<'info> {
+  pub fn settle_liquidation_auction(&mut self, bumps: &SettleLiquidationAuctionBumps) -> Result<()> {
+    if !self.auction.is_settleable(Clock::get()?.unix_timestamp) {
+      return err!(AuctionError::StillRunning);
+    }
+
+    self.auction.proceeds = self.proceeds_vault.amount;
+    let was_defaulted = self.loan_offer.deficit_amount > 0;
+    let (lender_due, fee_due) = self.loan_offer.auction_dues()?;
+    let (lender_amount, fee_amount, borrower_surplus) = self.auction.settlement_amounts(lender_due, fee_due)?;
+
+    self.distribute_proceeds(lender_amount, fee_amount, borrower_surplus)?;
+
+    self.loan_offer.status = LoanOfferStatus::Finished;
+    let deficit_amount = self.loan_offer.record_deficit(lender_amount)?;
+
+    // Unsold collateral only goes back to the borrower once the lender is paid in full. Otherwise
+    // it stays in the vault of the defaulted loan until another auction sells it.
+    let (returned_collateral, retained_collateral) = if deficit_amount == 0 {
+      let returned_collateral = self.loan_offer.collateral_amount;
+      transfer_from_native_vault(
+        &self.collateral_vault,
+        &self.borrower.to_account_info(),
+        &self.system_program,
+        self.loan_offer.key(),
+        bumps.collateral_vault,
+        self.collateral_vault.lamports()
+      )?;
+      self.loan_offer.collateral_amount = 0;
+      (returned_collateral, 0)
+    } else {
+      (0, self.loan_offer.collateral_amount)
+    };
+
+    if deficit_amount > 0 && !was_defaulted {
+      self.setting_account.record_bad_debt(deficit_amount)?;
+      emit_event_bad_debt(String::from("bad_debt"), &self.loan_offer, &self.setting_account, deficit_amount)?;
+    }
+
+    self.emit_event_settle_liquidation_auction(
+      String::from("settle_liquidation_auction"),
+      lender_amount,
+      fee_amount,
+      borrower_surplus,
+      returned_collateral,
+      retained_collateral
+    )
+  }
+
+  fn distribute_proceeds(&self, lender_amount: u64, fee_amount: u64, borrower_surplus: u64) -> Result<()> {
+    let loan_offer = self.loan_offer.key();
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LIQUIDATION_AUCTION_SEED,
+      loan_offer.as_ref(),
+      program_id.as_ref(),
+      &[self.auction.bump],
+    ]];
+
+    distribute_proceeds(
+      self.proceeds_vault.to_account_info(),
+      &self.lend_mint_asset,
+      [
+        (self.lender_lend_ata.to_account_info(), lender_amount),
+        (self.hot_wallet_ata.to_account_info(), fee_amount),
+        (self.borrower_lend_ata.to_account_info(), borrower_surplus),
+      ],
+      self.auction.to_account_info(),
+      self.initiator.to_account_info(),
+      self.token_program.to_account_info(),
+      signer
+    )
+  }
+
+  fn emit_event_settle_liquidation_auction(
+    &self,
+    label: String,
+    lender_amount: u64,
+    fee_amount: u64,
+    borrower_surplus: u64,
+    returned_collateral: u64,
+    retained_collateral: u64
+  ) -> Result<()> {
+    emit!(LiquidationAuctionSettledEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      lender_amount,
+      fee_amount,
+      borrower_surplus,
+      returned_collateral_amount: returned_collateral,
+      retained_collateral_amount: retained_collateral,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  struct Fixture {
+    receiver: Pubkey,
+    loan_offer: LoanOfferAccount,
+    auction: LiquidationAuction,
+    /// Lend tokens raised by the bids.
+    proceeds: u64,
+  }
+
+  impl Fixture {
+    /// Auction that sold `sold_collateral` of the one SOL of collateral for `proceeds`.
+    fn new(sold_collateral: u64, proceeds: u64) -> Self {
+      let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+      loan_offer.status = LoanOfferStatus::Liquidating;
+      loan_offer.collateral_amount -= sold_collateral;
+      let mut auction = liquidation_auction(loan_offer_key(&loan_offer.borrower, OFFER_ID), Pubkey::new_unique());
+      auction.remaining_collateral -= sold_collateral;
+      auction.proceeds = proceeds;
+
+      Self {
+        receiver: Pubkey::new_unique(),
+        loan_offer,
+        auction,
+        proceeds,
+      }
+    }
+
+    fn load(&self) -> (SettleLiquidationAuction<'static>, SettleLiquidationAuctionBumps) {
+      let loan_offer_key = loan_offer_key(&self.loan_offer.borrower, OFFER_ID);
+      let auction_key = liquidation_auction_key(&loan_offer_key);
+      let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+      let proceeds_vault = pda(&[ENSO_SEED, AUCTION_PROCEEDS_SEED, auction_key.as_ref(), crate::ID.as_ref()]);
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let borrower = self.loan_offer.borrower;
+      let lender = self.loan_offer.lender;
+      let mut setting_account = setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT);
+      setting_account.receiver = self.receiver;
+
+      let accounts = vec![
+        signer(Pubkey::new_unique()),
+        wallet(borrower),
+        wallet(lender),
+        wallet(self.auction.initiator),
+        state(loan_offer_key, &self.loan_offer),
+        state(auction_key, &self.auction),
+        account(
+          collateral_vault,
+          System::id(),
+          Rent::default().minimum_balance(0) + self.loan_offer.collateral_amount,
+          vec![],
+          false,
+          false
+        ),
+        mint(lend_mint, spl_token::ID),
+        token_account(proceeds_vault, spl_token::ID, lend_mint, auction_key, self.proceeds),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        token_account(ata(&borrower, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, borrower, 0),
+        token_account(ata(&self.receiver, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.receiver, 0),
+        state(setting_account_key(TIER_ID), &setting_account),
+        program(spl_token::ID),
+        program(System::id()),
+      ];
+      let (context, bumps, _) = load::<SettleLiquidationAuction, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+      (context, bumps)
+    }
+  }
+
+  #[test]
+  fn covered_auction_pays_everyone_and_returns_unsold_collateral() {
+    let fixture = Fixture::new(900_000_000, 120_000_000);
+    let (mut context, bumps) = fixture.load();
+    let vault_lamports = context.collateral_vault.lamports();
+
+    context.settle_liquidation_auction(&bumps).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 109_500_000);
+    assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 1_000_000);
+    assert_eq!(token_amount(&context.borrower_lend_ata.to_account_info()), 9_500_000);
+    assert_eq!(context.borrower.lamports(), vault_lamports);
+    assert_eq!(context.proceeds_vault.to_account_info().lamports(), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Finished);
+    assert_eq!(context.loan_offer.deficit_amount, 0);
+    assert_eq!(context.loan_offer.collateral_amount, 0);
+  }
+
+  #[test]
+  fn rejects_settlement_while_auction_is_running() {
+    let (mut context, bumps) = Fixture::new(500_000_000, 66_000_000).load();
+
+    assert_error(context.settle_liquidation_auction(&bumps), AuctionError::StillRunning);
+  }
+
+  #[test]
+  fn short_auction_defaults_loan_and_keeps_unsold_collateral_in_vault() {
+    let mut fixture = Fixture::new(900_000_000, 100_000_000);
+    fixture.auction.started_at -= fixture.auction.duration + 1;
+    let (mut context, bumps) = fixture.load();
+    let vault_lamports = context.collateral_vault.lamports();
+
+    context.settle_liquidation_auction(&bumps).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 100_000_000);
+    assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 0);
+    assert_eq!(token_amount(&context.borrower_lend_ata.to_account_info()), 0);
+    assert_eq!(context.collateral_vault.lamports(), vault_lamports);
+    assert_eq!(context.borrower.lamports(), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Defaulted);
+    assert_eq!(context.loan_offer.collateral_amount, 100_000_000);
+    assert_eq!(context.loan_offer.deficit_amount, 9_500_000);
+    assert_eq!(context.setting_account.bad_debt_amount, 9_500_000);
+    assert_eq!(context.setting_account.defaulted_loan_count, 1);
+  }
+
+  #[test]
+  fn auction_of_retained_collateral_pays_down_the_deficit() {
+    let mut fixture = Fixture::new(900_000_000, 12_000_000);
+    fixture.loan_offer.deficit_amount = 9_500_000;
+    fixture.auction.started_at -= fixture.auction.duration + 1;
+    let (mut context, bumps) = fixture.load();
+    let vault_lamports = context.collateral_vault.lamports();
+
+    context.settle_liquidation_auction(&bumps).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 9_500_000);
+    assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 0);
+    assert_eq!(token_amount(&context.borrower_lend_ata.to_account_info()), 2_500_000);
+    assert_eq!(context.borrower.lamports(), vault_lamports);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Finished);
+    assert_eq!(context.loan_offer.deficit_amount, 0);
+    assert_eq!(context.setting_account.defaulted_loan_count, 0);
+  }
+
+  #[test]
+  fn short_auction_of_retained_collateral_keeps_the_loan_defaulted() {
+    let mut fixture = Fixture::new(500_000_000, 4_000_000);
+    fixture.loan_offer.deficit_amount = 9_500_000;
+    fixture.auction.started_at -= fixture.auction.duration + 1;
+    let (mut context, bumps) = fixture.load();
+
+    context.settle_liquidation_auction(&bumps).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 4_000_000);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Defaulted);
+    assert_eq!(context.loan_offer.collateral_amount, 500_000_000);
+    assert_eq!(context.loan_offer.deficit_amount, 5_500_000);
+    assert_eq!(context.setting_account.bad_debt_amount, 0);
+    assert_eq!(context.setting_account.defaulted_loan_count, 0);
+  }
+}
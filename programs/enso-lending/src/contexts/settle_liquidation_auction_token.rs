@@ -0,0 +1,250 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+  common::constant::{AUCTION_PROCEEDS_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, LIQUIDATION_AUCTION_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED},
+  distribute_proceeds, emit_event_bad_debt, AuctionError, LiquidationAuction, LiquidationAuctionSettledEvent, LoanOfferAccount, LoanOfferError,
+  LoanOfferStatus, SettingAccount
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct SettleLiquidationAuctionToken<'info> {
+  pub caller: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the remaining collateral
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  /// CHECK: This is the account that paid for the auction accounts and receives their rent
+  #[account(mut)]
+  pub initiator: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::Liquidating @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    has_one = initiator,
+    close = initiator,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LIQUIDATION_AUCTION_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump = auction.bump
+  )]
+  pub auction: Account<'info, LiquidationAuction>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = collateral_token_program
+  )]
+  pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      AUCTION_PROCEEDS_SEED.as_ref(),
+      auction.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub proceeds_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_lend_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = setting_account.receiver,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SettleLiquidationAuctionToken<'info> {
+  pub fn settle_liquidation_auction(&mut self) -> Result<()> {
+    if !self.auction.is_settleable(Clock::get()?.unix_timestamp) {
+      return err!(AuctionError::StillRunning);
+    }
+
+    self.auction.proceeds = self.proceeds_vault.amount;
+    let was_defaulted = self.loan_offer.deficit_amount > 0;
+    let (lender_due, fee_due) = self.loan_offer.auction_dues()?;
+    let (lender_amount, fee_amount, borrower_surplus) = self.auction.settlement_amounts(lender_due, fee_due)?;
+
+    self.distribute_proceeds(lender_amount, fee_amount, borrower_surplus)?;
+
+    self.loan_offer.status = LoanOfferStatus::Finished;
+    let deficit_amount = self.loan_offer.record_deficit(lender_amount)?;
+
+    // Unsold collateral only goes back to the borrower once the lender is paid in full. Otherwise
+    // it stays in the vault of the defaulted loan until another auction sells it.
+    let (returned_collateral, retained_collateral) = if deficit_amount == 0 {
+      let returned_collateral = self.collateral_vault.amount;
+      self.release_collateral(returned_collateral)?;
+      self.loan_offer.collateral_amount = 0;
+      (returned_collateral, 0)
+    } else {
+      (0, self.collateral_vault.amount)
+    };
+
+    if deficit_amount > 0 && !was_defaulted {
+      self.setting_account.record_bad_debt(deficit_amount)?;
+      emit_event_bad_debt(String::from("bad_debt"), &self.loan_offer, &self.setting_account, deficit_amount)?;
+    }
+
+    self.emit_event_settle_liquidation_auction(
+      String::from("settle_liquidation_auction_token"),
+      lender_amount,
+      fee_amount,
+      borrower_surplus,
+      returned_collateral,
+      retained_collateral
+    )
+  }
+
+  fn distribute_proceeds(&self, lender_amount: u64, fee_amount: u64, borrower_surplus: u64) -> Result<()> {
+    let loan_offer = self.loan_offer.key();
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LIQUIDATION_AUCTION_SEED,
+      loan_offer.as_ref(),
+      program_id.as_ref(),
+      &[self.auction.bump],
+    ]];
+
+    distribute_proceeds(
+      self.proceeds_vault.to_account_info(),
+      &self.lend_mint_asset,
+      [
+        (self.lender_lend_ata.to_account_info(), lender_amount),
+        (self.hot_wallet_ata.to_account_info(), fee_amount),
+        (self.borrower_lend_ata.to_account_info(), borrower_surplus),
+      ],
+      self.auction.to_account_info(),
+      self.initiator.to_account_info(),
+      self.token_program.to_account_info(),
+      signer
+    )
+  }
+
+  fn release_collateral(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    if amount > 0 {
+      let transfer_accounts = TransferChecked {
+        from: self.collateral_vault.to_account_info(),
+        mint: self.collateral_mint_asset.to_account_info(),
+        to: self.borrower_collateral_ata.to_account_info(),
+        authority: self.loan_offer.to_account_info(),
+      };
+      transfer_checked(
+        CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), transfer_accounts, signer),
+        amount,
+        self.collateral_mint_asset.decimals,
+      )?;
+    }
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  fn emit_event_settle_liquidation_auction(
+    &self,
+    label: String,
+    lender_amount: u64,
+    fee_amount: u64,
+    borrower_surplus: u64,
+    returned_collateral: u64,
+    retained_collateral: u64
+  ) -> Result<()> {
+    emit!(LiquidationAuctionSettledEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      lender_amount,
+      fee_amount,
+      borrower_surplus,
+      returned_collateral_amount: returned_collateral,
+      retained_collateral_amount: retained_collateral,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{
+    constant::{ASSET_CONFIG_SEED, AUCTION_PROCEEDS_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LIQUIDATION_AUCTION_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED},
+    PauseError
+  },
+  mul_div, to_u64, AssetConfig, GlobalConfig, LiquidationAuction, LiquidationAuctionStartedEvent, LoanOfferAccount, LoanOfferError,
+  LoanOfferStatus, MathError, PauseAction, Rounding, SettingAccount, BPS_DENOMINATOR
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct StartLiquidationAuction<'info> {
+  #[account(mut)]
+  pub initiator: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds
+  pub borrower: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::FundTransferred
+    || (loan_offer.status == LoanOfferStatus::Defaulted && loan_offer.collateral_amount > 0) @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    init,
+    payer = initiator,
+    space = LiquidationAuction::INIT_SPACE + 8,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LIQUIDATION_AUCTION_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub auction: Account<'info, LiquidationAuction>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+  #[account(
+    init,
+    payer = initiator,
+    token::mint = lend_mint_asset,
+    token::authority = auction,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      AUCTION_PROCEEDS_SEED.as_ref(),
+      auction.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub proceeds_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> StartLiquidationAuction<'info> {
+  pub fn start_liquidation_auction(
+    &mut self,
+    bumps: &StartLiquidationAuctionBumps,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    // Collateral left over from an earlier auction of a defaulted loan can be auctioned again
+    // without the loan being unhealthy.
+    if self.loan_offer.status == LoanOfferStatus::FundTransferred {
+      self.loan_offer.validate_liquidatable(
+        current_timestamp,
+        &self.collateral_asset_config,
+        &self.collateral_price_feed_account,
+        &self.lend_asset_config,
+        &self.lend_price_feed_account,
+        &self.setting_account.risk_parameters,
+        basket_accounts
+      )?;
+    }
+    let (lender_due, fee_due) = self.loan_offer.auction_dues()?;
+
+    let reference_lot_price = self.loan_offer.collateral_value_in_lend(
+      self.loan_offer.collateral_amount,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account
+    )?;
+    let auction_parameters = self.global_config.auction_parameters;
+    let bps = BPS_DENOMINATOR as u128;
+
+    self.auction.set_inner(LiquidationAuction {
+      loan_offer: self.loan_offer.key(),
+      initiator: self.initiator.key(),
+      collateral_amount: self.loan_offer.collateral_amount,
+      remaining_collateral: self.loan_offer.collateral_amount,
+      start_lot_price: to_u64(mul_div(
+        reference_lot_price,
+        bps + auction_parameters.start_premium_bps as u128,
+        bps,
+        Rounding::Up
+      )?)?,
+      floor_lot_price: to_u64(mul_div(
+        reference_lot_price,
+        bps - auction_parameters.floor_discount_bps as u128,
+        bps,
+        Rounding::Down
+      )?)?,
+      started_at: current_timestamp,
+      duration: auction_parameters.duration,
+      debt_amount: lender_due.checked_add(fee_due).ok_or(MathError::Overflow)?,
+      proceeds: 0,
+      bump: bumps.auction,
+    });
+
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.liquidating_at = Some(current_timestamp as u64);
+    loan_offer.status = LoanOfferStatus::Liquidating;
+    loan_offer.clear_withdraw_request();
+
+    Ok(())
+  }
+
+  pub fn emit_event_start_liquidation_auction(&self, label: String) -> Result<()> {
+    emit!(LiquidationAuctionStartedEvent {
+      initiator: self.initiator.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      collateral_amount: self.auction.collateral_amount,
+      start_lot_price: self.auction.start_lot_price,
+      floor_lot_price: self.auction.floor_lot_price,
+      debt_amount: self.auction.debt_amount,
+      ends_at: self.auction.ends_at(),
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
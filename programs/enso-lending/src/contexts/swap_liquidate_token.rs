@@ -0,0 +1,455 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+  close_account, CloseAccount, Mint, TokenAccount, TokenInterface
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{
+    constant::{
+      ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED,
+      SWAP_ADAPTER_SEED, SWAP_PROCEEDS_SEED
+    },
+    PauseError, SwapAdapterError
+  },
+  distribute_proceeds, emit_event_bad_debt, execute_swap, mul_div, split_liquidation_proceeds, to_u64, validate_vault_balance, AssetConfig, GlobalConfig,
+  LoanOfferAccount, LoanOfferError, LoanOfferStatus, PauseAction, Rounding, SettingAccount, SwapAccounts, SwapAdapter,
+  SwapLiquidationEvent, BPS_DENOMINATOR
+};
+
+#[derive(Accounts)]
+#[instruction(offer_id: String)]
+pub struct SwapLiquidateToken<'info> {
+  #[account(mut)]
+  pub liquidator: Signer<'info>,
+  /// CHECK: This is the account used to make a seeds and receive the vault rent
+  #[account(mut)]
+  pub borrower: UncheckedAccount<'info>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    mut,
+    constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::InvalidOfferStatus,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Box<Account<'info, LoanOfferAccount>>,
+  #[account(
+    constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+  )]
+  pub collateral_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    mut,
+    token::mint = collateral_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = collateral_token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+  )]
+  pub lend_mint_asset: Box<InterfaceAccount<'info, Mint>>,
+  #[account(
+    init,
+    payer = liquidator,
+    token::mint = lend_mint_asset,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SWAP_PROCEEDS_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub swap_proceeds: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_lend_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = lend_mint_asset,
+    associated_token::authority = setting_account.receiver,
+    associated_token::token_program = token_program
+  )]
+  pub hot_wallet_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+  #[account(
+    mut,
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Box<Account<'info, SettingAccount>>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Liquidations) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Box<Account<'info, GlobalConfig>>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SWAP_ADAPTER_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = swap_adapter.bump
+  )]
+  pub swap_adapter: Box<Account<'info, SwapAdapter>>,
+  /// CHECK: Only invoked after matching the program id of the configured swap adapter
+  #[account(
+    executable,
+    constraint = swap_program.key() == swap_adapter.program_id @ SwapAdapterError::InvalidSwapProgram
+  )]
+  pub swap_program: UncheckedAccount<'info>,
+  pub lend_price_feed_account: Box<Account<'info, PriceUpdateV2>>,
+  pub collateral_price_feed_account: Box<Account<'info, PriceUpdateV2>>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Box<Account<'info, AssetConfig>>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Box<Account<'info, AssetConfig>>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapLiquidateToken<'info> {
+  /// The first remaining accounts fill the `Extra` slots of the swap adapter, the rest are
+  /// the basket positions of the loan.
+  pub fn swap_liquidate(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    let (swap_accounts, basket_accounts) = remaining_accounts.split_at(
+      self.swap_adapter.extra_account_count().min(remaining_accounts.len())
+    );
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    self.loan_offer.validate_liquidatable(
+      current_timestamp,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      &self.setting_account.risk_parameters,
+      basket_accounts
+    )?;
+
+    let collateral_amount = self.loan_offer.collateral_amount;
+    validate_vault_balance(self.collateral_vault.amount, collateral_amount)?;
+    let minimum_out = self.minimum_out(collateral_amount)?;
+
+    // Anything donated to the vault is sold along with the collateral and ends up in the
+    // borrower surplus, which lets the vault be closed afterwards.
+    self.swap(swap_accounts, self.collateral_vault.amount, minimum_out)?;
+
+    self.swap_proceeds.reload()?;
+    let proceeds = self.swap_proceeds.amount;
+    if proceeds < minimum_out {
+      return err!(SwapAdapterError::MinimumOutNotMet);
+    }
+
+    let fee_due = self.loan_offer.borrower_fee_amount()? + self.loan_offer.lender_fee_amount()?;
+    let (lender_amount, fee_amount, borrower_surplus) = split_liquidation_proceeds(
+      proceeds,
+      self.loan_offer.lender_repay_amount()?,
+      fee_due
+    )?;
+    self.distribute_proceeds(lender_amount, fee_amount, borrower_surplus)?;
+
+    // The proceeds are paid out above, so the loan is settled here and never goes through
+    // `system_finish_loan_offer`.
+    let loan_offer = &mut self.loan_offer;
+    loan_offer.collateral_amount = 0;
+    loan_offer.liquidating_at = Some(current_timestamp as u64);
+    loan_offer.status = LoanOfferStatus::Finished;
+    loan_offer.clear_withdraw_request();
+
+    let deficit_amount = loan_offer.record_deficit(lender_amount)?;
+    if deficit_amount > 0 {
+      self.setting_account.record_bad_debt(deficit_amount)?;
+      emit_event_bad_debt(String::from("bad_debt"), &self.loan_offer, &self.setting_account, deficit_amount)?;
+    }
+
+    self.emit_event_swap_liquidate(
+      String::from("swap_liquidate_token"),
+      collateral_amount,
+      minimum_out,
+      proceeds,
+      lender_amount,
+      fee_amount,
+      borrower_surplus
+    )
+  }
+
+  /// Oracle value of the collateral in the lend asset, less the configured slippage band.
+  fn minimum_out(&self, collateral_amount: u64) -> Result<u64> {
+    let expected_out = self.loan_offer.collateral_value_in_lend(
+      collateral_amount,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account
+    )?;
+
+    to_u64(mul_div(
+      expected_out,
+      (BPS_DENOMINATOR - self.global_config.liquidation_slippage_bps) as u128,
+      BPS_DENOMINATOR as u128,
+      Rounding::Up
+    )?)
+  }
+
+  fn swap(&self, swap_accounts: &[AccountInfo<'info>], collateral_amount: u64, minimum_out: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    execute_swap(
+      &self.swap_adapter,
+      &self.swap_program,
+      &SwapAccounts {
+        authority: self.loan_offer.to_account_info(),
+        source: self.collateral_vault.to_account_info(),
+        destination: self.swap_proceeds.to_account_info(),
+        source_mint: self.collateral_mint_asset.to_account_info(),
+        destination_mint: self.lend_mint_asset.to_account_info(),
+        source_token_program: self.collateral_token_program.to_account_info(),
+        destination_token_program: self.token_program.to_account_info(),
+      },
+      swap_accounts,
+      collateral_amount,
+      minimum_out,
+      signer
+    )?;
+
+    let close_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.collateral_token_program.to_account_info(), close_accounts, signer))
+  }
+
+  fn distribute_proceeds(&self, lender_amount: u64, fee_amount: u64, borrower_surplus: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    distribute_proceeds(
+      self.swap_proceeds.to_account_info(),
+      &self.lend_mint_asset,
+      [
+        (self.lender_lend_ata.to_account_info(), lender_amount),
+        (self.hot_wallet_ata.to_account_info(), fee_amount),
+        (self.borrower_lend_ata.to_account_info(), borrower_surplus),
+      ],
+      self.loan_offer.to_account_info(),
+      self.liquidator.to_account_info(),
+      self.token_program.to_account_info(),
+      signer
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn emit_event_swap_liquidate(
+    &self,
+    label: String,
+    collateral_amount: u64,
+    minimum_out: u64,
+    proceeds: u64,
+    lender_amount: u64,
+    fee_amount: u64,
+    borrower_surplus: u64
+  ) -> Result<()> {
+    emit!(SwapLiquidationEvent {
+      liquidator: self.liquidator.key(),
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      collateral_amount,
+      minimum_out,
+      proceeds,
+      lender_amount,
+      fee_amount,
+      borrower_surplus,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+
+  struct Fixture {
+    loan_offer: LoanOfferAccount,
+    collateral_price: i64,
+  }
+
+  impl Fixture {
+    /// Loan of 100 lend tokens against 1,000 collateral tokens priced at `collateral_price`.
+    fn new(collateral_price: i64) -> Self {
+      Self {
+        loan_offer: loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()),
+        collateral_price,
+      }
+    }
+
+    fn load(&self) -> (SwapLiquidateToken<'static>, &'static [AccountInfo<'static>]) {
+      let borrower = self.loan_offer.borrower;
+      let lender = self.loan_offer.lender;
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let collateral_mint = self.loan_offer.collateral_mint_token;
+      let loan_offer_key = loan_offer_key(&borrower, OFFER_ID);
+      let pool_authority = Pubkey::new_unique();
+      let setting_account = setting_account(Pubkey::new_unique(), lend_mint, collateral_mint);
+      let receiver = setting_account.receiver;
+
+      let accounts = vec![
+        signer(Pubkey::new_unique()),
+        wallet(borrower),
+        wallet(lender),
+        state(loan_offer_key, &self.loan_offer),
+        mint(collateral_mint, spl_token::ID),
+        token_account(
+          pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]),
+          spl_token::ID,
+          collateral_mint,
+          loan_offer_key,
+          self.loan_offer.collateral_amount
+        ),
+        mint(lend_mint, spl_token::ID),
+        empty(pda(&[ENSO_SEED, SWAP_PROCEEDS_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()])),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        token_account(ata(&borrower, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, borrower, 0),
+        token_account(ata(&receiver, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, receiver, 0),
+        state(setting_account_key(TIER_ID), &setting_account),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        state(swap_adapter_key(), &mock_swap_adapter()),
+        program(MOCK_SWAP_PROGRAM_ID),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, self.collateral_price, PRICE_EXPONENT),
+        state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&collateral_mint), &asset_config(collateral_mint, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+        program(spl_token::ID),
+        program(spl_token::ID),
+        program(System::id()),
+        token_account(Pubkey::new_unique(), spl_token::ID, collateral_mint, pool_authority, 0),
+        token_account(Pubkey::new_unique(), spl_token::ID, lend_mint, pool_authority, 1_000_000_000),
+        wallet(pool_authority),
+      ];
+
+      let (context, _, remaining_accounts) = load::<SwapLiquidateToken, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+      (context, remaining_accounts)
+    }
+
+    fn liquidate(&self, swap_out: u64) -> Result<SwapLiquidateToken<'static>> {
+      set_swap_out(swap_out);
+      let (mut context, remaining_accounts) = self.load();
+      context.swap_liquidate(remaining_accounts)?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn sells_collateral_vault_and_finishes_loan() {
+    // 0.12 USD a token values the collateral at 120 lend tokens.
+    let fixture = Fixture::new(12_000_000);
+    let context = fixture.liquidate(120_000_000).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 109_500_000);
+    assert_eq!(token_amount(&context.hot_wallet_ata.to_account_info()), 1_000_000);
+    assert_eq!(token_amount(&context.borrower_lend_ata.to_account_info()), 9_500_000);
+    assert_eq!(context.collateral_vault.to_account_info().lamports(), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Finished);
+    assert_eq!(context.loan_offer.collateral_amount, 0);
+  }
+
+  #[test]
+  fn records_deficit_when_proceeds_fall_short() {
+    let fixture = Fixture::new(10_000_000);
+    let context = fixture.liquidate(99_000_000).unwrap();
+
+    assert_eq!(token_amount(&context.lender_lend_ata.to_account_info()), 99_000_000);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Defaulted);
+    assert_eq!(context.loan_offer.deficit_amount, 10_500_000);
+    assert_eq!(context.setting_account.bad_debt_amount, 10_500_000);
+  }
+
+  #[test]
+  fn rejects_swap_below_oracle_minimum_out() {
+    let fixture = Fixture::new(12_000_000);
+
+    assert_error(fixture.liquidate(118_799_999).map(|_| ()), SwapAdapterError::MinimumOutNotMet);
+  }
+}
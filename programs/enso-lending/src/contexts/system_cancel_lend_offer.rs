@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::{
   common::{
-    LendOfferError, LendOfferStatus
-  }, states::lend_offer::LendOfferAccount, LendOfferCanceledEvent, SettingAccount, ENSO_SEED, LEND_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
+    LendOfferError, LendOfferStatus, RoleError
+  }, states::lend_offer::LendOfferAccount, refund_escrow, LendOfferCanceledEvent, OrderBook, Role, RoleAccount, SettingAccount, ENSO_SEED, LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED, ORDER_BOOK_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
 };
 
 #[derive(Accounts)]
@@ -14,22 +14,25 @@ use crate::{
 pub struct SystemCancelLendOffer<'info> {
   /// CHECK: This account is used to check the validate of wallet receive back lend amount
   #[account(
+    mut,
     constraint = lender.key() == lend_offer.lender @ LendOfferError::InvalidLender
   )]
   pub lender: AccountInfo<'info>,
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = lender
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
   )]
-  pub lender_ata_asset: Account<'info, TokenAccount>,
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
   #[account(
     constraint = mint_asset.key() == setting_account.lend_mint_asset @ LendOfferError::InvalidMintAsset,
   )]
-  pub mint_asset: Account<'info, Mint>,
+  pub mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     mut,
-    constraint = lend_offer.status == LendOfferStatus::Canceling @ LendOfferError::InvalidOfferStatus,
+    constraint = lend_offer.status == LendOfferStatus::Created
+    || lend_offer.status == LendOfferStatus::Canceling @ LendOfferError::InvalidOfferStatus,
     seeds = [
       ENSO_SEED.as_ref(),
       LEND_OFFER_ACCOUNT_SEED.as_ref(),  
@@ -40,6 +43,17 @@ pub struct SystemCancelLendOffer<'info> {
     bump = lend_offer.bump
   )]
   pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_ESCROW_SEED.as_ref(),
+      lend_offer.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
   #[account(
     seeds = [
         ENSO_SEED.as_ref(), 
@@ -50,39 +64,93 @@ pub struct SystemCancelLendOffer<'info> {
     bump = setting_account.bump
   )]
   pub setting_account: Account<'info, SettingAccount>,
+  /// CHECK: This is the order book of the tier, only updated once it has been set up
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ORDER_BOOK_SEED.as_ref(),
+      tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub order_book: UncheckedAccount<'info>,
   #[account(mut)]
   pub system: Signer<'info>,
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = system
+    associated_token::authority = system,
+    associated_token::token_program = token_program
+  )]
+  pub system_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Funder as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
   )]
-  pub system_ata: Account<'info, TokenAccount>,
-  pub token_program: Program<'info, Token>
+  pub role_account: Account<'info, RoleAccount>,
+  pub token_program: Interface<'info, TokenInterface>
 }
 
 impl<'info> SystemCancelLendOffer<'info> {
   pub fn system_cancel_lend_offer(&mut self, lend_amount: u64, waiting_interest: u64) -> Result<()>  {
-    if lend_amount != self.lend_offer.amount {
+    if lend_amount != self.lend_offer.remaining_amount {
       return err!(LendOfferError::InvalidLendAmount);
     }
 
     let total_repay = self.get_total_repay(lend_amount, waiting_interest);
 
-    if total_repay > self.system_ata.amount {
+    if waiting_interest > self.system_ata.amount {
       return err!(LendOfferError::NotEnoughAmount);
     }
 
-    self.transfer_back_lend_asset(total_repay)?;
+    self.refund_escrow(lend_amount)?;
+    if waiting_interest > 0 {
+      self.transfer_waiting_interest(waiting_interest)?;
+    }
 
+    self.lend_offer.remaining_amount = 0;
     self.lend_offer.status = LendOfferStatus::Canceled;
+    let lend_offer = self.lend_offer.key();
+    OrderBook::update_if_initialized(&self.order_book, |order_book| Ok(order_book.remove(&lend_offer)))?;
 
     self.emit_event_cancel_lend_offer(String::from("system_cancel_lend_offer"), total_repay)?;
 
     Ok(())
   }
 
-  fn transfer_back_lend_asset(&mut self, total_repay: u64) -> Result<()> {
+  fn refund_escrow(&mut self, refund_amount: u64) -> Result<()> {
+    let lender = self.lend_offer.lender;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LEND_OFFER_ACCOUNT_SEED,
+      lender.as_ref(),
+      self.lend_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.lend_offer.bump],
+    ]];
+
+    refund_escrow(
+      &mut self.lend_escrow,
+      &self.mint_asset,
+      self.lender_ata_asset.to_account_info(),
+      self.lend_offer.to_account_info(),
+      self.lender.to_account_info(),
+      self.token_program.to_account_info(),
+      signer,
+      refund_amount
+    )
+  }
+
+  fn transfer_waiting_interest(&mut self, waiting_interest: u64) -> Result<()> {
     let ctx = CpiContext::new(
       self.token_program.to_account_info(), 
       TransferChecked {
@@ -95,7 +163,7 @@ impl<'info> SystemCancelLendOffer<'info> {
 
     transfer_checked(
         ctx,
-        total_repay,
+        waiting_interest,
         self.mint_asset.decimals,
     )
   }
@@ -105,8 +173,8 @@ impl<'info> SystemCancelLendOffer<'info> {
       lender: self.lender.key(),
       amount: total_repay,
       duration: self.lend_offer.duration,
-      interest: self.lend_offer.interest,
-      lender_fee_percent: self.lend_offer.lender_fee_percent,
+      interest_bps: self.lend_offer.interest_bps,
+      lender_fee_bps: self.lend_offer.lender_fee_bps,
       offer_id: self.lend_offer.offer_id.clone()
     });
 
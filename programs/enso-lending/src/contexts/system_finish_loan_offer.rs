@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::{
   common::{
-    constant::LoanOfferStatus, RepayOfferError
-  }, duration_to_year, states::loan_offer::LoanOfferAccount, SystemFinishLoanOfferEvent, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED
+    constant::LoanOfferStatus, MathError, RepayOfferError, RoleError
+  }, states::loan_offer::LoanOfferAccount, deposit_reserve, reserve_amount, to_u64, ApprovalConfig, Proposal, ProposalAction, ReserveFund, Role, RoleAccount, SystemFinishLoanOfferEvent, APPROVAL_CONFIG_SEED, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, PROPOSAL_SEED, RESERVE_FUND_SEED, RESERVE_VAULT_SEED, ROLE_ACCOUNT_SEED
 };
 
 #[derive(Accounts)]
@@ -14,13 +14,14 @@ pub struct SystemFinishLoanOffer<'info> {
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = system
+    associated_token::authority = system,
+    associated_token::token_program = token_program
   )]
-  pub system_ata: Account<'info, TokenAccount>,
+  pub system_ata: InterfaceAccount<'info, TokenAccount>,
   #[account(
     constraint = mint_asset.key() == loan_offer.lend_mint_token @ RepayOfferError::InvalidMintAsset,
   )]
-  pub mint_asset: Account<'info, Mint>,
+  pub mint_asset: InterfaceAccount<'info, Mint>,
   /// CHECK: This account is used to validate the wallet receive back lend amount
   #[account(
     constraint = lender.key() == loan_offer.lender @ RepayOfferError::InvalidLender
@@ -29,9 +30,10 @@ pub struct SystemFinishLoanOffer<'info> {
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = lender
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
   )]
-  pub lender_ata_asset: Account<'info, TokenAccount>,
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
   /// CHECK: This is the account used to receive back the collateral amount
   #[account(mut)]
   pub borrower: AccountInfo<'info>,
@@ -49,13 +51,76 @@ pub struct SystemFinishLoanOffer<'info> {
     bump = loan_offer.bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
-  pub token_program: Program<'info, Token>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Settler as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal.proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = proposal.bump
+  )]
+  pub proposal: Account<'info, Proposal>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_FUND_SEED.as_ref(),
+      mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = reserve_fund.bump
+  )]
+  pub reserve_fund: Option<Account<'info, ReserveFund>>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      RESERVE_VAULT_SEED.as_ref(),
+      mint_asset.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
+  )]
+  pub reserve_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> SystemFinishLoanOffer<'info> {
   pub fn system_finish_loan_offer(&mut self, loan_amount: u64, waiting_interest: u64) -> Result<()>  {
-    let total_repay_to_lender = self.get_total_repay(loan_amount, waiting_interest);
+    self.proposal.execute(
+      &self.approval_config,
+      &ProposalAction::SystemFinishLoanOffer {
+        loan_offer: self.loan_offer.key(),
+        loan_amount,
+        interest_amount: waiting_interest,
+      },
+      self.system.key(),
+    )?;
+
+    let total_repay_to_lender = self.get_total_repay(loan_amount, waiting_interest)?;
 
     // TODO: Allow system finish contract at any time
     // let current_timestamp = Clock::get().unwrap().unix_timestamp;
@@ -66,6 +131,19 @@ impl<'info> SystemFinishLoanOffer<'info> {
     // }
 
     self.transfer_asset_to_lender(loan_amount, total_repay_to_lender)?;
+
+    let reserve_amount = reserve_amount(&self.reserve_fund, &self.reserve_vault, self.loan_offer.lender_fee_amount()?)?;
+    deposit_reserve(
+      &mut self.reserve_fund,
+      &self.reserve_vault,
+      self.system_ata.to_account_info(),
+      self.system.to_account_info(),
+      &self.mint_asset,
+      self.token_program.to_account_info(),
+      &self.loan_offer.offer_id,
+      reserve_amount
+    )?;
+
     self.loan_offer.status = LoanOfferStatus::Finished;
 
     self.emit_event_system_finish_loan_offer(
@@ -116,7 +194,7 @@ impl<'info> SystemFinishLoanOffer<'info> {
       system: self.system.key(),
       lender: self.lender.key(),
       borrower: self.borrower.key(),
-      interest: self.loan_offer.interest,
+      interest_bps: self.loan_offer.interest_bps,
       loan_amount,
       loan_offer_id: self.loan_offer.offer_id.clone(),
       tier_id: self.loan_offer.tier_id.clone(),
@@ -128,16 +206,14 @@ impl<'info> SystemFinishLoanOffer<'info> {
     Ok(())
   }
 
-  fn get_total_repay(&self, loan_amount: u64, waiting_interest: u64) -> u64 {
-    let loan_interest_percent = self.loan_offer.interest / 100.0;
-
-    let lender_fee_percent = self.loan_offer.lender_fee_percent / 100.0;
+  fn get_total_repay(&self, loan_amount: u64, waiting_interest: u64) -> Result<u64> {
+    let interest_amount = self.loan_offer.interest_amount()?;
+    let lender_fee_amount = self.loan_offer.lender_fee_amount()?;
 
-    let time_borrowed = duration_to_year(self.loan_offer.duration);
+    let total_repay = (loan_amount as u128 + interest_amount as u128 + waiting_interest as u128)
+      .checked_sub(lender_fee_amount as u128)
+      .ok_or(MathError::Overflow)?;
 
-    let interest_loan_amount = (loan_amount as f64) * loan_interest_percent * time_borrowed;
-    let lender_fee_amount = lender_fee_percent * (interest_loan_amount as f64);
-
-    return (loan_amount as f64 + interest_loan_amount + waiting_interest as f64 - lender_fee_amount) as u64;
+    to_u64(total_repay)
   }
-}
\ No newline at end of file
+}
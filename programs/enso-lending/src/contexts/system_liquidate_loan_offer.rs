@@ -1,32 +1,31 @@
-use std::str::FromStr;
-
 use crate::{
     common::{
-        constant::{LoanOfferStatus, OPERATE_SYSTEM_PUBKEY},
-        LiquidateOfferError,
-    }, duration_to_year, states::loan_offer::LoanOfferAccount, LiquidatedCollateralEvent, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED
+        constant::LoanOfferStatus,
+        LiquidateOfferError, RoleError,
+    }, states::loan_offer::LoanOfferAccount, emit_event_bad_debt, validate_within_band, ApprovalConfig, AssetConfig, GlobalConfig,
+    LiquidatedCollateralEvent, Proposal, ProposalAction, Role, RoleAccount, SettingAccount, APPROVAL_CONFIG_SEED, ASSET_CONFIG_SEED,
+    ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, PROPOSAL_SEED, ROLE_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 #[derive(Accounts)]
 #[instruction(loan_offer_id: String)]
 pub struct SystemLiquidateLoanOffer<'info> {
-  #[account(
-    mut,
-    constraint = system.key() == Pubkey::from_str(OPERATE_SYSTEM_PUBKEY).unwrap() @ LiquidateOfferError::InvalidSystem
-  )]
+  #[account(mut)]
   pub system: Signer<'info>,
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = system
+    associated_token::authority = system,
+    associated_token::token_program = token_program
   )]
-  pub system_ata: Account<'info, TokenAccount>,
+  pub system_ata: InterfaceAccount<'info, TokenAccount>,
   #[account(
     constraint = mint_asset.key() == loan_offer.lend_mint_token @ LiquidateOfferError::InvalidMintAsset,
   )]
-  pub mint_asset: Account<'info, Mint>,
+  pub mint_asset: InterfaceAccount<'info, Mint>,
   /// CHECK: This account is used to transfer back collateral for borrower
   #[account(
     constraint = borrower.key() == loan_offer.borrower @ LiquidateOfferError::InvalidBorrower
@@ -35,9 +34,22 @@ pub struct SystemLiquidateLoanOffer<'info> {
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = borrower
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_ata_asset: InterfaceAccount<'info, TokenAccount>,
+  /// CHECK: This account is used to validate the lender token account
+  #[account(
+    constraint = lender.key() == loan_offer.lender @ LiquidateOfferError::InvalidLender
   )]
-  pub borrower_ata_asset: Account<'info, TokenAccount>,
+  pub lender: AccountInfo<'info>,
+  #[account(
+    mut,
+    associated_token::mint = mint_asset,
+    associated_token::authority = lender,
+    associated_token::token_program = token_program
+  )]
+  pub lender_ata_asset: InterfaceAccount<'info, TokenAccount>,
   #[account(
     mut,
     constraint = loan_offer.status == LoanOfferStatus::Liquidating @ LiquidateOfferError::InvalidOfferStatus,
@@ -51,7 +63,80 @@ pub struct SystemLiquidateLoanOffer<'info> {
     bump = loan_offer.bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
-  pub token_program: Program<'info, Token>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Settler as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      APPROVAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = approval_config.bump
+  )]
+  pub approval_config: Account<'info, ApprovalConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      PROPOSAL_SEED.as_ref(),
+      proposal.proposal_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = proposal.bump
+  )]
+  pub proposal: Account<'info, Proposal>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> SystemLiquidateLoanOffer<'info> {
@@ -61,7 +146,20 @@ impl<'info> SystemLiquidateLoanOffer<'info> {
     liquidated_price: u64,
     liquidated_tx: String,
   ) -> Result<()> {
-    let remaining_fund_to_borrower = self.get_remaining_fund(collateral_swapped_amount);
+    self.proposal.execute(
+      &self.approval_config,
+      &ProposalAction::FinishLiquidateContract {
+        loan_offer: self.loan_offer.key(),
+        collateral_swapped_amount,
+        liquidated_price,
+        liquidated_tx: liquidated_tx.clone(),
+      },
+      self.system.key(),
+    )?;
+
+    self.validate_swap_result(collateral_swapped_amount, liquidated_price)?;
+
+    let remaining_fund_to_borrower = self.get_remaining_fund(collateral_swapped_amount)?;
 
     if remaining_fund_to_borrower > 0 {
       self.transfer_asset_to_borrower(remaining_fund_to_borrower)?;
@@ -71,7 +169,20 @@ impl<'info> SystemLiquidateLoanOffer<'info> {
     loan_offer.liquidated_price = Some(liquidated_price);
     loan_offer.liquidated_tx = Some(liquidated_tx);
     loan_offer.status = LoanOfferStatus::Liquidated;
-    
+
+    // The lender is paid out of the proceeds before the protocol fees.
+    let lender_amount = collateral_swapped_amount.min(loan_offer.lender_repay_amount()?);
+    let deficit_amount = loan_offer.record_deficit(lender_amount)?;
+    if deficit_amount > 0 {
+      // A defaulted loan is never finished, so the lender takes its share of the proceeds now
+      // and the rest only through the reserve fund.
+      if lender_amount > 0 {
+        self.process_transfer(lender_amount, self.lender_ata_asset.to_account_info())?;
+      }
+      self.setting_account.record_bad_debt(deficit_amount)?;
+      emit_event_bad_debt(String::from("bad_debt"), &self.loan_offer, &self.setting_account, deficit_amount)?;
+    }
+
     self.emit_event_system_liquidate_loan_offer(
       String::from("system_liquidate_loan_offer"),
       remaining_fund_to_borrower,
@@ -80,6 +191,24 @@ impl<'info> SystemLiquidateLoanOffer<'info> {
     Ok(())
   }
 
+  /// The reported swap must match the oracle value of the seized collateral within the
+  /// configured slippage band.
+  fn validate_swap_result(&self, collateral_swapped_amount: u64, liquidated_price: u64) -> Result<()> {
+    let slippage_bps = self.global_config.liquidation_slippage_bps;
+
+    let expected_price = self.collateral_asset_config.unit_price(&self.collateral_price_feed_account)?;
+    validate_within_band(liquidated_price as u128, expected_price as u128, slippage_bps)?;
+
+    let expected_swapped_amount = self.loan_offer.collateral_value_in_lend(
+      self.loan_offer.collateral_amount,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account
+    )?;
+    validate_within_band(collateral_swapped_amount as u128, expected_swapped_amount, slippage_bps)
+  }
+
   fn transfer_asset_to_borrower(&mut self, remaining_fund_to_borrower: u64) -> Result<()> {
     self.process_transfer(
       remaining_fund_to_borrower,
@@ -126,19 +255,132 @@ impl<'info> SystemLiquidateLoanOffer<'info> {
     Ok(())
   }
 
-  fn get_remaining_fund(&self, collateral_swapped_amount: u64) -> u64 {
-    let loan_interest_percent = self.loan_offer.interest / 100.0;
-    let borrower_fee_percent = self.loan_offer.borrower_fee_percent / 100.0;
-    let time_borrowed = duration_to_year(self.loan_offer.duration);
-    let interest_loan_amount =
-        loan_interest_percent * self.loan_offer.borrow_amount as f64 * time_borrowed;
+  fn get_remaining_fund(&self, collateral_swapped_amount: u64) -> Result<u64> {
+    let total_repay = self.loan_offer.total_repay_amount()?;
+
+    Ok(collateral_swapped_amount.saturating_sub(total_repay))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::{test_utils::*, NATIVE_MINT};
+
+  const OFFER_ID: &str = "loan";
+  const PROPOSAL_ID: &str = "proposal";
+  const LIQUIDATED_TX: &str = "swap_tx";
+
+  struct Fixture {
+    system: Pubkey,
+    loan_offer: LoanOfferAccount,
+    collateral_usd_price: i64,
+    collateral_swapped_amount: u64,
+    liquidated_price: u64,
+  }
+
+  impl Fixture {
+    /// Liquidating loan of 100 USD whose one SOL was swapped at the oracle price.
+    fn new(collateral_usd_price: i64) -> Self {
+      let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+      loan_offer.status = LoanOfferStatus::Liquidating;
+      let oracle_amount = collateral_usd_price as u64 * 1_000_000;
+
+      Self {
+        system: Pubkey::new_unique(),
+        loan_offer,
+        collateral_usd_price,
+        collateral_swapped_amount: oracle_amount,
+        liquidated_price: oracle_amount,
+      }
+    }
+
+    fn load(&self) -> SystemLiquidateLoanOffer<'static> {
+      let loan_offer_key = loan_offer_key(&self.loan_offer.borrower, OFFER_ID);
+      let lend_mint = self.loan_offer.lend_mint_token;
+      let borrower = self.loan_offer.borrower;
+      let lender = self.loan_offer.lender;
+      let action = ProposalAction::FinishLiquidateContract {
+        loan_offer: loan_offer_key,
+        collateral_swapped_amount: self.collateral_swapped_amount,
+        liquidated_price: self.liquidated_price,
+        liquidated_tx: LIQUIDATED_TX.to_string(),
+      };
+
+      let accounts = vec![
+        signer(self.system),
+        token_account(ata(&self.system, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, self.system, self.collateral_swapped_amount),
+        mint(lend_mint, spl_token::ID),
+        wallet(borrower),
+        token_account(ata(&borrower, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, borrower, 0),
+        wallet(lender),
+        token_account(ata(&lender, &lend_mint, &spl_token::ID), spl_token::ID, lend_mint, lender, 0),
+        state(loan_offer_key, &self.loan_offer),
+        state(role_account_key(Role::Settler), &role_account(Role::Settler, vec![self.system])),
+        state(approval_config_key(), &approval_config(vec![self.system], 1)),
+        state(proposal_key(PROPOSAL_ID), &proposal(PROPOSAL_ID, action, vec![self.system])),
+        state(global_config_key(), &global_config(Pubkey::new_unique(), Pubkey::new_unique())),
+        price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+        price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(self.collateral_usd_price), PRICE_EXPONENT),
+        state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+        state(asset_config_key(&NATIVE_MINT), &asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9)),
+        state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT)),
+        program(spl_token::ID),
+      ];
+
+      load::<SystemLiquidateLoanOffer, _>(accounts, &args(OFFER_ID.to_string())).unwrap().0
+    }
+
+    fn liquidate(&self) -> Result<SystemLiquidateLoanOffer<'static>> {
+      let mut context = self.load();
+      context.system_liquidate_loan_offer(self.collateral_swapped_amount, self.liquidated_price, LIQUIDATED_TX.to_string())?;
+
+      Ok(context)
+    }
+  }
+
+  #[test]
+  fn settles_swap_within_slippage_band() {
+    let mut fixture = Fixture::new(120);
+    // 0.5% below the oracle, inside the 1% band.
+    fixture.collateral_swapped_amount = 119_400_000;
+    let context = fixture.liquidate().unwrap();
+
+    assert_eq!(token_amount(&context.borrower_ata_asset.to_account_info()), 119_400_000 - 110_500_000);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Liquidated);
+    assert_eq!(context.loan_offer.liquidated_price, Some(120_000_000));
+    assert_eq!(context.loan_offer.liquidated_tx.as_deref(), Some(LIQUIDATED_TX));
+  }
+
+  #[test]
+  fn rejects_swapped_amount_outside_slippage_band() {
+    let mut fixture = Fixture::new(120);
+    fixture.collateral_swapped_amount = 118_000_000;
+
+    assert_error(fixture.liquidate().map(|_| ()), LiquidateOfferError::OutsideSlippageBand);
+  }
+
+  #[test]
+  fn rejects_liquidated_price_outside_slippage_band() {
+    let mut fixture = Fixture::new(120);
+    fixture.liquidated_price = 122_000_000;
+
+    assert_error(fixture.liquidate().map(|_| ()), LiquidateOfferError::OutsideSlippageBand);
+  }
 
-    let borrower_fee_amount =
-        borrower_fee_percent * interest_loan_amount;
+  #[test]
+  fn underwater_swap_defaults_loan_and_records_bad_debt() {
+    // One SOL at 90 USD cannot cover the 109.5 USD owed to the lender.
+    let context = Fixture::new(90).liquidate().unwrap();
 
-    return (collateral_swapped_amount as f64
-      - self.loan_offer.borrow_amount as f64
-      - interest_loan_amount
-      - borrower_fee_amount) as u64;
+    assert_eq!(token_amount(&context.lender_ata_asset.to_account_info()), 90_000_000);
+    assert_eq!(token_amount(&context.borrower_ata_asset.to_account_info()), 0);
+    assert_eq!(token_amount(&context.system_ata.to_account_info()), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::Defaulted);
+    assert_eq!(context.loan_offer.deficit_amount, 19_500_000);
+    assert_eq!(context.setting_account.bad_debt_amount, 19_500_000);
+    assert_eq!(context.setting_account.defaulted_loan_count, 1);
   }
 }
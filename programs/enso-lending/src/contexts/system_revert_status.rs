@@ -1,8 +1,6 @@
-use std::str::FromStr;
-
 use anchor_lang::prelude::*;
 
-use crate::{common::constant::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, OPERATE_SYSTEM_PUBKEY}, LoanOfferAccount, LoanOfferError, LoanOfferStatus, SystemRevertEvent};
+use crate::{common::{constant::{ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, ROLE_ACCOUNT_SEED}, RoleError}, LoanOfferAccount, Role, RoleAccount, LoanOfferError, LoanOfferStatus, SystemRevertEvent};
 
 #[derive(Accounts)]
 #[instruction(offer_id: String)]
@@ -23,14 +21,21 @@ pub struct SystemRevertStatus<'info> {
     bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Liquidator as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
 }
 
 impl<'info> SystemRevertStatus<'info> {
   pub fn system_revert_status(&mut self) -> Result<()> {
-    if self.system.key() != Pubkey::from_str(OPERATE_SYSTEM_PUBKEY).unwrap() {
-      return err!(LoanOfferError::InvalidSystem);
-    }
-
     let loan_offer = &mut self.loan_offer;
     if loan_offer.status != LoanOfferStatus::Liquidating {
       return err!(LoanOfferError::InvalidOfferStatus);
@@ -52,4 +57,42 @@ impl<'info> SystemRevertStatus<'info> {
     msg!(&label.clone());
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn revert_accounts(system: Pubkey, members: Vec<Pubkey>) -> Vec<AccountInfo<'static>> {
+    let borrower = Pubkey::new_unique();
+    let mut loan_offer = loan_offer(borrower, "loan", Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT);
+    loan_offer.status = LoanOfferStatus::Liquidating;
+    loan_offer.liquidating_price = Some(100);
+
+    vec![
+      signer(system),
+      wallet(borrower),
+      state(loan_offer_key(&borrower, "loan"), &loan_offer),
+      state(role_account_key(Role::Liquidator), &role_account(Role::Liquidator, members)),
+    ]
+  }
+
+  #[test]
+  fn liquidator_reverts_liquidating_loan() {
+    let system = Pubkey::new_unique();
+    let (mut context, _, _) = load::<SystemRevertStatus, _>(revert_accounts(system, vec![system]), &args("loan".to_string())).unwrap();
+
+    context.system_revert_status().unwrap();
+
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::FundTransferred);
+    assert_eq!(context.loan_offer.liquidating_price, None);
+  }
+
+  #[test]
+  fn rejects_signer_without_liquidator_role() {
+    let accounts = revert_accounts(Pubkey::new_unique(), vec![Pubkey::new_unique()]);
+
+    assert_error(load::<SystemRevertStatus, _>(accounts, &args("loan".to_string())).map(|_| ()), RoleError::Unauthorized);
+  }
+}
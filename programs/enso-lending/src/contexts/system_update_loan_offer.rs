@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::{
-  common::LoanOfferError, LoanOfferAccount, LoanOfferStatus, LoanOfferUpdateEvent, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED
+  common::{LoanOfferError, RoleError}, LendOfferAccount, LoanOfferAccount, LoanOfferStatus, LoanOfferUpdateEvent, Role, RoleAccount, ENSO_SEED,
+  LEND_ESCROW_SEED, LEND_OFFER_ACCOUNT_SEED, LOAN_OFFER_ACCOUNT_SEED, ROLE_ACCOUNT_SEED
 };
 
 #[derive(Accounts)]
@@ -19,13 +20,14 @@ pub struct SystemUpdateLoanOffer<'info> {
   #[account(
     mut,
     associated_token::mint = mint_asset,
-    associated_token::authority = borrower
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
   )]
-  pub borrower_ata_asset: Account<'info, TokenAccount>,
+  pub borrower_ata_asset: InterfaceAccount<'info, TokenAccount>,
   #[account(
     constraint = mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
   )]
-  pub mint_asset: Account<'info, Mint>,
+  pub mint_asset: InterfaceAccount<'info, Mint>,
   #[account(
     mut,
     constraint = loan_offer.status == LoanOfferStatus::Matched @ LoanOfferError::InvalidLoanOffer,
@@ -39,16 +41,48 @@ pub struct SystemUpdateLoanOffer<'info> {
     bump = loan_offer.bump
   )]
   pub loan_offer: Account<'info, LoanOfferAccount>,
-  #[account(mut)]
-  pub system: Signer<'info>,
+  /// CHECK: This is the account used to make the lend offer seeds and receive the escrow rent
   #[account(
     mut,
-    constraint = system_ata.amount >= borrow_amount @ LoanOfferError::NotEnoughAmount,
-    associated_token::mint = mint_asset,
-    associated_token::authority = system
+    constraint = lender.key() == loan_offer.lender @ LoanOfferError::InvalidLoanOffer
+  )]
+  pub lender: UncheckedAccount<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_OFFER_ACCOUNT_SEED.as_ref(),
+      lender.key().as_ref(),
+      loan_offer.lend_offer_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_offer.bump
+  )]
+  pub lend_offer: Account<'info, LendOfferAccount>,
+  #[account(
+    mut,
+    constraint = lend_escrow.amount >= borrow_amount @ LoanOfferError::NotEnoughAmount,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LEND_ESCROW_SEED.as_ref(),
+      lend_offer.key().as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump
   )]
-  pub system_ata: Account<'info, TokenAccount>,
-  pub token_program: Program<'info, Token>
+  pub lend_escrow: InterfaceAccount<'info, TokenAccount>,
+  pub system: Signer<'info>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ROLE_ACCOUNT_SEED.as_ref(),
+      &[Role::Funder as u8],
+      crate::ID.key().as_ref(),
+    ],
+    bump = role_account.bump,
+    constraint = role_account.is_member(&system.key()) @ RoleError::Unauthorized
+  )]
+  pub role_account: Account<'info, RoleAccount>,
+  pub token_program: Interface<'info, TokenInterface>
 }
 
 impl<'info> SystemUpdateLoanOffer<'info> {
@@ -67,29 +101,54 @@ impl<'info> SystemUpdateLoanOffer<'info> {
   }
 
   fn transfer_lend_asset_to_borrower(&mut self, borrow_amount: u64) -> Result<()> {
-    let ctx = CpiContext::new(
+    let lender = self.lend_offer.lender;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LEND_OFFER_ACCOUNT_SEED,
+      lender.as_ref(),
+      self.lend_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.lend_offer.bump],
+    ]];
+
+    let ctx = CpiContext::new_with_signer(
       self.token_program.to_account_info(), 
       TransferChecked {
-        from: self.system_ata.to_account_info(),
+        from: self.lend_escrow.to_account_info(),
         mint: self.mint_asset.to_account_info(),
         to: self.borrower_ata_asset.to_account_info(),
-        authority: self.system.to_account_info(),
-    });
+        authority: self.lend_offer.to_account_info(),
+      },
+      signer
+    );
 
     transfer_checked(
       ctx,
       borrow_amount,
       self.mint_asset.decimals,
-    )
+    )?;
+
+    self.lend_escrow.reload()?;
+    if self.lend_escrow.amount > 0 {
+      return Ok(());
+    }
+
+    let close_accounts = CloseAccount {
+      account: self.lend_escrow.to_account_info(),
+      destination: self.lender.to_account_info(),
+      authority: self.lend_offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer))
   }
 
   fn emit_event_system_update_loan_offer(&mut self, label: String) -> Result<()> {
     emit!(LoanOfferUpdateEvent {
       tier_id: self.loan_offer.tier_id.clone(),
       lend_offer_id: self.loan_offer.lend_offer_id.clone(),
-      interest: self.loan_offer.interest,
+      interest_bps: self.loan_offer.interest_bps,
       borrow_amount: self.loan_offer.borrow_amount,
-      lender_fee_percent: self.loan_offer.lender_fee_percent,
+      lender_fee_bps: self.loan_offer.lender_fee_bps,
       duration: self.loan_offer.duration,
       lend_mint_token: self.loan_offer.lend_mint_token,
       lender: self.loan_offer.lender,
@@ -98,7 +157,7 @@ impl<'info> SystemUpdateLoanOffer<'info> {
       collateral_mint_token: self.loan_offer.collateral_mint_token,
       collateral_amount: self.loan_offer.collateral_amount,
       status: self.loan_offer.status,
-      borrower_fee_percent: self.loan_offer.borrower_fee_percent,
+      borrower_fee_bps: self.loan_offer.borrower_fee_bps,
       started_at: self.loan_offer.started_at,
     });
 
@@ -106,4 +165,75 @@ impl<'info> SystemUpdateLoanOffer<'info> {
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::test_utils::*;
+
+  const OFFER_ID: &str = "loan";
+  const BORROW_AMOUNT: u64 = 100_000_000;
+
+  fn load_context(escrow_amount: u64) -> SystemUpdateLoanOffer<'static> {
+    let system = Pubkey::new_unique();
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    loan_offer.status = LoanOfferStatus::Matched;
+    let borrower = loan_offer.borrower;
+    let lender = loan_offer.lender;
+    let mint = loan_offer.lend_mint_token;
+    let lend_offer_key = lend_offer_key(&lender, &loan_offer.lend_offer_id);
+    let lend_offer = lend_offer(lender, &loan_offer.lend_offer_id, mint, escrow_amount);
+
+    let accounts = vec![
+      wallet(borrower),
+      token_account(ata(&borrower, &mint, &spl_token::ID), spl_token::ID, mint, borrower, 0),
+      crate::test_utils::mint(mint, spl_token::ID),
+      state(loan_offer_key(&borrower, OFFER_ID), &loan_offer),
+      wallet(lender),
+      state(lend_offer_key, &lend_offer),
+      token_account(
+        pda(&[ENSO_SEED, LEND_ESCROW_SEED, lend_offer_key.as_ref(), crate::ID.as_ref()]),
+        spl_token::ID,
+        mint,
+        lend_offer_key,
+        escrow_amount
+      ),
+      signer(system),
+      state(role_account_key(Role::Funder), &role_account(Role::Funder, vec![system])),
+      program(spl_token::ID),
+    ];
+
+    load::<SystemUpdateLoanOffer, _>(accounts, &args((OFFER_ID.to_string(), TIER_ID.to_string(), BORROW_AMOUNT))).unwrap().0
+  }
+
+  #[test]
+  fn funds_borrower_from_escrow_and_closes_it() {
+    let mut context = load_context(BORROW_AMOUNT);
+
+    context.system_update_loan_offer(BORROW_AMOUNT).unwrap();
+
+    assert_eq!(token_amount(&context.borrower_ata_asset.to_account_info()), BORROW_AMOUNT);
+    assert_eq!(context.lend_escrow.to_account_info().lamports(), 0);
+    assert_eq!(context.loan_offer.status, LoanOfferStatus::FundTransferred);
+  }
+
+  #[test]
+  fn keeps_escrow_open_while_funds_remain() {
+    let mut context = load_context(150_000_000);
+
+    context.system_update_loan_offer(BORROW_AMOUNT).unwrap();
+
+    assert_eq!(token_amount(&context.borrower_ata_asset.to_account_info()), BORROW_AMOUNT);
+    assert_eq!(token_amount(&context.lend_escrow.to_account_info()), 50_000_000);
+  }
+
+  #[test]
+  fn rejects_amount_other_than_borrowed() {
+    let mut context = load_context(150_000_000);
+
+    assert_error(context.system_update_loan_offer(BORROW_AMOUNT + 1), LoanOfferError::InvalidBorrowAmount);
+  }
+}
@@ -1,30 +1,33 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 use crate::{
   common::{
-    constant::MIN_BORROW_HEALTH_RATIO, WithdrawCollateralEvent, ENSO_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
-  }, convert_to_usd_price, states::{
+    PauseAction, PauseError, WithdrawCollateralEvent, ASSET_CONFIG_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED, SETTING_ACCOUNT_SEED
+  }, states::{
+    asset_config::AssetConfig,
+    global_config::GlobalConfig,
     loan_offer::LoanOfferAccount,
     setting_account::SettingAccount
-  }, LoanOfferError, LoanOfferStatus, SOL_USD_PRICE_FEED_ID, USDC_USD_PRICE_FEED_ID
+  }, native_vault_balance, transfer_from_native_vault, validate_vault_balance, LoanOfferError, LoanOfferStatus, NATIVE_MINT
 };
 
 #[derive(Accounts)]
-#[instruction(loan_offer_id: String, withdraw_amount: u64)]
+#[instruction(loan_offer_id: String)]
 pub struct WithdrawCollateral<'info> {
     #[account(mut)]
     pub borrower: Signer<'info>,
     #[account(
       constraint = collateral_mint_asset.key() == setting_account.collateral_mint_asset @ LoanOfferError::InvalidCollateralMintAsset,
     )]
-    pub collateral_mint_asset: Account<'info, Mint>,
+    pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
     #[account(
       constraint = lend_mint_asset.key() == setting_account.lend_mint_asset @ LoanOfferError::InvalidLendMintAsset,
     )]
-    pub lend_mint_asset: Account<'info, Mint>,
+    pub lend_mint_asset: InterfaceAccount<'info, Mint>,
     #[account(
+      constraint = !setting_account.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::TierPaused,
       seeds = [
           ENSO_SEED.as_ref(), 
           SETTING_ACCOUNT_SEED.as_ref(),
@@ -37,6 +40,7 @@ pub struct WithdrawCollateral<'info> {
     #[account(
       mut,
       constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::NotAvailableToWithdraw,
+      constraint = loan_offer.collateral_mint_token == NATIVE_MINT @ LoanOfferError::InvalidCollateralMintAsset,
       seeds = [
         ENSO_SEED.as_ref(),
         LOAN_OFFER_ACCOUNT_SEED.as_ref(),
@@ -47,45 +51,101 @@ pub struct WithdrawCollateral<'info> {
       bump = loan_offer.bump
     )]
     pub loan_offer: Account<'info, LoanOfferAccount>,
+    #[account(
+      mut,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        NATIVE_COLLATERAL_VAULT_SEED.as_ref(),
+        loan_offer.key().as_ref(),
+        crate::ID.key().as_ref()
+      ],
+      bump
+    )]
+    pub collateral_vault: SystemAccount<'info>,
     pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
     pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+      seeds = [
+        ENSO_SEED.as_ref(),
+        ASSET_CONFIG_SEED.as_ref(),
+        lend_mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = lend_asset_config.bump
+    )]
+    pub lend_asset_config: Account<'info, AssetConfig>,
+    #[account(
+      seeds = [
+        ENSO_SEED.as_ref(),
+        ASSET_CONFIG_SEED.as_ref(),
+        collateral_mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = collateral_asset_config.bump
+    )]
+    pub collateral_asset_config: Account<'info, AssetConfig>,
+    #[account(
+      constraint = !global_config.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::ProtocolPaused,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        GLOBAL_CONFIG_SEED.as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> WithdrawCollateral<'info> {
-  pub fn withdraw_collateral(&mut self, loan_offer_id: String, withdraw_amount: u64) -> Result<()> {
-    let lend_amount_to_usd = convert_to_usd_price(
-      &self.lend_price_feed_account, 
-      USDC_USD_PRICE_FEED_ID,
-      self.setting_account.amount as f64 / 10f64.powf(self.lend_mint_asset.decimals as f64)
-    ).unwrap();
-
-    let remaining_collateral = self.loan_offer.collateral_amount - withdraw_amount;
+  pub fn withdraw_collateral(
+    &mut self,
+    bumps: &WithdrawCollateralBumps,
+    loan_offer_id: String,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let withdraw_amount = self.loan_offer.executable_withdraw_amount(
+      current_timestamp,
+      self.global_config.withdraw_request_cooldown,
+      None
+    )?;
 
-    let remaining_collateral_in_usd = convert_to_usd_price(
-      &self.collateral_price_feed_account, 
-      SOL_USD_PRICE_FEED_ID,
-      remaining_collateral as f64 / 10f64.powf(self.collateral_mint_asset.decimals as f64)
-    ).unwrap();
+    let remaining_collateral = self.loan_offer.collateral_amount
+      .checked_sub(withdraw_amount)
+      .ok_or(LoanOfferError::NotEnoughAmount)?;
 
-    let health_ratio = remaining_collateral_in_usd / lend_amount_to_usd;
+    let ltv_bps = self.loan_offer.ltv_bps(
+      remaining_collateral,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      basket_accounts
+    )?;
 
-    if health_ratio < MIN_BORROW_HEALTH_RATIO {
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
       return err!(LoanOfferError::HealthRatioLimit);
     }
 
-    let current_timestamp = Clock::get().unwrap().unix_timestamp;
-    let end_borrowed_loan_offer = self.loan_offer.started_at + self.loan_offer.duration as i64;
-
-    if current_timestamp > end_borrowed_loan_offer {
+    if current_timestamp > self.loan_offer.end_at() {
       return err!(LoanOfferError::LoanOfferExpired)?;
     }
     
-    self.loan_offer.sub_lamports(withdraw_amount)?;
-    self.borrower.add_lamports(withdraw_amount)?;
+    transfer_from_native_vault(
+      &self.collateral_vault,
+      &self.borrower,
+      &self.system_program,
+      self.loan_offer.key(),
+      bumps.collateral_vault,
+      withdraw_amount
+    )?;
 
     self.loan_offer.collateral_amount = remaining_collateral;
+    self.loan_offer.clear_withdraw_request();
+
+    validate_vault_balance(native_vault_balance(&self.collateral_vault)?, self.loan_offer.collateral_amount)?;
 
     self.emit_event_withdraw_collateral(
       String::from("withdraw_collateral"),
@@ -108,4 +168,83 @@ impl<'info> WithdrawCollateral<'info> {
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::{test_utils::*, WithdrawRequestError};
+
+  const OFFER_ID: &str = "loan";
+  /// Cooldown of the global config in the fixture.
+  const COOLDOWN: i64 = 3_600;
+
+  /// Loan of 100 USD against one SOL priced at `collateral_usd_price`, with a pending
+  /// request to withdraw `withdraw_amount` filed `requested_ago` seconds ago.
+  fn load_withdraw(collateral_usd_price: i64, withdraw_amount: u64, requested_ago: i64) -> (WithdrawCollateral<'static>, WithdrawCollateralBumps) {
+    let lend_mint = Pubkey::new_unique();
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), lend_mint, NATIVE_MINT);
+    loan_offer.request_withdraw_amount = Some(withdraw_amount);
+    loan_offer.request_withdraw_at = Some(now() - requested_ago);
+    let loan_offer_key = loan_offer_key(&loan_offer.borrower, OFFER_ID);
+    let collateral_vault = pda(&[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), crate::ID.as_ref()]);
+    let mut global_config = global_config(Pubkey::new_unique(), Pubkey::new_unique());
+    global_config.withdraw_request_cooldown = COOLDOWN;
+
+    let accounts = vec![
+      signer(loan_offer.borrower),
+      mint(NATIVE_MINT, spl_token::ID),
+      mint(lend_mint, spl_token::ID),
+      state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT)),
+      state(loan_offer_key, &loan_offer),
+      account(
+        collateral_vault,
+        System::id(),
+        Rent::default().minimum_balance(0) + loan_offer.collateral_amount,
+        vec![],
+        false,
+        false
+      ),
+      price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+      price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(collateral_usd_price), PRICE_EXPONENT),
+      state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+      state(asset_config_key(&NATIVE_MINT), &asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9)),
+      state(global_config_key(), &global_config),
+      program(spl_token::ID),
+      program(System::id()),
+    ];
+    let (context, bumps, _) = load::<WithdrawCollateral, _>(accounts, &args(OFFER_ID.to_string())).unwrap();
+
+    (context, bumps)
+  }
+
+  #[test]
+  fn withdraws_requested_amount_after_cooldown() {
+    let (mut context, bumps) = load_withdraw(1_000, 800_000_000, COOLDOWN);
+    let borrower_lamports = context.borrower.lamports();
+
+    // 100 USD of debt against the 200 USD left sits exactly at the 50% initial LTV.
+    context.withdraw_collateral(&bumps, OFFER_ID.to_string(), &[]).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_amount, 200_000_000);
+    assert_eq!(native_vault_balance(&context.collateral_vault).unwrap(), 200_000_000);
+    assert_eq!(context.borrower.lamports(), borrower_lamports + 800_000_000);
+    assert_eq!(context.loan_offer.request_withdraw_amount, None);
+  }
+
+  #[test]
+  fn rejects_withdraw_before_cooldown() {
+    let (mut context, bumps) = load_withdraw(1_000, 800_000_000, COOLDOWN - 1);
+
+    assert_error(context.withdraw_collateral(&bumps, OFFER_ID.to_string(), &[]), WithdrawRequestError::NotReady);
+  }
+
+  #[test]
+  fn rechecks_health_at_current_price() {
+    // The request was healthy at 1000 USD, but collateral fell to 900 USD before it executed.
+    let (mut context, bumps) = load_withdraw(900, 800_000_000, COOLDOWN);
+
+    assert_error(context.withdraw_collateral(&bumps, OFFER_ID.to_string(), &[]), LoanOfferError::HealthRatioLimit);
+  }
+}
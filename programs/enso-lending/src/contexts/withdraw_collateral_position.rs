@@ -0,0 +1,386 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{
+    PauseAction, PauseError, ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
+  }, states::{
+    asset_config::AssetConfig,
+    global_config::GlobalConfig,
+    loan_offer::LoanOfferAccount,
+    setting_account::SettingAccount
+  }, validate_vault_balance, CollateralPositionError, CollateralPositionWithdrawnEvent, LoanOfferError, LoanOfferStatus,
+  WithdrawRequestError
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String, withdraw_amount: u64)]
+pub struct WithdrawCollateralPosition<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = borrower,
+    associated_token::token_program = token_program
+  )]
+  pub borrower_ata: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+      borrower.key().as_ref(),
+      loan_offer_id.as_bytes(),
+      crate::ID.key().as_ref()
+    ],
+    bump = loan_offer.bump
+  )]
+  pub loan_offer: Account<'info, LoanOfferAccount>,
+  #[account(
+    mut,
+    token::mint = mint,
+    token::authority = loan_offer,
+    token::token_program = token_program,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      COLLATERAL_VAULT_SEED.as_ref(),
+      loan_offer.key().as_ref(),
+      mint.key().as_ref(),
+      crate::ID.key().as_ref()
+    ],
+    bump
+  )]
+  pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    constraint = !setting_account.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::TierPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      SETTING_ACCOUNT_SEED.as_ref(),
+      loan_offer.tier_id.as_bytes(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = setting_account.bump
+  )]
+  pub setting_account: Account<'info, SettingAccount>,
+  #[account(
+    constraint = !global_config.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::ProtocolPaused,
+    seeds = [
+      ENSO_SEED.as_ref(),
+      GLOBAL_CONFIG_SEED.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = global_config.bump
+  )]
+  pub global_config: Account<'info, GlobalConfig>,
+  pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+  pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.lend_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = lend_asset_config.bump
+  )]
+  pub lend_asset_config: Account<'info, AssetConfig>,
+  #[account(
+    seeds = [
+      ENSO_SEED.as_ref(),
+      ASSET_CONFIG_SEED.as_ref(),
+      loan_offer.collateral_mint_token.as_ref(),
+      crate::ID.key().as_ref(),
+    ],
+    bump = collateral_asset_config.bump
+  )]
+  pub collateral_asset_config: Account<'info, AssetConfig>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawCollateralPosition<'info> {
+  /// While the loan is active a position only leaves the vault through an executable withdraw
+  /// request for its mint, and the remaining collateral must keep the loan within its initial LTV.
+  /// Once the loan is closed positions are released freely.
+  pub fn withdraw_collateral_position(
+    &mut self,
+    withdraw_amount: u64,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    let index = self.loan_offer
+      .find_collateral_position(&self.mint.key())
+      .ok_or(CollateralPositionError::PositionNotFound)?;
+    let remaining_amount = self.loan_offer.collateral_positions[index].amount
+      .checked_sub(withdraw_amount)
+      .ok_or(LoanOfferError::NotEnoughAmount)?;
+
+    self.loan_offer.collateral_positions[index].amount = remaining_amount;
+
+    match self.loan_offer.status {
+      LoanOfferStatus::Matched | LoanOfferStatus::FundTransferred => {
+        let requested_amount = self.loan_offer.executable_withdraw_amount(
+          Clock::get()?.unix_timestamp,
+          self.global_config.withdraw_request_cooldown,
+          Some(self.mint.key())
+        )?;
+        if requested_amount != withdraw_amount {
+          return err!(WithdrawRequestError::RequestMismatch);
+        }
+
+        self.validate_health(basket_accounts)?;
+        self.loan_offer.clear_withdraw_request();
+      }
+      LoanOfferStatus::BorrowerPaid | LoanOfferStatus::Finished | LoanOfferStatus::Liquidated
+        | LoanOfferStatus::Canceled => {}
+      _ => return err!(LoanOfferError::NotAvailableToWithdraw),
+    }
+
+    // Closing the vault needs it empty, so the last withdrawal also releases any surplus.
+    let transfer_amount = if remaining_amount == 0 { self.collateral_vault.amount } else { withdraw_amount };
+    self.transfer_from_vault(transfer_amount)?;
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, remaining_amount)?;
+
+    if remaining_amount == 0 {
+      self.loan_offer.collateral_positions.remove(index);
+      self.close_vault()?;
+    }
+
+    self.emit_event_withdraw_collateral_position(
+      String::from("withdraw_collateral_position"),
+      withdraw_amount,
+      remaining_amount,
+    )
+  }
+
+  fn validate_health(&self, basket_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+    if Clock::get()?.unix_timestamp > self.loan_offer.end_at() {
+      return err!(LoanOfferError::LoanOfferExpired);
+    }
+
+    let ltv_bps = self.loan_offer.ltv_bps(
+      self.loan_offer.collateral_amount,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      basket_accounts
+    )?;
+
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
+      return err!(LoanOfferError::HealthRatioLimit);
+    }
+
+    Ok(())
+  }
+
+  fn transfer_from_vault(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.mint.to_account_info(),
+      to: self.borrower_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+
+    transfer_checked(cpi_ctx, amount, self.mint.decimals)
+  }
+
+  fn close_vault(&self) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let cpi_accounts = CloseAccount {
+      account: self.collateral_vault.to_account_info(),
+      destination: self.borrower.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+
+    close_account(CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer))
+  }
+
+  fn emit_event_withdraw_collateral_position(&self, label: String, amount: u64, position_amount: u64) -> Result<()> {
+    emit!(CollateralPositionWithdrawnEvent {
+      loan_offer_id: self.loan_offer.offer_id.clone(),
+      borrower: self.borrower.key(),
+      mint: self.mint.key(),
+      amount,
+      position_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token::spl_token;
+
+  use super::*;
+  use crate::{test_utils::*, CollateralPosition, NATIVE_MINT};
+
+  const OFFER_ID: &str = "loan";
+  const AMOUNT: u64 = 100_000_000;
+  /// Cooldown of the global config in the fixture.
+  const COOLDOWN: i64 = 3_600;
+
+  /// Loan of 100 USD against one SOL at 100 USD plus a basket position of `AMOUNT` tokens
+  /// at 2 USD, which puts it at a 33% LTV.
+  fn test_loan_offer(status: LoanOfferStatus, mint: Pubkey) -> LoanOfferAccount {
+    let mut loan_offer = loan_offer(Pubkey::new_unique(), OFFER_ID, Pubkey::new_unique(), Pubkey::new_unique(), NATIVE_MINT);
+    loan_offer.status = status;
+    loan_offer.collateral_positions.push(CollateralPosition { mint, amount: AMOUNT });
+    loan_offer
+  }
+
+  /// Pending request to withdraw `amount` of the `mint` position, filed `requested_ago` seconds ago.
+  fn with_request(mut loan_offer: LoanOfferAccount, mint: Option<Pubkey>, amount: u64, requested_ago: i64) -> LoanOfferAccount {
+    loan_offer.request_withdraw_amount = Some(amount);
+    loan_offer.request_withdraw_at = Some(now() - requested_ago);
+    loan_offer.request_withdraw_mint = mint;
+    loan_offer
+  }
+
+  fn load_withdraw(
+    loan_offer: &LoanOfferAccount,
+    mint_key: Pubkey,
+    withdraw_amount: u64
+  ) -> (WithdrawCollateralPosition<'static>, &'static [AccountInfo<'static>]) {
+    let borrower = loan_offer.borrower;
+    let lend_mint = loan_offer.lend_mint_token;
+    let loan_offer_key = loan_offer_key(&borrower, OFFER_ID);
+    let collateral_vault = pda(&[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), mint_key.as_ref(), crate::ID.as_ref()]);
+    let mut global_config = global_config(Pubkey::new_unique(), Pubkey::new_unique());
+    global_config.withdraw_request_cooldown = COOLDOWN;
+
+    let accounts = vec![
+      signer(borrower),
+      mint(mint_key, spl_token::ID),
+      token_account(ata(&borrower, &mint_key, &spl_token::ID), spl_token::ID, mint_key, borrower, 0),
+      state(loan_offer_key, loan_offer),
+      token_account(collateral_vault, spl_token::ID, mint_key, loan_offer_key, AMOUNT),
+      state(setting_account_key(TIER_ID), &setting_account(Pubkey::new_unique(), lend_mint, NATIVE_MINT)),
+      state(global_config_key(), &global_config),
+      price_feed(Pubkey::new_unique(), LEND_FEED_ID, usd_price(1), PRICE_EXPONENT),
+      price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(100), PRICE_EXPONENT),
+      state(asset_config_key(&lend_mint), &asset_config(lend_mint, LEND_FEED_ID, TOKEN_DECIMALS)),
+      state(asset_config_key(&NATIVE_MINT), &asset_config(NATIVE_MINT, COLLATERAL_FEED_ID, 9)),
+      program(spl_token::ID),
+    ];
+    let (context, _, _) = load::<WithdrawCollateralPosition, _>(accounts, &args((OFFER_ID.to_string(), withdraw_amount))).unwrap();
+    let basket_accounts = vec![
+      state(asset_config_key(&mint_key), &asset_config(mint_key, COLLATERAL_FEED_ID, TOKEN_DECIMALS)),
+      price_feed(Pubkey::new_unique(), COLLATERAL_FEED_ID, usd_price(2), PRICE_EXPONENT),
+    ];
+
+    (context, Box::leak(basket_accounts.into_boxed_slice()))
+  }
+
+  #[test]
+  fn keeps_positions_locked_without_a_request() {
+    let mint = Pubkey::new_unique();
+    let (mut context, basket_accounts) = load_withdraw(&test_loan_offer(LoanOfferStatus::FundTransferred, mint), mint, AMOUNT / 4);
+
+    assert_error(context.withdraw_collateral_position(AMOUNT / 4, basket_accounts), WithdrawRequestError::NoPendingRequest);
+  }
+
+  #[test]
+  fn rejects_withdraw_that_does_not_match_the_request() {
+    let mint = Pubkey::new_unique();
+    let loan_offer = with_request(test_loan_offer(LoanOfferStatus::FundTransferred, mint), None, AMOUNT / 4, COOLDOWN);
+    let (mut context, basket_accounts) = load_withdraw(&loan_offer, mint, AMOUNT / 4);
+
+    // The pending request is for the primary collateral.
+    assert_error(context.withdraw_collateral_position(AMOUNT / 4, basket_accounts), WithdrawRequestError::RequestMismatch);
+
+    let loan_offer = with_request(test_loan_offer(LoanOfferStatus::FundTransferred, mint), Some(mint), AMOUNT / 4, COOLDOWN);
+    let (mut context, basket_accounts) = load_withdraw(&loan_offer, mint, AMOUNT / 2);
+
+    assert_error(context.withdraw_collateral_position(AMOUNT / 2, basket_accounts), WithdrawRequestError::RequestMismatch);
+  }
+
+  #[test]
+  fn rejects_withdraw_before_cooldown() {
+    let mint = Pubkey::new_unique();
+    let loan_offer = with_request(test_loan_offer(LoanOfferStatus::Matched, mint), Some(mint), AMOUNT / 4, COOLDOWN - 1);
+    let (mut context, basket_accounts) = load_withdraw(&loan_offer, mint, AMOUNT / 4);
+
+    assert_error(context.withdraw_collateral_position(AMOUNT / 4, basket_accounts), WithdrawRequestError::NotReady);
+  }
+
+  #[test]
+  fn withdraws_requested_amount_after_cooldown() {
+    let mint = Pubkey::new_unique();
+    let loan_offer = with_request(test_loan_offer(LoanOfferStatus::FundTransferred, mint), Some(mint), AMOUNT / 4, COOLDOWN);
+    let (mut context, basket_accounts) = load_withdraw(&loan_offer, mint, AMOUNT / 4);
+
+    // 100 USD of debt against the 250 USD left is a 40% LTV.
+    context.withdraw_collateral_position(AMOUNT / 4, basket_accounts).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_positions, vec![CollateralPosition { mint, amount: AMOUNT * 3 / 4 }]);
+    assert_eq!(token_amount(&context.borrower_ata.to_account_info()), AMOUNT / 4);
+    assert_eq!(context.loan_offer.request_withdraw_amount, None);
+    assert_eq!(context.loan_offer.request_withdraw_mint, None);
+  }
+
+  #[test]
+  fn rejects_withdraw_above_initial_ltv() {
+    let mint = Pubkey::new_unique();
+    let loan_offer = with_request(test_loan_offer(LoanOfferStatus::FundTransferred, mint), Some(mint), AMOUNT, COOLDOWN);
+    let (mut context, basket_accounts) = load_withdraw(&loan_offer, mint, AMOUNT);
+
+    // Only the 100 USD of primary collateral would be left against 100 USD of debt.
+    assert_error(context.withdraw_collateral_position(AMOUNT, basket_accounts), LoanOfferError::HealthRatioLimit);
+  }
+
+  #[test]
+  fn releases_part_of_position_once_loan_is_repaid() {
+    let mint = Pubkey::new_unique();
+    let (mut context, basket_accounts) = load_withdraw(&test_loan_offer(LoanOfferStatus::BorrowerPaid, mint), mint, AMOUNT / 4);
+
+    context.withdraw_collateral_position(AMOUNT / 4, basket_accounts).unwrap();
+
+    assert_eq!(context.loan_offer.collateral_positions, vec![CollateralPosition { mint, amount: AMOUNT * 3 / 4 }]);
+    assert_eq!(token_amount(&context.collateral_vault.to_account_info()), AMOUNT * 3 / 4);
+    assert_eq!(token_amount(&context.borrower_ata.to_account_info()), AMOUNT / 4);
+  }
+
+  #[test]
+  fn closes_vault_when_position_is_emptied() {
+    let mint = Pubkey::new_unique();
+    let (mut context, basket_accounts) = load_withdraw(&test_loan_offer(LoanOfferStatus::BorrowerPaid, mint), mint, AMOUNT);
+    let borrower_lamports = context.borrower.lamports();
+    let vault_lamports = context.collateral_vault.to_account_info().lamports();
+
+    context.withdraw_collateral_position(AMOUNT, basket_accounts).unwrap();
+
+    assert!(context.loan_offer.collateral_positions.is_empty());
+    assert_eq!(token_amount(&context.borrower_ata.to_account_info()), AMOUNT);
+    assert_eq!(context.collateral_vault.to_account_info().lamports(), 0);
+    assert_eq!(context.borrower.lamports(), borrower_lamports + vault_lamports);
+  }
+}
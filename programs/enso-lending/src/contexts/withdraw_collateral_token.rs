@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+  common::{
+    PauseAction, PauseError, WithdrawCollateralEvent, ASSET_CONFIG_SEED, COLLATERAL_VAULT_SEED, ENSO_SEED, GLOBAL_CONFIG_SEED, LOAN_OFFER_ACCOUNT_SEED, SETTING_ACCOUNT_SEED
+  }, states::{
+    asset_config::AssetConfig,
+    global_config::GlobalConfig,
+    loan_offer::LoanOfferAccount,
+    setting_account::SettingAccount
+  }, validate_vault_balance, LoanOfferError, LoanOfferStatus
+};
+
+#[derive(Accounts)]
+#[instruction(loan_offer_id: String)]
+pub struct WithdrawCollateralToken<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    #[account(
+      constraint = collateral_mint_asset.key() == loan_offer.collateral_mint_token @ LoanOfferError::InvalidCollateralMintAsset,
+    )]
+    pub collateral_mint_asset: InterfaceAccount<'info, Mint>,
+    #[account(
+      constraint = lend_mint_asset.key() == loan_offer.lend_mint_token @ LoanOfferError::InvalidLendMintAsset,
+    )]
+    pub lend_mint_asset: InterfaceAccount<'info, Mint>,
+    #[account(
+      mut,
+      associated_token::mint = collateral_mint_asset,
+      associated_token::authority = borrower,
+      associated_token::token_program = token_program
+    )]
+    pub borrower_collateral_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+      constraint = !setting_account.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::TierPaused,
+      seeds = [
+          ENSO_SEED.as_ref(), 
+          SETTING_ACCOUNT_SEED.as_ref(),
+          loan_offer.tier_id.as_bytes(), 
+          crate::ID.key().as_ref(), 
+      ],
+      bump = setting_account.bump
+    )]
+    pub setting_account: Account<'info, SettingAccount>,
+    #[account(
+      mut,
+      constraint = loan_offer.status == LoanOfferStatus::FundTransferred @ LoanOfferError::NotAvailableToWithdraw,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        LOAN_OFFER_ACCOUNT_SEED.as_ref(),
+        borrower.key().as_ref(),
+        loan_offer_id.as_bytes(),
+        crate::ID.key().as_ref()
+      ],
+      bump = loan_offer.bump
+    )]
+    pub loan_offer: Account<'info, LoanOfferAccount>,
+    #[account(
+      mut,
+      token::mint = collateral_mint_asset,
+      token::authority = loan_offer,
+      token::token_program = token_program,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        COLLATERAL_VAULT_SEED.as_ref(),
+        loan_offer.key().as_ref(),
+        crate::ID.key().as_ref()
+      ],
+      bump
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    pub lend_price_feed_account: Account<'info, PriceUpdateV2>,
+    pub collateral_price_feed_account: Account<'info, PriceUpdateV2>,
+    #[account(
+      seeds = [
+        ENSO_SEED.as_ref(),
+        ASSET_CONFIG_SEED.as_ref(),
+        lend_mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = lend_asset_config.bump
+    )]
+    pub lend_asset_config: Account<'info, AssetConfig>,
+    #[account(
+      seeds = [
+        ENSO_SEED.as_ref(),
+        ASSET_CONFIG_SEED.as_ref(),
+        collateral_mint_asset.key().as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = collateral_asset_config.bump
+    )]
+    pub collateral_asset_config: Account<'info, AssetConfig>,
+    #[account(
+      constraint = !global_config.pause_flags.is_paused(PauseAction::Withdrawals) @ PauseError::ProtocolPaused,
+      seeds = [
+        ENSO_SEED.as_ref(),
+        GLOBAL_CONFIG_SEED.as_ref(),
+        crate::ID.key().as_ref(),
+      ],
+      bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawCollateralToken<'info> {
+  pub fn withdraw_collateral(
+    &mut self,
+    loan_offer_id: String,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let withdraw_amount = self.loan_offer.executable_withdraw_amount(
+      current_timestamp,
+      self.global_config.withdraw_request_cooldown,
+      None
+    )?;
+
+    let remaining_collateral = self.loan_offer.collateral_amount
+      .checked_sub(withdraw_amount)
+      .ok_or(LoanOfferError::NotEnoughAmount)?;
+
+    let ltv_bps = self.loan_offer.ltv_bps(
+      remaining_collateral,
+      &self.collateral_asset_config,
+      &self.collateral_price_feed_account,
+      &self.lend_asset_config,
+      &self.lend_price_feed_account,
+      basket_accounts
+    )?;
+
+    if ltv_bps > self.setting_account.risk_parameters.initial_ltv_bps {
+      return err!(LoanOfferError::HealthRatioLimit);
+    }
+
+    if current_timestamp > self.loan_offer.end_at() {
+      return err!(LoanOfferError::LoanOfferExpired);
+    }
+
+    self.transfer_from_vault(withdraw_amount)?;
+
+    self.loan_offer.collateral_amount = remaining_collateral;
+    self.loan_offer.clear_withdraw_request();
+
+    self.collateral_vault.reload()?;
+    validate_vault_balance(self.collateral_vault.amount, self.loan_offer.collateral_amount)?;
+
+    self.emit_event_withdraw_collateral(
+      String::from("withdraw_collateral_token"),
+      loan_offer_id,
+      withdraw_amount,
+    )?;
+
+    Ok(())
+  }
+
+  fn transfer_from_vault(&self, amount: u64) -> Result<()> {
+    let borrower = self.loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+      ENSO_SEED,
+      LOAN_OFFER_ACCOUNT_SEED,
+      borrower.as_ref(),
+      self.loan_offer.offer_id.as_bytes(),
+      program_id.as_ref(),
+      &[self.loan_offer.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+      from: self.collateral_vault.to_account_info(),
+      mint: self.collateral_mint_asset.to_account_info(),
+      to: self.borrower_collateral_ata.to_account_info(),
+      authority: self.loan_offer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+
+    transfer_checked(cpi_ctx, amount, self.collateral_mint_asset.decimals)
+  }
+
+  fn emit_event_withdraw_collateral(&mut self, label: String, loan_offer_id: String, withdraw_amount: u64) -> Result<()> {
+    emit!(WithdrawCollateralEvent {
+      borrower: self.borrower.key(),
+      loan_offer_id,
+      collateral_amount: self.loan_offer.collateral_amount,
+      withdraw_amount,
+    });
+
+    msg!(&label.clone());
+
+    Ok(())
+  }
+}
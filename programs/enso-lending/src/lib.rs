@@ -18,21 +18,232 @@ pub mod enso_lending {
 
     use super::*;
 
+    pub fn init_global_config(
+        ctx: Context<InitGlobalConfig>,
+        hot_wallet: Pubkey,
+        setting_change_delay: i64,
+        withdraw_request_cooldown: i64,
+        auction_parameters: AuctionParameters,
+        liquidation_slippage_bps: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_global_config(
+            &ctx.bumps,
+            hot_wallet,
+            setting_change_delay,
+            withdraw_request_cooldown,
+            auction_parameters,
+            liquidation_slippage_bps,
+        )?;
+        ctx.accounts
+            .emit_event_init_global_config(String::from("init_global_config"))?;
+
+        Ok(())
+    }
+
+    pub fn edit_global_config(
+        ctx: Context<EditGlobalConfig>,
+        admin: Option<Pubkey>,
+        hot_wallet: Option<Pubkey>,
+        setting_change_delay: Option<i64>,
+        withdraw_request_cooldown: Option<i64>,
+        auction_parameters: Option<AuctionParameters>,
+        liquidation_slippage_bps: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.edit_global_config(
+            admin,
+            hot_wallet,
+            setting_change_delay,
+            withdraw_request_cooldown,
+            auction_parameters,
+            liquidation_slippage_bps,
+        )?;
+        ctx.accounts
+            .emit_event_edit_global_config(String::from("edit_global_config"))?;
+
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.grant_role(&ctx.bumps, role, member)?;
+        ctx.accounts
+            .emit_event_grant_role(String::from("grant_role"), member)?;
+
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, _role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_role(member)?;
+        ctx.accounts
+            .emit_event_revoke_role(String::from("revoke_role"), member)?;
+
+        Ok(())
+    }
+
+    pub fn set_approval_config(
+        ctx: Context<SetApprovalConfig>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_approval_config(&ctx.bumps, approvers, threshold)?;
+        ctx.accounts
+            .emit_event_set_approval_config(String::from("set_approval_config"))?;
+
+        Ok(())
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        proposal_id: String,
+        action: ProposalAction,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_proposal(&ctx.bumps, proposal_id, action)?;
+        ctx.accounts
+            .emit_event_create_proposal(String::from("create_proposal"))?;
+
+        Ok(())
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, _proposal_id: String) -> Result<()> {
+        ctx.accounts.approve_proposal()?;
+        ctx.accounts
+            .emit_event_approve_proposal(String::from("approve_proposal"))?;
+
+        Ok(())
+    }
+
+    pub fn set_swap_adapter(
+        ctx: Context<SetSwapAdapter>,
+        program_id: Pubkey,
+        instruction_discriminator: [u8; 8],
+        account_layout: Vec<SwapAccountSlot>,
+    ) -> Result<()> {
+        ctx.accounts.set_swap_adapter(
+            &ctx.bumps,
+            program_id,
+            instruction_discriminator,
+            account_layout,
+        )?;
+        ctx.accounts
+            .emit_event_set_swap_adapter(String::from("set_swap_adapter"))?;
+
+        Ok(())
+    }
+
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, pause_flags: PauseFlags) -> Result<()> {
+        ctx.accounts.set_global_pause(pause_flags)?;
+        ctx.accounts
+            .emit_event_set_global_pause(String::from("set_global_pause"))?;
+
+        Ok(())
+    }
+
+    pub fn set_tier_pause(
+        ctx: Context<SetTierPause>,
+        _tier_id: String,
+        pause_flags: PauseFlags,
+    ) -> Result<()> {
+        ctx.accounts.set_tier_pause(pause_flags)?;
+        ctx.accounts
+            .emit_event_set_tier_pause(String::from("set_tier_pause"))?;
+
+        Ok(())
+    }
+
+    pub fn init_asset_config(
+        ctx: Context<InitAssetConfig>,
+        price_feed_id: String,
+        max_price_age: u64,
+        collateral_haircut_bps: u64,
+        liquidation_priority: u8,
+    ) -> Result<()> {
+        ctx.accounts.init_asset_config(
+            &ctx.bumps,
+            price_feed_id,
+            max_price_age,
+            collateral_haircut_bps,
+            liquidation_priority,
+        )?;
+        ctx.accounts
+            .emit_event_init_asset_config(String::from("init_asset_config"))?;
+
+        Ok(())
+    }
+
+    pub fn edit_asset_config(
+        ctx: Context<EditAssetConfig>,
+        price_feed_id: Option<String>,
+        max_price_age: Option<u64>,
+        enabled: Option<bool>,
+        collateral_haircut_bps: Option<u64>,
+        liquidation_priority: Option<u8>,
+    ) -> Result<()> {
+        ctx.accounts.edit_asset_config(
+            price_feed_id,
+            max_price_age,
+            enabled,
+            collateral_haircut_bps,
+            liquidation_priority,
+        )?;
+        ctx.accounts
+            .emit_event_edit_asset_config(String::from("edit_asset_config"))?;
+
+        Ok(())
+    }
+
+    pub fn init_order_book(ctx: Context<InitOrderBook>, _tier_id: String) -> Result<()> {
+        ctx.accounts.init_order_book(&ctx.bumps)?;
+        ctx.accounts
+            .emit_event_init_order_book(String::from("init_order_book"))?;
+
+        Ok(())
+    }
+
+    pub fn list_lend_offer(ctx: Context<ListLendOffer>, _offer_id: String) -> Result<()> {
+        ctx.accounts.list_lend_offer()?;
+        ctx.accounts
+            .emit_event_list_lend_offer(String::from("list_lend_offer"))?;
+
+        Ok(())
+    }
+
+    pub fn init_reserve_fund(ctx: Context<InitReserveFund>, fee_share_bps: u64) -> Result<()> {
+        ctx.accounts.init_reserve_fund(&ctx.bumps, fee_share_bps)?;
+        ctx.accounts
+            .emit_event_init_reserve_fund(String::from("init_reserve_fund"))?;
+
+        Ok(())
+    }
+
+    pub fn edit_reserve_fund(ctx: Context<EditReserveFund>, fee_share_bps: u64) -> Result<()> {
+        ctx.accounts.edit_reserve_fund(fee_share_bps)?;
+        ctx.accounts
+            .emit_event_edit_reserve_fund(String::from("edit_reserve_fund"))?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn init_setting_account(
         ctx: Context<InitSettingAccount>,
         tier_id: String,
         amount: u64,
+        min_amount: u64,
         duration: u64,
-        lender_fee_percent: f64,
-        borrower_fee_percent: f64,
+        lender_fee_bps: u64,
+        borrower_fee_bps: u64,
+        risk_parameters: RiskParameters,
     ) -> Result<()> {
         ctx.accounts.init_setting_account(
             &ctx.bumps,
             tier_id.clone(),
             amount,
+            min_amount,
             duration,
-            lender_fee_percent,
-            borrower_fee_percent,
+            lender_fee_bps,
+            borrower_fee_bps,
+            risk_parameters,
         )?;
         ctx.accounts
             .emit_init_setting_account_event(String::from("Emit event init setting account"))?;
@@ -40,19 +251,25 @@ pub mod enso_lending {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn edit_setting_account(
         ctx: Context<EditSettingAccount>,
         _tier_id: String,
         amount: Option<u64>,
+        min_amount: Option<u64>,
         duration: Option<u64>,
-        lender_fee_percent: Option<f64>,
-        borrower_fee_percent: Option<f64>,
+        lender_fee_bps: Option<u64>,
+        borrower_fee_bps: Option<u64>,
+        risk_parameters: Option<RiskParameters>,
     ) -> Result<()> {
         ctx.accounts.edit_setting_account(
+            &ctx.bumps,
             amount,
+            min_amount,
             duration,
-            lender_fee_percent,
-            borrower_fee_percent,
+            lender_fee_bps,
+            borrower_fee_bps,
+            risk_parameters,
         )?;
 
         ctx.accounts
@@ -61,6 +278,21 @@ pub mod enso_lending {
         Ok(())
     }
 
+    pub fn apply_setting_change(ctx: Context<ApplySettingChange>, _tier_id: String) -> Result<()> {
+        ctx.accounts.apply_setting_change()?;
+        ctx.accounts
+            .emit_event_apply_setting_change(String::from("apply_setting_change"))?;
+
+        Ok(())
+    }
+
+    pub fn cancel_setting_change(ctx: Context<CancelSettingChange>, _tier_id: String) -> Result<()> {
+        ctx.accounts
+            .emit_event_cancel_setting_change(String::from("cancel_setting_change"))?;
+
+        Ok(())
+    }
+
     pub fn close_setting_account(ctx: Context<CloseSettingAccount>, tier_id: String) -> Result<()> {
         ctx.accounts.close_setting_account()?;
 
@@ -72,15 +304,36 @@ pub mod enso_lending {
         Ok(())
     }
 
+    pub fn propose_tier_owner(
+        ctx: Context<ProposeTierOwner>,
+        _tier_id: String,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.propose_tier_owner(new_owner)?;
+        ctx.accounts
+            .emit_event_propose_tier_owner(String::from("propose_tier_owner"), new_owner)?;
+
+        Ok(())
+    }
+
+    pub fn accept_tier_owner(ctx: Context<AcceptTierOwner>, _tier_id: String) -> Result<()> {
+        let previous_owner = ctx.accounts.accept_tier_owner()?;
+        ctx.accounts
+            .emit_event_accept_tier_owner(String::from("accept_tier_owner"), previous_owner)?;
+
+        Ok(())
+    }
+
     pub fn create_lend_offer(
         ctx: Context<CreateLendOffer>,
         offer_id: String,
         _tier_id: String,
-        interest: f64,
+        interest_bps: u64,
+        amount: u64,
     ) -> Result<()> {
         ctx.accounts
-            .initialize_lend_offer(&ctx.bumps, offer_id, interest)?;
-        ctx.accounts.deposit()?;
+            .initialize_lend_offer(&ctx.bumps, offer_id, interest_bps, amount)?;
+        ctx.accounts.deposit(amount)?;
         ctx.accounts
             .emit_event_create_lend_offer(String::from("create_lend_offer"))?;
 
@@ -90,9 +343,9 @@ pub mod enso_lending {
     pub fn edit_lend_offer(
         ctx: Context<EditLendOffer>,
         _offer_id: String,
-        interest: f64,
+        interest_bps: u64,
     ) -> Result<()> {
-        ctx.accounts.edit_lend_offer(interest)?;
+        ctx.accounts.edit_lend_offer(interest_bps)?;
         ctx.accounts
             .emit_event_edit_lend_offer(String::from("edit_lend_offer"))?;
 
@@ -115,9 +368,6 @@ pub mod enso_lending {
     pub fn cancel_lend_offer(ctx: Context<CancelLendOffer>, _offer_id: String) -> Result<()> {
         ctx.accounts.cancel_lend_offer()?;
 
-        ctx.accounts
-            .emit_event_cancel_lend_offer(String::from("cancel_lend_offer"))?;
-
         Ok(())
     }
 
@@ -127,7 +377,8 @@ pub mod enso_lending {
         lend_offer_id: String,
         tier_id: String,
         collateral_amount: u64,
-        interest: f64,
+        borrow_amount: u64,
+        interest_bps: u64,
     ) -> Result<()> {
         ctx.accounts.initialize_loan_offer(
             &ctx.bumps,
@@ -135,7 +386,8 @@ pub mod enso_lending {
             lend_offer_id,
             tier_id,
             collateral_amount,
-            interest
+            borrow_amount,
+            interest_bps
         )?;
         ctx.accounts
             .emit_event_create_loan_offer(String::from("create_loan_offer_native"))?;
@@ -154,6 +406,38 @@ pub mod enso_lending {
         Ok(())
     }
 
+    pub fn cancel_matched_loan(ctx: Context<CancelMatchedLoan>, _offer_id: String) -> Result<()> {
+        ctx.accounts.cancel_matched_loan(&ctx.bumps)
+    }
+
+    pub fn cancel_matched_loan_token(ctx: Context<CancelMatchedLoanToken>, _offer_id: String) -> Result<()> {
+        ctx.accounts.cancel_matched_loan()
+    }
+
+    pub fn borrow_best_offer(
+        ctx: Context<CreateLoanOfferNative>,
+        offer_id: String,
+        lend_offer_id: String,
+        tier_id: String,
+        collateral_amount: u64,
+        borrow_amount: u64,
+        max_interest_bps: u64,
+    ) -> Result<()> {
+        ctx.accounts.borrow_best_offer(
+            &ctx.bumps,
+            offer_id,
+            lend_offer_id,
+            tier_id,
+            collateral_amount,
+            borrow_amount,
+            max_interest_bps
+        )?;
+        ctx.accounts
+            .emit_event_create_loan_offer(String::from("borrow_best_offer"))?;
+
+        Ok(())
+    }
+
     pub fn deposit_collateral_loan_offer_native(
         ctx: Context<DepositCollateralLoanOfferNative>,
         _offer_id: String,
@@ -169,36 +453,342 @@ pub mod enso_lending {
         Ok(())
     }
 
+    pub fn create_loan_offer_token(
+        ctx: Context<CreateLoanOfferToken>,
+        offer_id: String,
+        lend_offer_id: String,
+        tier_id: String,
+        collateral_amount: u64,
+        borrow_amount: u64,
+        interest_bps: u64,
+    ) -> Result<()> {
+        ctx.accounts.initialize_loan_offer(
+            &ctx.bumps,
+            offer_id,
+            lend_offer_id,
+            tier_id,
+            collateral_amount,
+            borrow_amount,
+            interest_bps
+        )?;
+        ctx.accounts
+            .emit_event_create_loan_offer(String::from("create_loan_offer_token"))?;
+
+        Ok(())
+    }
+
+    pub fn borrow_best_offer_token(
+        ctx: Context<CreateLoanOfferToken>,
+        offer_id: String,
+        lend_offer_id: String,
+        tier_id: String,
+        collateral_amount: u64,
+        borrow_amount: u64,
+        max_interest_bps: u64,
+    ) -> Result<()> {
+        ctx.accounts.borrow_best_offer(
+            &ctx.bumps,
+            offer_id,
+            lend_offer_id,
+            tier_id,
+            collateral_amount,
+            borrow_amount,
+            max_interest_bps
+        )?;
+        ctx.accounts
+            .emit_event_create_loan_offer(String::from("borrow_best_offer_token"))?;
+
+        Ok(())
+    }
+
+    pub fn deposit_collateral_loan_offer_token(
+        ctx: Context<DepositCollateralLoanOfferToken>,
+        _offer_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_collateral_loan_offer(amount)?;
+        ctx.accounts
+            .emit_event_deposit_collateral_loan_offer(String::from(
+                "deposit_collateral_loan_offer_token",
+            ))?;
+
+        Ok(())
+    }
+
+    pub fn deposit_collateral_position(
+        ctx: Context<DepositCollateralPosition>,
+        _offer_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_collateral_position(amount)?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_borrow_request(
+        ctx: Context<CreateBorrowRequest>,
+        request_id: String,
+        tier_id: String,
+        collateral_amount: u64,
+        amount: u64,
+        max_interest_bps: u64,
+        duration: u64,
+    ) -> Result<()> {
+        ctx.accounts.create_borrow_request(
+            &ctx.bumps,
+            request_id,
+            tier_id,
+            collateral_amount,
+            amount,
+            max_interest_bps,
+            duration
+        )?;
+        ctx.accounts
+            .emit_event_create_borrow_request(String::from("create_borrow_request"))?;
+
+        Ok(())
+    }
+
+    pub fn fill_borrow_request(
+        ctx: Context<FillBorrowRequest>,
+        _request_id: String,
+        interest_bps: u64,
+    ) -> Result<()> {
+        ctx.accounts.fill_borrow_request(&ctx.bumps, interest_bps)?;
+        ctx.accounts
+            .emit_event_fill_borrow_request(String::from("fill_borrow_request"))?;
+
+        Ok(())
+    }
+
+    pub fn cancel_borrow_request(ctx: Context<CancelBorrowRequest>, _request_id: String) -> Result<()> {
+        ctx.accounts.cancel_borrow_request()
+    }
+
     pub fn repay_loan_offer(ctx: Context<RepayLoanOffer>, _loan_offer_id: String) -> Result<()> {
+        ctx.accounts.repay_loan_offer(&ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn repay_loan_offer_token(ctx: Context<RepayLoanOfferToken>, _loan_offer_id: String) -> Result<()> {
         ctx.accounts.repay_loan_offer()?;
 
         Ok(())
     }
 
-    pub fn withdraw_collateral(
-        ctx: Context<WithdrawCollateral>,
-        loan_offer_id: String,
+    pub fn request_withdraw_collateral(
+        ctx: Context<RequestWithdrawCollateral>,
+        _loan_offer_id: String,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.request_withdraw_collateral(withdraw_amount)?;
+        ctx.accounts
+            .emit_event_request_withdraw_collateral(String::from("request_withdraw_collateral"))?;
+
+        Ok(())
+    }
+
+    pub fn request_withdraw_collateral_position(
+        ctx: Context<RequestWithdrawCollateralPosition>,
+        _loan_offer_id: String,
+        mint: Pubkey,
         withdraw_amount: u64,
     ) -> Result<()> {
-        ctx.accounts.withdraw_collateral(loan_offer_id, withdraw_amount)?;
+        ctx.accounts.request_withdraw_collateral_position(mint, withdraw_amount)?;
+        ctx.accounts
+            .emit_event_request_withdraw_collateral_position(String::from("request_withdraw_collateral_position"))?;
+
+        Ok(())
+    }
+
+    pub fn approve_withdraw_request(ctx: Context<ApproveWithdrawRequest>, _loan_offer_id: String) -> Result<()> {
+        ctx.accounts.approve_withdraw_request()?;
+        ctx.accounts
+            .emit_event_approve_withdraw_request(String::from("approve_withdraw_request"))?;
+
+        Ok(())
+    }
+
+    pub fn cancel_withdraw_request(ctx: Context<CancelWithdrawRequest>, _loan_offer_id: String) -> Result<()> {
+        ctx.accounts.cancel_withdraw_request()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawCollateral<'info>>,
+        loan_offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_collateral(&ctx.bumps, loan_offer_id, ctx.remaining_accounts)?;
         
         Ok(())
     }
 
-    pub fn start_liquidate_contract(
-        ctx: Context<LiquidateCollateral>,
+    pub fn withdraw_collateral_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawCollateralToken<'info>>,
+        loan_offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_collateral(loan_offer_id, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_collateral_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawCollateralPosition<'info>>,
+        _loan_offer_id: String,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_collateral_position(withdraw_amount, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn start_liquidate_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCollateral<'info>>,
         _offer_id: String,
-        liquidating_price: u64,
-        liquidating_at: u64,
     ) -> Result<()> {
         ctx.accounts
-            .start_liquidate_contract(liquidating_price, liquidating_at)?;
+            .start_liquidate_contract(&ctx.bumps, ctx.remaining_accounts)?;
         ctx.accounts
             .emit_event_start_liquidate_contract(String::from("liquidating_collateral"))?;
 
         Ok(())
     }
 
+    pub fn start_liquidate_contract_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCollateralToken<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .start_liquidate_contract(ctx.remaining_accounts)?;
+        ctx.accounts
+            .emit_event_start_liquidate_contract(String::from("liquidating_collateral_token"))?;
+
+        Ok(())
+    }
+
+    pub fn liquidate_collateral_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCollateralPosition<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .liquidate_collateral_position(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn partial_liquidate_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCollateral<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .partial_liquidate_contract(&ctx.bumps, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn partial_liquidate_contract_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCollateralToken<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .partial_liquidate_contract(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn liquidate<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts.liquidate(&ctx.bumps, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn liquidate_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateToken<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts.liquidate(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn swap_liquidate<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapLiquidate<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts.swap_liquidate(&ctx.bumps, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn swap_liquidate_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapLiquidateToken<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts.swap_liquidate(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn start_liquidation_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StartLiquidationAuction<'info>>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .start_liquidation_auction(&ctx.bumps, ctx.remaining_accounts)?;
+        ctx.accounts
+            .emit_event_start_liquidation_auction(String::from("start_liquidation_auction"))?;
+
+        Ok(())
+    }
+
+    pub fn bid_liquidation_auction(
+        ctx: Context<BidLiquidationAuction>,
+        _offer_id: String,
+        collateral_amount: u64,
+        max_lend_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .bid_liquidation_auction(&ctx.bumps, collateral_amount, max_lend_amount)?;
+
+        Ok(())
+    }
+
+    pub fn bid_liquidation_auction_token(
+        ctx: Context<BidLiquidationAuctionToken>,
+        _offer_id: String,
+        collateral_amount: u64,
+        max_lend_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .bid_liquidation_auction(collateral_amount, max_lend_amount)?;
+
+        Ok(())
+    }
+
+    pub fn settle_liquidation_auction(ctx: Context<SettleLiquidationAuction>, _offer_id: String) -> Result<()> {
+        ctx.accounts.settle_liquidation_auction(&ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn settle_liquidation_auction_token(
+        ctx: Context<SettleLiquidationAuctionToken>,
+        _offer_id: String,
+    ) -> Result<()> {
+        ctx.accounts.settle_liquidation_auction()?;
+
+        Ok(())
+    }
+
     pub fn finish_liquidate_contract(
         ctx: Context<SystemLiquidateLoanOffer>,
         _loan_offer_id: String,
@@ -216,6 +806,12 @@ pub mod enso_lending {
         Ok(())
     }
 
+    pub fn cover_bad_debt(ctx: Context<CoverBadDebt>, _loan_offer_id: String, amount: u64) -> Result<()> {
+        ctx.accounts.cover_bad_debt(amount)?;
+
+        Ok(())
+    }
+
     pub fn system_finish_loan_offer(
       ctx: Context<SystemFinishLoanOffer>,
       _loan_offer_id: String,
@@ -0,0 +1,18 @@
+pub use anchor_lang::prelude::*;
+
+use crate::MAX_APPROVERS;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ApprovalConfig {
+  #[max_len(MAX_APPROVERS)]
+  pub approvers: Vec<Pubkey>,
+  pub threshold: u8,
+  pub bump: u8
+}
+
+impl ApprovalConfig {
+  pub fn is_approver(&self, key: &Pubkey) -> bool {
+    self.approvers.contains(key)
+  }
+}
@@ -0,0 +1,124 @@
+pub use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{convert_to_usd_value, mul_div, to_u64, AssetConfigError, MathError, Rounding, BPS_DENOMINATOR};
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct AssetConfig {
+  pub mint: Pubkey,
+  pub price_feed_id: [u8; 32],
+  pub max_price_age: u64,
+  pub decimals: u8,
+  pub enabled: bool,
+  pub collateral_haircut_bps: u64,
+  /// Basket positions in assets with a lower value are seized first during liquidation.
+  pub liquidation_priority: u8,
+  pub bump: u8
+}
+
+impl AssetConfig {
+  pub fn validate_parameters(max_price_age: u64, collateral_haircut_bps: u64) -> Result<()> {
+    if max_price_age == 0 {
+      return err!(AssetConfigError::ZeroMaxPriceAge);
+    }
+
+    if collateral_haircut_bps > BPS_DENOMINATOR {
+      return err!(AssetConfigError::HaircutTooHigh);
+    }
+
+    Ok(())
+  }
+
+  pub fn usd_value(&self, price_feed_account: &PriceUpdateV2, amount: u64, rounding: Rounding) -> Result<u128> {
+    convert_to_usd_value(
+      price_feed_account,
+      &self.price_feed_id,
+      self.max_price_age,
+      amount,
+      self.decimals,
+      rounding
+    )
+  }
+
+  /// USD price of one whole token, with `USD_DECIMALS` decimals.
+  pub fn unit_price(&self, price_feed_account: &PriceUpdateV2) -> Result<u64> {
+    let unit = 10u64.checked_pow(self.decimals as u32).ok_or(MathError::Overflow)?;
+
+    to_u64(self.usd_value(price_feed_account, unit, Rounding::Down)?)
+  }
+
+  /// USD value of `amount` when held as collateral, after the haircut and rounded down.
+  pub fn collateral_value(&self, price_feed_account: &PriceUpdateV2, amount: u64) -> Result<u128> {
+    let value = self.usd_value(price_feed_account, amount, Rounding::Down)?;
+
+    mul_div(
+      value,
+      (BPS_DENOMINATOR - self.collateral_haircut_bps) as u128,
+      BPS_DENOMINATOR as u128,
+      Rounding::Down
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pyth_solana_receiver_sdk::error::GetPriceError;
+
+  use super::*;
+  use crate::{test_utils::*, OracleError};
+
+  fn sol_asset_config() -> AssetConfig {
+    asset_config(Pubkey::new_unique(), COLLATERAL_FEED_ID, 9)
+  }
+
+  #[test]
+  fn prices_amount_in_usd_decimals() {
+    let price_update = price_update(COLLATERAL_FEED_ID, usd_price(100));
+    let asset_config = sol_asset_config();
+
+    assert_eq!(asset_config.usd_value(&price_update, 1_000_000_000, Rounding::Down).unwrap(), 100_000_000);
+    assert_eq!(asset_config.usd_value(&price_update, 1, Rounding::Down).unwrap(), 0);
+    assert_eq!(asset_config.usd_value(&price_update, 1, Rounding::Up).unwrap(), 1);
+    assert_eq!(asset_config.unit_price(&price_update).unwrap(), 100_000_000);
+  }
+
+  #[test]
+  fn collateral_value_applies_haircut() {
+    let price_update = price_update(COLLATERAL_FEED_ID, usd_price(100));
+    let mut asset_config = sol_asset_config();
+    asset_config.collateral_haircut_bps = 1_000;
+
+    assert_eq!(asset_config.collateral_value(&price_update, 1_000_000_000).unwrap(), 90_000_000);
+  }
+
+  #[test]
+  fn rejects_price_of_other_feed() {
+    let price_update = price_update(LEND_FEED_ID, usd_price(1));
+
+    assert_error(sol_asset_config().usd_value(&price_update, 1, Rounding::Down), GetPriceError::MismatchedFeedId);
+  }
+
+  #[test]
+  fn rejects_price_older_than_max_age() {
+    let price_update = price_update(COLLATERAL_FEED_ID, usd_price(100));
+    let asset_config = sol_asset_config();
+    set_now(now() + asset_config.max_price_age as i64 + 1);
+
+    assert_error(asset_config.usd_value(&price_update, 1, Rounding::Down), GetPriceError::PriceTooOld);
+  }
+
+  #[test]
+  fn rejects_non_positive_price() {
+    let price_update = price_update(COLLATERAL_FEED_ID, 0);
+
+    assert_error(sol_asset_config().usd_value(&price_update, 1, Rounding::Down), OracleError::InvalidPrice);
+  }
+
+  #[test]
+  fn validates_registry_parameters() {
+    assert!(AssetConfig::validate_parameters(60, BPS_DENOMINATOR).is_ok());
+    assert_error(AssetConfig::validate_parameters(0, 0), AssetConfigError::ZeroMaxPriceAge);
+    assert_error(AssetConfig::validate_parameters(60, BPS_DENOMINATOR + 1), AssetConfigError::HaircutTooHigh);
+  }
+}
@@ -0,0 +1,59 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{BorrowRequestError, BorrowRequestStatus};
+
+/// Loan asked for by a borrower. The collateral sits in the request escrow, owned by this
+/// account, until a lender fills the request or the borrower cancels it.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct BorrowRequestAccount {
+  #[max_len(50)]
+  pub request_id: String,
+  #[max_len(50)]
+  pub tier_id: String,
+  pub borrower: Pubkey,
+  pub lend_mint_token: Pubkey,
+  pub collateral_mint_token: Pubkey,
+  pub collateral_amount: u64,
+  pub amount: u64,
+  pub max_interest_bps: u64,
+  pub duration: u64,
+  pub bump: u8,
+  pub status: BorrowRequestStatus
+}
+
+impl BorrowRequestAccount {
+  pub fn validate_interest(&self, interest_bps: u64) -> Result<()> {
+    if interest_bps == 0 {
+      return err!(BorrowRequestError::InterestGreaterThanZero);
+    }
+
+    if interest_bps > self.max_interest_bps {
+      return err!(BorrowRequestError::InterestExceedsMax);
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  #[test]
+  fn accepts_interest_up_to_borrower_max() {
+    let borrow_request = borrow_request(Pubkey::new_unique(), "request", Pubkey::new_unique(), Pubkey::new_unique());
+
+    borrow_request.validate_interest(1).unwrap();
+    borrow_request.validate_interest(1_500).unwrap();
+  }
+
+  #[test]
+  fn rejects_zero_or_excess_interest() {
+    let borrow_request = borrow_request(Pubkey::new_unique(), "request", Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_error(borrow_request.validate_interest(0), BorrowRequestError::InterestGreaterThanZero);
+    assert_error(borrow_request.validate_interest(1_501), BorrowRequestError::InterestExceedsMax);
+  }
+}
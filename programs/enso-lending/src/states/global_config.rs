@@ -0,0 +1,16 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{AuctionParameters, PauseFlags};
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct GlobalConfig {
+  pub admin: Pubkey,
+  pub hot_wallet: Pubkey,
+  pub pause_flags: PauseFlags,
+  pub setting_change_delay: i64,
+  pub withdraw_request_cooldown: i64,
+  pub auction_parameters: AuctionParameters,
+  pub liquidation_slippage_bps: u64,
+  pub bump: u8
+}
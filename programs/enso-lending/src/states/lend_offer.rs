@@ -1,18 +1,121 @@
 pub use anchor_lang::prelude::*;
 
-use crate::LendOfferStatus;
+use crate::{LendOfferError, LendOfferStatus, MathError};
 
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct LendOfferAccount {
-  pub interest: f64,
-  pub lender_fee_percent: f64,
+  pub interest_bps: u64,
+  pub lender_fee_bps: u64,
   pub duration: u64,
   #[max_len(50)]
   pub offer_id: String,
+  #[max_len(50)]
+  pub tier_id: String,
   pub lender: Pubkey,
   pub lend_mint_token: Pubkey,
   pub amount: u64,
+  /// Part of `amount` still available to borrowers.
+  pub remaining_amount: u64,
+  /// Number of loans drawn from this offer so far.
+  pub loan_count: u64,
   pub bump: u8,
   pub status: LendOfferStatus
 }
+
+impl LendOfferAccount {
+  /// Reserves `draw_amount` for a new loan. The offer is `Loaned` once nothing remains.
+  pub fn draw(&mut self, draw_amount: u64) -> Result<()> {
+    if draw_amount == 0 || draw_amount > self.remaining_amount {
+      return err!(LendOfferError::InvalidDrawAmount);
+    }
+
+    self.remaining_amount -= draw_amount;
+    self.loan_count = self.loan_count.checked_add(1).ok_or(MathError::Overflow)?;
+
+    if self.remaining_amount == 0 {
+      self.status = LendOfferStatus::Loaned;
+    }
+
+    Ok(())
+  }
+
+  /// Gives back `draw_amount` of a loan that was canceled before it was funded, so the offer
+  /// can be drawn again.
+  pub fn undraw(&mut self, draw_amount: u64) -> Result<()> {
+    let remaining_amount = self.remaining_amount.checked_add(draw_amount).ok_or(MathError::Overflow)?;
+    if remaining_amount > self.amount {
+      return err!(LendOfferError::InvalidDrawAmount);
+    }
+
+    self.remaining_amount = remaining_amount;
+    self.loan_count = self.loan_count.checked_sub(1).ok_or(MathError::Overflow)?;
+
+    if self.status == LendOfferStatus::Loaned {
+      self.status = LendOfferStatus::Created;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn offer() -> LendOfferAccount {
+    lend_offer(Pubkey::new_unique(), "lend_offer", Pubkey::new_unique(), 100_000_000)
+  }
+
+  #[test]
+  fn draws_partially_and_stays_open() {
+    let mut lend_offer = offer();
+
+    lend_offer.draw(40_000_000).unwrap();
+    lend_offer.draw(30_000_000).unwrap();
+
+    assert_eq!(lend_offer.remaining_amount, 30_000_000);
+    assert_eq!(lend_offer.loan_count, 2);
+    assert_eq!(lend_offer.status, LendOfferStatus::Created);
+  }
+
+  #[test]
+  fn last_draw_marks_offer_loaned() {
+    let mut lend_offer = offer();
+
+    lend_offer.draw(60_000_000).unwrap();
+    lend_offer.draw(40_000_000).unwrap();
+
+    assert_eq!(lend_offer.remaining_amount, 0);
+    assert_eq!(lend_offer.status, LendOfferStatus::Loaned);
+  }
+
+  #[test]
+  fn rejects_zero_or_oversized_draw() {
+    let mut lend_offer = offer();
+
+    assert_error(lend_offer.draw(0), LendOfferError::InvalidDrawAmount);
+    assert_error(lend_offer.draw(100_000_001), LendOfferError::InvalidDrawAmount);
+  }
+
+  #[test]
+  fn undraw_reopens_loaned_offer() {
+    let mut lend_offer = offer();
+    lend_offer.draw(100_000_000).unwrap();
+
+    lend_offer.undraw(100_000_000).unwrap();
+
+    assert_eq!(lend_offer.remaining_amount, 100_000_000);
+    assert_eq!(lend_offer.loan_count, 0);
+    assert_eq!(lend_offer.status, LendOfferStatus::Created);
+  }
+
+  #[test]
+  fn rejects_undraw_above_offer_amount() {
+    let mut lend_offer = offer();
+    lend_offer.draw(40_000_000).unwrap();
+
+    assert_error(lend_offer.undraw(40_000_001), LendOfferError::InvalidDrawAmount);
+  }
+}
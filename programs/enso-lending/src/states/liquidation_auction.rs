@@ -0,0 +1,149 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{mul_div, split_liquidation_proceeds, to_u64, Rounding};
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct LiquidationAuction {
+  pub loan_offer: Pubkey,
+  pub initiator: Pubkey,
+  /// Collateral put up for auction when it started. Lot prices are quoted for this amount.
+  pub collateral_amount: u64,
+  pub remaining_collateral: u64,
+  pub start_lot_price: u64,
+  pub floor_lot_price: u64,
+  pub started_at: i64,
+  pub duration: i64,
+  /// Lend amount the auction has to raise to cover the loan.
+  pub debt_amount: u64,
+  pub proceeds: u64,
+  pub bump: u8
+}
+
+impl LiquidationAuction {
+  pub fn ends_at(&self) -> i64 {
+    self.started_at + self.duration
+  }
+
+  /// Price of the whole lot in the lend mint, decaying linearly from the start price to the floor.
+  pub fn lot_price(&self, current_timestamp: i64) -> Result<u64> {
+    let elapsed = current_timestamp.saturating_sub(self.started_at).clamp(0, self.duration);
+    let decay = mul_div(
+      (self.start_lot_price - self.floor_lot_price) as u128,
+      elapsed as u128,
+      self.duration as u128,
+      Rounding::Down
+    )?;
+
+    to_u64(self.start_lot_price as u128 - decay)
+  }
+
+  /// Collateral a bid for `requested_amount` actually fills, without raising more than the
+  /// outstanding debt, and the lend amount the bidder pays for it.
+  pub fn fill(&self, requested_amount: u64, current_timestamp: i64) -> Result<(u64, u64)> {
+    let lot_price = self.lot_price(current_timestamp)? as u128;
+    let outstanding_debt = self.debt_amount.saturating_sub(self.proceeds) as u128;
+
+    let mut amount = requested_amount.min(self.remaining_collateral);
+    if lot_price > 0 {
+      let amount_for_debt = mul_div(outstanding_debt, self.collateral_amount as u128, lot_price, Rounding::Up)?;
+      amount = amount.min(to_u64(amount_for_debt).unwrap_or(u64::MAX));
+    }
+
+    let cost = mul_div(amount as u128, lot_price, self.collateral_amount as u128, Rounding::Up)?;
+
+    Ok((amount, to_u64(cost)?))
+  }
+
+  pub fn is_debt_covered(&self) -> bool {
+    self.proceeds >= self.debt_amount
+  }
+
+  pub fn is_settleable(&self, current_timestamp: i64) -> bool {
+    self.is_debt_covered()
+      || self.remaining_collateral == 0
+      || current_timestamp > self.ends_at()
+  }
+
+  /// Splits the proceeds into the lender payout, the protocol fees and the borrower surplus,
+  /// paying the lender first when the auction fell short of the debt.
+  pub fn settlement_amounts(&self, lender_due: u64, fee_due: u64) -> Result<(u64, u64, u64)> {
+    split_liquidation_proceeds(self.proceeds, lender_due, fee_due)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn test_auction() -> LiquidationAuction {
+    liquidation_auction(Pubkey::new_unique(), Pubkey::new_unique())
+  }
+
+  #[test]
+  fn lot_price_decays_linearly_to_floor() {
+    let auction = test_auction();
+
+    assert_eq!(auction.lot_price(auction.started_at - 1).unwrap(), 132_000_000);
+    assert_eq!(auction.lot_price(auction.started_at).unwrap(), 132_000_000);
+    assert_eq!(auction.lot_price(auction.started_at + 1_800).unwrap(), 114_000_000);
+    assert_eq!(auction.lot_price(auction.ends_at()).unwrap(), 96_000_000);
+    assert_eq!(auction.lot_price(auction.ends_at() + 1_000).unwrap(), 96_000_000);
+  }
+
+  #[test]
+  fn fill_prices_slice_of_the_lot() {
+    let auction = test_auction();
+
+    assert_eq!(auction.fill(500_000_000, auction.started_at).unwrap(), (500_000_000, 66_000_000));
+    assert_eq!(auction.fill(500_000_000, auction.ends_at()).unwrap(), (500_000_000, 48_000_000));
+  }
+
+  #[test]
+  fn fill_stops_once_debt_is_covered() {
+    let mut auction = test_auction();
+
+    assert_eq!(auction.fill(1_000_000_000, auction.started_at).unwrap(), (837_121_213, 110_500_001));
+
+    auction.proceeds = 100_000_000;
+    assert_eq!(auction.fill(1_000_000_000, auction.started_at).unwrap(), (79_545_455, 10_500_001));
+  }
+
+  #[test]
+  fn fill_is_capped_at_remaining_collateral() {
+    let mut auction = test_auction();
+    auction.remaining_collateral = 100_000_000;
+
+    assert_eq!(auction.fill(500_000_000, auction.ends_at()).unwrap(), (100_000_000, 9_600_000));
+  }
+
+  #[test]
+  fn settles_once_debt_is_covered_collateral_sold_or_time_is_up() {
+    let mut auction = test_auction();
+    assert!(!auction.is_settleable(auction.started_at));
+    assert!(auction.is_settleable(auction.ends_at() + 1));
+
+    auction.remaining_collateral = 0;
+    assert!(auction.is_settleable(auction.started_at));
+
+    auction.remaining_collateral = 1;
+    auction.proceeds = auction.debt_amount;
+    assert!(auction.is_debt_covered());
+    assert!(auction.is_settleable(auction.started_at));
+  }
+
+  #[test]
+  fn settlement_pays_lender_then_fees_then_borrower() {
+    let mut auction = test_auction();
+
+    auction.proceeds = 120_000_000;
+    assert_eq!(auction.settlement_amounts(109_500_000, 1_000_000).unwrap(), (109_500_000, 1_000_000, 9_500_000));
+
+    auction.proceeds = 110_000_000;
+    assert_eq!(auction.settlement_amounts(109_500_000, 1_000_000).unwrap(), (109_500_000, 500_000, 0));
+
+    auction.proceeds = 90_000_000;
+    assert_eq!(auction.settlement_amounts(109_500_000, 1_000_000).unwrap(), (90_000_000, 0, 0));
+  }
+}
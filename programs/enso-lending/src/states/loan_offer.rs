@@ -1,6 +1,11 @@
 pub use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::LoanOfferStatus;
+use crate::{
+  allocate_seizure, calculate_fee, calculate_interest, calculate_ltv_bps, calculate_partial_liquidation_repay_value, mul_div, to_u64, AssetConfig,
+  CollateralPosition, CollateralPositionError, LiquidateOfferError, LoanOfferStatus, MathError, RiskParameters, Rounding,
+  WithdrawRequestError, BPS_DENOMINATOR, MAX_COLLATERAL_POSITIONS
+};
 
 #[account]
 #[derive(InitSpace, Debug)]
@@ -9,9 +14,9 @@ pub struct LoanOfferAccount {
   pub tier_id: String,
   #[max_len(50)]
   pub lend_offer_id: String,
-  pub interest: f64,
+  pub interest_bps: u64,
   pub borrow_amount: u64,
-  pub lender_fee_percent: f64,
+  pub lender_fee_bps: u64,
   pub duration: u64,
   pub lend_mint_token: Pubkey,
   pub lender: Pubkey,
@@ -21,13 +26,536 @@ pub struct LoanOfferAccount {
   pub collateral_mint_token: Pubkey,
   pub collateral_amount: u64,
   pub request_withdraw_amount: Option<u64>,
+  pub request_withdraw_at: Option<i64>,
+  pub request_withdraw_approved: bool,
+  /// Basket position the pending withdraw request is for, `None` for the primary collateral.
+  pub request_withdraw_mint: Option<Pubkey>,
+  #[max_len(MAX_COLLATERAL_POSITIONS)]
+  pub collateral_positions: Vec<CollateralPosition>,
   pub status: LoanOfferStatus,
-  pub borrower_fee_percent: f64,
+  pub borrower_fee_bps: u64,
   pub started_at: i64,
   pub liquidating_at: Option<u64>,
   pub liquidating_price: Option<u64>,
   #[max_len(50)]
   pub liquidated_tx: Option<String>,
   pub liquidated_price: Option<u64>,
+  pub deficit_amount: u64,
   pub bump: u8,
 }
+
+impl LoanOfferAccount {
+  /// Interest over the full loan duration, rounded up. Borrower debt and lender payout
+  /// are both derived from this figure so the two sides always agree.
+  pub fn interest_amount(&self) -> Result<u64> {
+    calculate_interest(self.borrow_amount, self.interest_bps, self.duration, Rounding::Up)
+  }
+
+  pub fn borrower_fee_amount(&self) -> Result<u64> {
+    calculate_fee(self.interest_amount()?, self.borrower_fee_bps, Rounding::Up)
+  }
+
+  pub fn lender_fee_amount(&self) -> Result<u64> {
+    calculate_fee(self.interest_amount()?, self.lender_fee_bps, Rounding::Up)
+  }
+
+  /// Principal and interest owed to the lender, net of the lender fee.
+  pub fn lender_repay_amount(&self) -> Result<u64> {
+    to_u64(
+      (self.borrow_amount as u128 + self.interest_amount()? as u128)
+        .checked_sub(self.lender_fee_amount()? as u128)
+        .ok_or(MathError::Overflow)?
+    )
+  }
+
+  /// Principal, interest and borrower fee owed by the borrower.
+  pub fn total_repay_amount(&self) -> Result<u64> {
+    to_u64(
+      self.borrow_amount as u128
+        + self.interest_amount()? as u128
+        + self.borrower_fee_amount()? as u128
+    )
+  }
+
+  /// What the lender is still owed: the recorded deficit once the loan has defaulted, the full
+  /// lender repayment before that.
+  pub fn lender_due_amount(&self) -> Result<u64> {
+    if self.deficit_amount > 0 {
+      return Ok(self.deficit_amount);
+    }
+
+    self.lender_repay_amount()
+  }
+
+  /// Lender and protocol fee amounts an auction of the collateral has to raise. Fees are already
+  /// forgone on a defaulted loan, so its collateral is only auctioned towards the deficit.
+  pub fn auction_dues(&self) -> Result<(u64, u64)> {
+    if self.deficit_amount > 0 {
+      return Ok((self.deficit_amount, 0));
+    }
+
+    Ok((self.lender_repay_amount()?, self.borrower_fee_amount()? + self.lender_fee_amount()?))
+  }
+
+  /// Records the part of the lender payout that `lender_received_amount` failed to cover and
+  /// returns it. Unpaid protocol fees are forgone and never count as bad debt. A loan with a
+  /// deficit can no longer be made whole and is `Defaulted`.
+  pub fn record_deficit(&mut self, lender_received_amount: u64) -> Result<u64> {
+    let deficit_amount = self.lender_due_amount()?.saturating_sub(lender_received_amount);
+    self.deficit_amount = deficit_amount;
+
+    if deficit_amount > 0 {
+      self.status = LoanOfferStatus::Defaulted;
+    }
+
+    Ok(deficit_amount)
+  }
+
+  pub fn end_at(&self) -> i64 {
+    self.started_at + self.duration as i64
+  }
+
+  /// LTV of the loan as if its primary collateral were `collateral_amount`, priced through
+  /// the asset registry and summed with every basket position.
+  pub fn ltv_bps<'info>(
+    &self,
+    collateral_amount: u64,
+    collateral_asset_config: &AssetConfig,
+    collateral_price_feed_account: &PriceUpdateV2,
+    lend_asset_config: &AssetConfig,
+    lend_price_feed_account: &PriceUpdateV2,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<u64> {
+    let collateral_value = collateral_asset_config
+      .collateral_value(collateral_price_feed_account, collateral_amount)?
+      .checked_add(self.basket_collateral_value(basket_accounts)?)
+      .ok_or(MathError::Overflow)?;
+    let debt_value = lend_asset_config.usd_value(lend_price_feed_account, self.borrow_amount, Rounding::Up)?;
+
+    calculate_ltv_bps(debt_value, collateral_value)
+  }
+
+  /// Fails unless the loan is past its end or its LTV reached the liquidation threshold.
+  #[allow(clippy::too_many_arguments)]
+  pub fn validate_liquidatable<'info>(
+    &self,
+    current_timestamp: i64,
+    collateral_asset_config: &AssetConfig,
+    collateral_price_feed_account: &PriceUpdateV2,
+    lend_asset_config: &AssetConfig,
+    lend_price_feed_account: &PriceUpdateV2,
+    risk_parameters: &RiskParameters,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<()> {
+    if current_timestamp > self.end_at() {
+      return Ok(());
+    }
+
+    let ltv_bps = self.ltv_bps(
+      self.collateral_amount,
+      collateral_asset_config,
+      collateral_price_feed_account,
+      lend_asset_config,
+      lend_price_feed_account,
+      basket_accounts
+    )?;
+
+    if ltv_bps < risk_parameters.liquidation_threshold_bps {
+      return err!(LiquidateOfferError::LoanNotLiquidatable);
+    }
+
+    Ok(())
+  }
+
+  /// Primary collateral to seize and debt to write off so the loan returns to the initial LTV,
+  /// with the liquidation penalty paid out of the seized collateral.
+  pub fn partial_liquidation_amounts<'info>(
+    &self,
+    collateral_asset_config: &AssetConfig,
+    collateral_price_feed_account: &PriceUpdateV2,
+    lend_asset_config: &AssetConfig,
+    lend_price_feed_account: &PriceUpdateV2,
+    risk_parameters: &RiskParameters,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<(u64, u64)> {
+    let primary_value = collateral_asset_config.collateral_value(collateral_price_feed_account, self.collateral_amount)?;
+    let collateral_value = primary_value
+      .checked_add(self.basket_collateral_value(basket_accounts)?)
+      .ok_or(MathError::Overflow)?;
+    let debt_value = lend_asset_config.usd_value(lend_price_feed_account, self.borrow_amount, Rounding::Up)?;
+
+    let repay_value = calculate_partial_liquidation_repay_value(
+      debt_value,
+      collateral_value,
+      risk_parameters.initial_ltv_bps,
+      risk_parameters.liquidation_penalty_bps
+    )?;
+    let seized_value = mul_div(
+      repay_value,
+      (BPS_DENOMINATOR + risk_parameters.liquidation_penalty_bps) as u128,
+      BPS_DENOMINATOR as u128,
+      Rounding::Up
+    )?;
+
+    let seized_collateral = to_u64(mul_div(seized_value, self.collateral_amount as u128, primary_value, Rounding::Up)?)?;
+    let debt_reduction = to_u64(mul_div(repay_value, self.borrow_amount as u128, debt_value, Rounding::Down)?)?;
+
+    if seized_collateral >= self.collateral_amount || debt_reduction >= self.borrow_amount {
+      return err!(LiquidateOfferError::PartialLiquidationNotPossible);
+    }
+
+    Ok((seized_collateral, debt_reduction))
+  }
+
+  /// Writes `debt_reduction` off the principal and returns the part of the lender payout it
+  /// settles: the principal plus the interest it would have earned, net of the lender fee.
+  pub fn write_off_debt(&mut self, debt_reduction: u64) -> Result<u64> {
+    let lender_repay_amount = self.lender_repay_amount()?;
+    self.borrow_amount = self.borrow_amount.checked_sub(debt_reduction).ok_or(MathError::Overflow)?;
+
+    Ok(lender_repay_amount.checked_sub(self.lender_repay_amount()?).ok_or(MathError::Overflow)?)
+  }
+
+  /// Oracle value of `collateral_amount` of the primary collateral, expressed in the lend mint.
+  pub fn collateral_value_in_lend(
+    &self,
+    collateral_amount: u64,
+    collateral_asset_config: &AssetConfig,
+    collateral_price_feed_account: &PriceUpdateV2,
+    lend_asset_config: &AssetConfig,
+    lend_price_feed_account: &PriceUpdateV2
+  ) -> Result<u128> {
+    let collateral_value = collateral_asset_config.usd_value(collateral_price_feed_account, collateral_amount, Rounding::Down)?;
+    let lend_unit = 10u64
+      .checked_pow(lend_asset_config.decimals as u32)
+      .ok_or(MathError::Overflow)?;
+    let lend_unit_value = lend_asset_config.usd_value(lend_price_feed_account, lend_unit, Rounding::Up)?;
+
+    mul_div(collateral_value, lend_unit as u128, lend_unit_value, Rounding::Down)
+  }
+
+  /// Collateral owed to a liquidator who repays the whole debt: the repaid value plus the
+  /// liquidation penalty as a bonus, priced without haircut and capped at what the loan holds.
+  /// The primary collateral and the basket positions are seized in ascending
+  /// `liquidation_priority`. Returns the primary amount and one amount per basket position.
+  #[allow(clippy::too_many_arguments)]
+  pub fn liquidator_collateral_amounts<'info>(
+    &self,
+    collateral_asset_config: &AssetConfig,
+    collateral_price_feed_account: &PriceUpdateV2,
+    lend_asset_config: &AssetConfig,
+    lend_price_feed_account: &PriceUpdateV2,
+    liquidation_penalty_bps: u64,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<(u64, Vec<u64>)> {
+    let debt_value = lend_asset_config.usd_value(lend_price_feed_account, self.total_repay_amount()?, Rounding::Up)?;
+    let seized_value = mul_div(
+      debt_value,
+      (BPS_DENOMINATOR + liquidation_penalty_bps) as u128,
+      BPS_DENOMINATOR as u128,
+      Rounding::Down
+    )?;
+
+    let mut holdings = vec![(
+      collateral_asset_config.liquidation_priority,
+      self.collateral_amount,
+      collateral_asset_config.usd_value(collateral_price_feed_account, self.collateral_amount, Rounding::Up)?
+    )];
+    for (position, (asset_config, price_feed_account)) in self.collateral_positions.iter().zip(self.load_basket_accounts(basket_accounts)?) {
+      let value = asset_config.usd_value(&price_feed_account, position.amount, Rounding::Up)?;
+      holdings.push((asset_config.liquidation_priority, position.amount, value));
+    }
+
+    let mut seizure_order: Vec<usize> = (0..holdings.len()).collect();
+    seizure_order.sort_by_key(|&index| holdings[index].0);
+    let ordered_holdings: Vec<(u64, u128)> = seizure_order
+      .iter()
+      .map(|&index| (holdings[index].1, holdings[index].2))
+      .collect();
+
+    let mut seized_amounts = vec![0; holdings.len()];
+    for (&index, amount) in seizure_order.iter().zip(allocate_seizure(seized_value, &ordered_holdings)?) {
+      seized_amounts[index] = amount;
+    }
+
+    let position_amounts = seized_amounts.split_off(1);
+    Ok((seized_amounts[0], position_amounts))
+  }
+
+  /// Collateral value of the basket positions. `basket_accounts` holds an asset config and
+  /// a price update account for each position, in the order the positions are stored.
+  pub fn basket_collateral_value<'info>(&self, basket_accounts: &'info [AccountInfo<'info>]) -> Result<u128> {
+    let mut total_value: u128 = 0;
+    for (position, (asset_config, price_feed_account)) in self.collateral_positions.iter().zip(self.load_basket_accounts(basket_accounts)?) {
+      let value = asset_config.collateral_value(&price_feed_account, position.amount)?;
+      total_value = total_value.checked_add(value).ok_or(MathError::Overflow)?;
+    }
+
+    Ok(total_value)
+  }
+
+  fn load_basket_accounts<'info>(
+    &self,
+    basket_accounts: &'info [AccountInfo<'info>]
+  ) -> Result<Vec<(Account<'info, AssetConfig>, Account<'info, PriceUpdateV2>)>> {
+    if basket_accounts.len() != self.collateral_positions.len() * 2 {
+      return err!(CollateralPositionError::PositionAccountsMismatch);
+    }
+
+    let mut loaded_accounts = Vec::with_capacity(self.collateral_positions.len());
+    for (position, accounts) in self.collateral_positions.iter().zip(basket_accounts.chunks(2)) {
+      let asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+      if asset_config.mint != position.mint {
+        return err!(CollateralPositionError::PositionAccountsMismatch);
+      }
+
+      loaded_accounts.push((asset_config, Account::<PriceUpdateV2>::try_from(&accounts[1])?));
+    }
+
+    Ok(loaded_accounts)
+  }
+
+  /// Amount of the pending withdraw request for `mint`, once an operator approved it or
+  /// `cooldown` seconds have passed since it was filed. `mint` is `None` for the primary collateral.
+  pub fn executable_withdraw_amount(&self, current_timestamp: i64, cooldown: i64, mint: Option<Pubkey>) -> Result<u64> {
+    let (Some(amount), Some(requested_at)) = (self.request_withdraw_amount, self.request_withdraw_at) else {
+      return err!(WithdrawRequestError::NoPendingRequest);
+    };
+
+    if self.request_withdraw_mint != mint {
+      return err!(WithdrawRequestError::RequestMismatch);
+    }
+
+    let cooldown_end = requested_at.checked_add(cooldown).ok_or(MathError::Overflow)?;
+    if !self.request_withdraw_approved && current_timestamp < cooldown_end {
+      return err!(WithdrawRequestError::NotReady);
+    }
+
+    Ok(amount)
+  }
+
+  pub fn clear_withdraw_request(&mut self) {
+    self.request_withdraw_amount = None;
+    self.request_withdraw_at = None;
+    self.request_withdraw_approved = false;
+    self.request_withdraw_mint = None;
+  }
+
+  pub fn find_collateral_position(&self, mint: &Pubkey) -> Option<usize> {
+    self.collateral_positions.iter().position(|position| &position.mint == mint)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{test_utils::*, SECONDS_PER_YEAR};
+
+  fn test_loan_offer() -> LoanOfferAccount {
+    loan_offer(Pubkey::new_unique(), "loan", Pubkey::new_unique(), Pubkey::new_unique(), crate::NATIVE_MINT)
+  }
+
+  /// Loan holding a basket position of `amount` for each asset config, with the matching
+  /// asset config and a price update at the given dollar price.
+  fn basket_loan_offer(positions: &[(AssetConfig, u64, i64)]) -> (LoanOfferAccount, &'static [AccountInfo<'static>]) {
+    let mut loan_offer = test_loan_offer();
+    let mut basket_accounts = Vec::new();
+    for (asset_config, amount, price) in positions {
+      loan_offer.collateral_positions.push(CollateralPosition { mint: asset_config.mint, amount: *amount });
+      basket_accounts.push(state(asset_config_key(&asset_config.mint), asset_config));
+      basket_accounts.push(price_feed(Pubkey::new_unique(), asset_config.price_feed_id, usd_price(*price), PRICE_EXPONENT));
+    }
+
+    (loan_offer, Box::leak(basket_accounts.into_boxed_slice()))
+  }
+
+  fn basket_asset_config(collateral_haircut_bps: u64) -> AssetConfig {
+    let mut asset_config = asset_config(Pubkey::new_unique(), COLLATERAL_FEED_ID, TOKEN_DECIMALS);
+    asset_config.collateral_haircut_bps = collateral_haircut_bps;
+    asset_config
+  }
+
+  #[test]
+  fn repay_amounts_share_one_interest_figure() {
+    let loan_offer = test_loan_offer();
+
+    assert_eq!(loan_offer.interest_amount().unwrap(), 10_000_000);
+    assert_eq!(loan_offer.borrower_fee_amount().unwrap(), 500_000);
+    assert_eq!(loan_offer.lender_fee_amount().unwrap(), 500_000);
+    assert_eq!(loan_offer.lender_repay_amount().unwrap(), 109_500_000);
+    assert_eq!(loan_offer.total_repay_amount().unwrap(), 110_500_000);
+  }
+
+  #[test]
+  fn fees_round_up_on_small_interest() {
+    let mut loan_offer = test_loan_offer();
+    loan_offer.borrow_amount = 1_000;
+    loan_offer.duration = 1;
+
+    assert_eq!(loan_offer.interest_amount().unwrap(), 1);
+    assert_eq!(loan_offer.borrower_fee_amount().unwrap(), 1);
+    assert_eq!(loan_offer.lender_repay_amount().unwrap(), 1_000);
+    assert_eq!(loan_offer.total_repay_amount().unwrap(), 1_002);
+  }
+
+  #[test]
+  fn loan_ends_after_its_duration() {
+    let loan_offer = test_loan_offer();
+
+    assert_eq!(loan_offer.end_at(), loan_offer.started_at + SECONDS_PER_YEAR as i64);
+  }
+
+  #[test]
+  fn basket_value_sums_positions_after_haircut() {
+    let (loan_offer, basket_accounts) = basket_loan_offer(&[
+      (basket_asset_config(5_000), 100_000_000, 2),
+      (basket_asset_config(0), 100_000_000, 3),
+    ]);
+
+    assert_eq!(loan_offer.basket_collateral_value(basket_accounts).unwrap(), 400_000_000);
+  }
+
+  #[test]
+  fn ltv_counts_primary_collateral_and_basket() {
+    let (loan_offer, basket_accounts) = basket_loan_offer(&[(basket_asset_config(5_000), 100_000_000, 2)]);
+    let lend_asset_config = asset_config(loan_offer.lend_mint_token, LEND_FEED_ID, TOKEN_DECIMALS);
+    let collateral_asset_config = asset_config(loan_offer.collateral_mint_token, COLLATERAL_FEED_ID, TOKEN_DECIMALS);
+
+    // 100 USD debt against 100 USD of primary collateral and 100 USD of basket value.
+    let ltv_bps = loan_offer.ltv_bps(
+      100_000_000,
+      &collateral_asset_config,
+      &price_update(COLLATERAL_FEED_ID, usd_price(1)),
+      &lend_asset_config,
+      &price_update(LEND_FEED_ID, usd_price(1)),
+      basket_accounts
+    ).unwrap();
+
+    assert_eq!(ltv_bps, 5_000);
+  }
+
+  #[test]
+  fn rejects_basket_accounts_not_matching_positions() {
+    let (loan_offer, basket_accounts) = basket_loan_offer(&[(basket_asset_config(0), 100_000_000, 2)]);
+
+    assert_error(loan_offer.basket_collateral_value(&basket_accounts[..1]), CollateralPositionError::PositionAccountsMismatch);
+
+    let (other_loan_offer, _) = basket_loan_offer(&[(basket_asset_config(0), 100_000_000, 2)]);
+    assert_error(other_loan_offer.basket_collateral_value(basket_accounts), CollateralPositionError::PositionAccountsMismatch);
+  }
+
+  #[test]
+  fn withdraw_request_executes_after_cooldown() {
+    let mut loan_offer = test_loan_offer();
+    assert_error(loan_offer.executable_withdraw_amount(now(), 3_600, None), WithdrawRequestError::NoPendingRequest);
+
+    loan_offer.request_withdraw_amount = Some(1_000);
+    loan_offer.request_withdraw_at = Some(now());
+
+    assert_error(loan_offer.executable_withdraw_amount(now() + 3_599, 3_600, None), WithdrawRequestError::NotReady);
+    assert_eq!(loan_offer.executable_withdraw_amount(now() + 3_600, 3_600, None).unwrap(), 1_000);
+  }
+
+  #[test]
+  fn approved_withdraw_request_skips_cooldown() {
+    let mut loan_offer = test_loan_offer();
+    loan_offer.request_withdraw_amount = Some(1_000);
+    loan_offer.request_withdraw_at = Some(now());
+    loan_offer.request_withdraw_approved = true;
+
+    assert_eq!(loan_offer.executable_withdraw_amount(now(), 3_600, None).unwrap(), 1_000);
+
+    loan_offer.clear_withdraw_request();
+    assert_error(loan_offer.executable_withdraw_amount(now(), 3_600, None), WithdrawRequestError::NoPendingRequest);
+    assert!(!loan_offer.request_withdraw_approved);
+  }
+
+  #[test]
+  fn liquidator_seizes_collateral_in_priority_order() {
+    let mut first = basket_asset_config(0);
+    first.liquidation_priority = 0;
+    let mut last = basket_asset_config(0);
+    last.liquidation_priority = 2;
+    // 100 USD in each basket position, seized before and after the primary collateral.
+    let (loan_offer, basket_accounts) = basket_loan_offer(&[(last, 100_000_000, 1), (first, 100_000_000, 1)]);
+    let lend_asset_config = asset_config(loan_offer.lend_mint_token, LEND_FEED_ID, TOKEN_DECIMALS);
+    let mut collateral_asset_config = asset_config(loan_offer.collateral_mint_token, COLLATERAL_FEED_ID, 9);
+    collateral_asset_config.liquidation_priority = 1;
+
+    // 110.5 USD of debt plus a 5% bonus takes the first position and 16.025 USD of SOL.
+    let (primary_amount, position_amounts) = loan_offer.liquidator_collateral_amounts(
+      &collateral_asset_config,
+      &price_update(COLLATERAL_FEED_ID, usd_price(100)),
+      &lend_asset_config,
+      &price_update(LEND_FEED_ID, usd_price(1)),
+      500,
+      basket_accounts
+    ).unwrap();
+
+    assert_eq!(primary_amount, 160_250_000);
+    assert_eq!(position_amounts, vec![0, 100_000_000]);
+  }
+
+  #[test]
+  fn covered_lender_payout_leaves_no_deficit() {
+    let mut loan_offer = test_loan_offer();
+
+    // Unpaid protocol fees are forgone rather than counted as bad debt.
+    assert_eq!(loan_offer.record_deficit(loan_offer.lender_repay_amount().unwrap()).unwrap(), 0);
+    assert_eq!(loan_offer.deficit_amount, 0);
+    assert_eq!(loan_offer.status, LoanOfferStatus::FundTransferred);
+  }
+
+  #[test]
+  fn lender_shortfall_is_recorded_and_defaults_the_loan() {
+    let mut loan_offer = test_loan_offer();
+
+    assert_eq!(loan_offer.record_deficit(90_000_000).unwrap(), 19_500_000);
+    assert_eq!(loan_offer.deficit_amount, 19_500_000);
+    assert_eq!(loan_offer.status, LoanOfferStatus::Defaulted);
+  }
+
+  #[test]
+  fn defaulted_loan_is_only_auctioned_towards_its_deficit() {
+    let mut loan_offer = test_loan_offer();
+    assert_eq!(loan_offer.auction_dues().unwrap(), (109_500_000, 1_000_000));
+
+    loan_offer.record_deficit(90_000_000).unwrap();
+    assert_eq!(loan_offer.auction_dues().unwrap(), (19_500_000, 0));
+
+    assert_eq!(loan_offer.record_deficit(5_000_000).unwrap(), 14_500_000);
+    assert_eq!(loan_offer.deficit_amount, 14_500_000);
+  }
+
+  #[test]
+  fn loan_is_liquidatable_past_threshold_or_expiry() {
+    let loan_offer = test_loan_offer();
+    let lend_asset_config = asset_config(loan_offer.lend_mint_token, LEND_FEED_ID, TOKEN_DECIMALS);
+    let collateral_asset_config = asset_config(loan_offer.collateral_mint_token, COLLATERAL_FEED_ID, 9);
+    let risk_parameters = setting_account(Pubkey::new_unique(), loan_offer.lend_mint_token, loan_offer.collateral_mint_token).risk_parameters;
+    let validate = |current_timestamp, collateral_usd_price| loan_offer.validate_liquidatable(
+      current_timestamp,
+      &collateral_asset_config,
+      &price_update(COLLATERAL_FEED_ID, usd_price(collateral_usd_price)),
+      &lend_asset_config,
+      &price_update(LEND_FEED_ID, usd_price(1)),
+      &risk_parameters,
+      &[]
+    );
+
+    // 100 USD of debt: liquidatable from 125 USD of collateral down, at the 80% threshold.
+    assert!(validate(now(), 125).is_ok());
+    assert_error(validate(now(), 126), LiquidateOfferError::LoanNotLiquidatable);
+    assert!(validate(loan_offer.end_at() + 1, 200).is_ok());
+  }
+
+  #[test]
+  fn debt_write_off_settles_principal_and_interest_for_the_lender() {
+    let mut loan_offer = test_loan_offer();
+
+    // 40 USD of principal carried 4 USD of interest, less the 5% lender fee.
+    assert_eq!(loan_offer.write_off_debt(40_000_000).unwrap(), 43_800_000);
+    assert_eq!(loan_offer.borrow_amount, 60_000_000);
+    assert_eq!(loan_offer.lender_repay_amount().unwrap(), 109_500_000 - 43_800_000);
+  }
+}
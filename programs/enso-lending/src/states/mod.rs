@@ -4,5 +4,37 @@ pub use setting_account::*;
 pub mod lend_offer;
 pub use lend_offer::*;
 
+pub mod order_book;
+pub use order_book::*;
+
 pub mod loan_offer;
-pub use loan_offer::*;
\ No newline at end of file
+pub use loan_offer::*;
+
+pub mod borrow_request;
+pub use borrow_request::*;
+pub mod global_config;
+pub use global_config::*;
+
+pub mod role_account;
+pub use role_account::*;
+
+pub mod pending_setting_change;
+pub use pending_setting_change::*;
+
+pub mod approval_config;
+pub use approval_config::*;
+
+pub mod proposal;
+pub use proposal::*;
+
+pub mod asset_config;
+pub use asset_config::*;
+
+pub mod liquidation_auction;
+pub use liquidation_auction::*;
+
+pub mod reserve_fund;
+pub use reserve_fund::*;
+
+pub mod swap_adapter;
+pub use swap_adapter::*;
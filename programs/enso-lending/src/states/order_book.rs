@@ -0,0 +1,306 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{OrderBookError, MAX_ORDER_BOOK_ENTRIES};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct OrderBookEntry {
+  pub lend_offer: Pubkey,
+  pub interest_bps: u64,
+  pub remaining_amount: u64,
+}
+
+/// Active lend offers of a tier kept as a slab sorted by interest rate. Offers with the same
+/// rate stay in the order they were listed, so earlier offers are matched first.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct OrderBook {
+  #[max_len(50)]
+  pub tier_id: String,
+  /// Offers with less than this amount left are not listed, so dust can not fill the book.
+  pub min_amount: u64,
+  #[max_len(MAX_ORDER_BOOK_ENTRIES)]
+  pub entries: Vec<OrderBookEntry>,
+  pub bump: u8
+}
+
+impl OrderBook {
+  /// Applies `update` to the order book at `order_book` once its tier has one set up. The
+  /// address is pinned by seeds, so an existing book can not be skipped, while tiers without
+  /// a book and offers that were never listed keep working.
+  pub fn update_if_initialized<'info, T>(
+    order_book: &AccountInfo<'info>,
+    update: impl FnOnce(&mut OrderBook) -> Result<T>
+  ) -> Result<Option<T>> {
+    if order_book.owner != &crate::ID || order_book.data_is_empty() {
+      return Ok(None);
+    }
+
+    let mut data = order_book.try_borrow_mut_data()?;
+    let mut order_book_account = OrderBook::try_deserialize(&mut &data[..])?;
+    let result = update(&mut order_book_account)?;
+    let mut writer: &mut [u8] = &mut data;
+    order_book_account.try_serialize(&mut writer)?;
+
+    Ok(Some(result))
+  }
+
+  /// Lists an offer behind the offers already listed at its rate. A full book makes room by
+  /// evicting its worst offer when the new one has a lower rate, and returns the evicted entry.
+  pub fn insert(&mut self, lend_offer: Pubkey, interest_bps: u64, remaining_amount: u64) -> Result<Option<OrderBookEntry>> {
+    if remaining_amount < self.min_amount {
+      return err!(OrderBookError::OfferTooSmall);
+    }
+
+    let mut evicted_entry = None;
+    if self.entries.len() >= MAX_ORDER_BOOK_ENTRIES {
+      match self.entries.last() {
+        Some(worst_entry) if interest_bps < worst_entry.interest_bps => evicted_entry = self.entries.pop(),
+        _ => return err!(OrderBookError::OrderBookFull),
+      }
+    }
+
+    let index = self.entries.partition_point(|entry| entry.interest_bps <= interest_bps);
+    self.entries.insert(index, OrderBookEntry {
+      lend_offer,
+      interest_bps,
+      remaining_amount,
+    });
+
+    Ok(evicted_entry)
+  }
+
+  /// Whether `insert` would list the offer rather than reject it as too small or outbid.
+  pub fn accepts(&self, interest_bps: u64, remaining_amount: u64) -> bool {
+    if remaining_amount < self.min_amount {
+      return false;
+    }
+
+    match self.entries.last() {
+      Some(worst_entry) if self.entries.len() >= MAX_ORDER_BOOK_ENTRIES => interest_bps < worst_entry.interest_bps,
+      _ => true,
+    }
+  }
+
+  pub fn remove(&mut self, lend_offer: &Pubkey) -> Option<OrderBookEntry> {
+    let index = self.entries.iter().position(|entry| entry.lend_offer == *lend_offer)?;
+
+    Some(self.entries.remove(index))
+  }
+
+  /// Moves an offer to the slot of its new rate, behind the offers already listed at that rate.
+  /// Offers that are not listed are left out.
+  pub fn reprice(&mut self, lend_offer: &Pubkey, interest_bps: u64) -> Result<()> {
+    if let Some(entry) = self.remove(lend_offer) {
+      self.insert(entry.lend_offer, interest_bps, entry.remaining_amount)?;
+    }
+
+    Ok(())
+  }
+
+  /// Tracks what is left of an offer after a draw and delists it once less than the minimum
+  /// remains. Offers that are not listed are left out.
+  pub fn update_remaining(&mut self, lend_offer: &Pubkey, remaining_amount: u64) -> Result<()> {
+    if remaining_amount < self.min_amount {
+      self.remove(lend_offer);
+      return Ok(());
+    }
+
+    if let Some(entry) = self.entries.iter_mut().find(|entry| entry.lend_offer == *lend_offer) {
+      entry.remaining_amount = remaining_amount;
+    }
+
+    Ok(())
+  }
+
+  /// Lists an offer again with what is left of it, or updates its entry if it is still listed.
+  /// Returns the entry evicted to make room, if any.
+  pub fn relist(&mut self, lend_offer: Pubkey, interest_bps: u64, remaining_amount: u64) -> Result<Option<OrderBookEntry>> {
+    if let Some(entry) = self.entries.iter_mut().find(|entry| entry.lend_offer == lend_offer) {
+      entry.remaining_amount = remaining_amount;
+      return Ok(None);
+    }
+
+    if remaining_amount < self.min_amount {
+      return Ok(None);
+    }
+
+    self.insert(lend_offer, interest_bps, remaining_amount)
+  }
+
+  /// Offers that fill `borrow_amount` in rate order without going above `max_interest_bps`, with
+  /// the amount taken from each. Fails unless the book can cover the whole amount.
+  pub fn fill_plan(&self, borrow_amount: u64, max_interest_bps: u64) -> Result<Vec<(Pubkey, u64)>> {
+    let mut plan = Vec::new();
+    let mut unfilled_amount = borrow_amount;
+
+    for entry in self.entries.iter().take_while(|entry| entry.interest_bps <= max_interest_bps) {
+      if unfilled_amount == 0 {
+        break;
+      }
+
+      let fill_amount = entry.remaining_amount.min(unfilled_amount);
+      plan.push((entry.lend_offer, fill_amount));
+      unfilled_amount -= fill_amount;
+    }
+
+    if borrow_amount == 0 || unfilled_amount > 0 {
+      return err!(OrderBookError::NoMatchingOffer);
+    }
+
+    Ok(plan)
+  }
+
+  /// Amount `lend_offer` fills of `borrow_amount`, provided it is the first offer of the fill plan.
+  pub fn next_fill(&self, lend_offer: &Pubkey, borrow_amount: u64, max_interest_bps: u64) -> Result<u64> {
+    let plan = self.fill_plan(borrow_amount, max_interest_bps)?;
+    let (best_offer, fill_amount) = plan[0];
+
+    if best_offer != *lend_offer {
+      return err!(OrderBookError::NotBestOffer);
+    }
+
+    Ok(fill_amount)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  const AMOUNT: u64 = 100_000_000;
+
+  fn listed(order_book: &OrderBook) -> Vec<(Pubkey, u64)> {
+    order_book.entries.iter().map(|entry| (entry.lend_offer, entry.interest_bps)).collect()
+  }
+
+  #[test]
+  fn keeps_offers_sorted_by_rate_then_listing_order() {
+    let (first, second, cheapest) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut order_book = order_book();
+
+    order_book.insert(first, 1_000, AMOUNT).unwrap();
+    order_book.insert(second, 1_000, AMOUNT).unwrap();
+    order_book.insert(cheapest, 800, AMOUNT).unwrap();
+
+    assert_eq!(listed(&order_book), vec![(cheapest, 800), (first, 1_000), (second, 1_000)]);
+  }
+
+  #[test]
+  fn rejects_dust_offer() {
+    let mut order_book = order_book();
+
+    assert_error(order_book.insert(Pubkey::new_unique(), 1_000, 999_999), OrderBookError::OfferTooSmall);
+  }
+
+  #[test]
+  fn full_book_evicts_worst_offer_for_better_rate() {
+    let mut order_book = full_order_book();
+    let worst = order_book.entries.last().unwrap().lend_offer;
+    let better = Pubkey::new_unique();
+
+    let evicted = order_book.insert(better, 900, AMOUNT).unwrap();
+
+    assert_eq!(evicted.unwrap().lend_offer, worst);
+    assert_eq!(order_book.entries.len(), MAX_ORDER_BOOK_ENTRIES);
+    assert_eq!(order_book.entries[0].lend_offer, better);
+  }
+
+  #[test]
+  fn full_book_rejects_offer_without_better_rate() {
+    let mut order_book = full_order_book();
+
+    assert_error(order_book.insert(Pubkey::new_unique(), 1_000, AMOUNT), OrderBookError::OrderBookFull);
+  }
+
+  #[test]
+  fn accepts_only_offers_insert_would_list() {
+    let full_order_book = full_order_book();
+
+    assert!(order_book().accepts(1_000, AMOUNT));
+    assert!(!order_book().accepts(1_000, 999_999));
+    assert!(full_order_book.accepts(900, AMOUNT));
+    assert!(!full_order_book.accepts(1_000, AMOUNT));
+  }
+
+  #[test]
+  fn reprice_moves_offer_behind_its_new_rate() {
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut order_book = order_book();
+    order_book.insert(first, 800, AMOUNT).unwrap();
+    order_book.insert(second, 1_000, AMOUNT).unwrap();
+
+    order_book.reprice(&first, 1_000).unwrap();
+
+    assert_eq!(listed(&order_book), vec![(second, 1_000), (first, 1_000)]);
+  }
+
+  #[test]
+  fn update_remaining_delists_offer_below_minimum() {
+    let lend_offer = Pubkey::new_unique();
+    let mut order_book = order_book();
+    order_book.insert(lend_offer, 1_000, AMOUNT).unwrap();
+
+    order_book.update_remaining(&lend_offer, 40_000_000).unwrap();
+    assert_eq!(order_book.entries[0].remaining_amount, 40_000_000);
+
+    order_book.update_remaining(&lend_offer, 999_999).unwrap();
+    assert!(order_book.entries.is_empty());
+  }
+
+  #[test]
+  fn relist_returns_offer_with_what_is_left() {
+    let lend_offer = Pubkey::new_unique();
+    let mut order_book = order_book();
+
+    order_book.relist(lend_offer, 1_000, 999_999).unwrap();
+    assert!(order_book.entries.is_empty());
+
+    order_book.relist(lend_offer, 1_000, 30_000_000).unwrap();
+    order_book.relist(lend_offer, 1_000, 60_000_000).unwrap();
+    assert_eq!(order_book.entries.len(), 1);
+    assert_eq!(order_book.entries[0].remaining_amount, 60_000_000);
+  }
+
+  #[test]
+  fn fill_plan_takes_offers_in_rate_order() {
+    let (cheap, expensive) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut order_book = order_book();
+    order_book.insert(expensive, 1_200, AMOUNT).unwrap();
+    order_book.insert(cheap, 800, 60_000_000).unwrap();
+
+    assert_eq!(order_book.fill_plan(AMOUNT, 1_200).unwrap(), vec![(cheap, 60_000_000), (expensive, 40_000_000)]);
+    assert_eq!(order_book.next_fill(&cheap, AMOUNT, 1_200).unwrap(), 60_000_000);
+    assert_error(order_book.next_fill(&expensive, AMOUNT, 1_200), OrderBookError::NotBestOffer);
+  }
+
+  #[test]
+  fn fill_plan_needs_enough_offers_under_max_rate() {
+    let mut order_book = order_book();
+    order_book.insert(Pubkey::new_unique(), 800, 60_000_000).unwrap();
+    order_book.insert(Pubkey::new_unique(), 1_200, AMOUNT).unwrap();
+
+    assert_error(order_book.fill_plan(AMOUNT, 1_000), OrderBookError::NoMatchingOffer);
+    assert_error(order_book.fill_plan(0, 1_000), OrderBookError::NoMatchingOffer);
+  }
+
+  #[test]
+  fn update_if_initialized_skips_missing_book() {
+    let order_book = empty(order_book_key());
+
+    let result = OrderBook::update_if_initialized(&order_book, |_| Ok(())).unwrap();
+
+    assert!(result.is_none());
+  }
+
+  #[test]
+  fn update_if_initialized_writes_back_existing_book() {
+    let lend_offer = Pubkey::new_unique();
+    let account = state(order_book_key(), &order_book());
+
+    OrderBook::update_if_initialized(&account, |order_book| order_book.insert(lend_offer, 1_000, AMOUNT)).unwrap().unwrap();
+
+    let order_book: OrderBook = read_state(&account);
+    assert_eq!(order_book.entries[0].lend_offer, lend_offer);
+  }
+}
@@ -0,0 +1,22 @@
+pub use anchor_lang::prelude::*;
+
+use crate::RiskParameters;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PendingSettingChange {
+  #[max_len(50)]
+  pub tier_id: String,
+  pub amount: Option<u64>,
+  pub min_amount: Option<u64>,
+  pub duration: Option<u64>,
+  pub lender_fee_bps: Option<u64>,
+  pub borrower_fee_bps: Option<u64>,
+  pub risk_parameters: Option<RiskParameters>,
+  pub receiver: Pubkey,
+  pub lend_mint_asset: Pubkey,
+  pub collateral_mint_asset: Pubkey,
+  pub queued_at: i64,
+  pub effective_at: i64,
+  pub bump: u8
+}
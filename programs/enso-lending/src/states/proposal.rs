@@ -0,0 +1,142 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{ApprovalConfig, ApprovalError, ProposalAction, ProposalExecutedEvent, MAX_APPROVERS};
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Proposal {
+  #[max_len(50)]
+  pub proposal_id: String,
+  pub proposer: Pubkey,
+  pub action: ProposalAction,
+  #[max_len(MAX_APPROVERS)]
+  pub approvals: Vec<Pubkey>,
+  pub executed: bool,
+  pub created_at: i64,
+  pub bump: u8
+}
+
+impl Proposal {
+  /// Only approvals from keys that are still configured approvers count toward the threshold.
+  pub fn approval_count(&self, approval_config: &ApprovalConfig) -> usize {
+    self.approvals
+      .iter()
+      .filter(|approver| approval_config.is_approver(approver))
+      .count()
+  }
+
+  pub fn execute(
+    &mut self,
+    approval_config: &ApprovalConfig,
+    action: &ProposalAction,
+    executor: Pubkey
+  ) -> Result<()> {
+    if self.executed {
+      return err!(ApprovalError::ProposalAlreadyExecuted);
+    }
+
+    if &self.action != action {
+      return err!(ApprovalError::ProposalActionMismatch);
+    }
+
+    if self.approval_count(approval_config) < approval_config.threshold as usize {
+      return err!(ApprovalError::ThresholdNotMet);
+    }
+
+    self.executed = true;
+
+    emit!(ProposalExecutedEvent {
+      proposal_id: self.proposal_id.clone(),
+      executor,
+      action: action.clone(),
+    });
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn approval_config(approvers: Vec<Pubkey>, threshold: u8) -> ApprovalConfig {
+    ApprovalConfig {
+      approvers,
+      threshold,
+      bump: 0,
+    }
+  }
+
+  fn proposal(action: ProposalAction, approvals: Vec<Pubkey>) -> Proposal {
+    Proposal {
+      proposal_id: "proposal".to_string(),
+      proposer: Pubkey::new_unique(),
+      action,
+      approvals,
+      executed: false,
+      created_at: 0,
+      bump: 0,
+    }
+  }
+
+  fn close_setting_account(setting_account: Pubkey) -> ProposalAction {
+    ProposalAction::CloseSettingAccount { setting_account }
+  }
+
+  #[test]
+  fn executes_once_threshold_is_met() {
+    let approvers = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let action = close_setting_account(Pubkey::new_unique());
+    let mut proposal = proposal(action.clone(), approvers[..2].to_vec());
+
+    proposal.execute(&approval_config(approvers, 2), &action, Pubkey::new_unique()).unwrap();
+
+    assert!(proposal.executed);
+  }
+
+  #[test]
+  fn rejects_execution_below_threshold() {
+    let approvers = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let action = close_setting_account(Pubkey::new_unique());
+    let mut proposal = proposal(action.clone(), approvers[..1].to_vec());
+
+    let result = proposal.execute(&approval_config(approvers, 2), &action, Pubkey::new_unique());
+
+    assert_eq!(result.unwrap_err(), ApprovalError::ThresholdNotMet.into());
+    assert!(!proposal.executed);
+  }
+
+  #[test]
+  fn ignores_approvals_of_removed_approvers() {
+    let removed_approver = Pubkey::new_unique();
+    let approver = Pubkey::new_unique();
+    let action = close_setting_account(Pubkey::new_unique());
+    let mut proposal = proposal(action.clone(), vec![removed_approver, approver]);
+    let approval_config = approval_config(vec![approver, Pubkey::new_unique()], 2);
+
+    assert_eq!(proposal.approval_count(&approval_config), 1);
+    assert_eq!(
+      proposal.execute(&approval_config, &action, Pubkey::new_unique()).unwrap_err(),
+      ApprovalError::ThresholdNotMet.into()
+    );
+  }
+
+  #[test]
+  fn rejects_different_action_and_second_execution() {
+    let approver = Pubkey::new_unique();
+    let action = close_setting_account(Pubkey::new_unique());
+    let mut proposal = proposal(action.clone(), vec![approver]);
+    let approval_config = approval_config(vec![approver], 1);
+
+    assert_eq!(
+      proposal.execute(&approval_config, &close_setting_account(Pubkey::new_unique()), approver).unwrap_err(),
+      ApprovalError::ProposalActionMismatch.into()
+    );
+
+    proposal.execute(&approval_config, &action, approver).unwrap();
+    assert_eq!(
+      proposal.execute(&approval_config, &action, approver).unwrap_err(),
+      ApprovalError::ProposalAlreadyExecuted.into()
+    );
+  }
+}
@@ -0,0 +1,80 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{calculate_fee, MathError, ReserveFundError, Rounding, BPS_DENOMINATOR};
+
+/// Insurance reserve for a single lend mint. The tokens sit in the reserve vault, owned by
+/// this account, while `balance` tracks what has been accounted for on-chain.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ReserveFund {
+  pub lend_mint: Pubkey,
+  pub fee_share_bps: u64,
+  pub balance: u64,
+  pub total_inflow: u64,
+  pub total_payout: u64,
+  pub bump: u8
+}
+
+impl ReserveFund {
+  pub fn validate_fee_share(fee_share_bps: u64) -> Result<()> {
+    if fee_share_bps > BPS_DENOMINATOR {
+      return err!(ReserveFundError::FeeShareTooHigh);
+    }
+
+    Ok(())
+  }
+
+  /// Portion of a collected fee that is routed into the reserve.
+  pub fn fee_share(&self, fee_amount: u64) -> Result<u64> {
+    calculate_fee(fee_amount, self.fee_share_bps, Rounding::Down)
+  }
+
+  pub fn record_inflow(&mut self, amount: u64) -> Result<()> {
+    self.balance = self.balance.checked_add(amount).ok_or(MathError::Overflow)?;
+    self.total_inflow = self.total_inflow.checked_add(amount).ok_or(MathError::Overflow)?;
+
+    Ok(())
+  }
+
+  pub fn record_payout(&mut self, amount: u64) -> Result<()> {
+    self.balance = self.balance
+      .checked_sub(amount)
+      .ok_or(ReserveFundError::InsufficientBalance)?;
+    self.total_payout = self.total_payout.checked_add(amount).ok_or(MathError::Overflow)?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  #[test]
+  fn fee_share_rounds_down() {
+    let reserve_fund = reserve_fund(Pubkey::new_unique(), 2_000, 0);
+
+    assert_eq!(reserve_fund.fee_share(500_000).unwrap(), 100_000);
+    assert_eq!(reserve_fund.fee_share(4).unwrap(), 0);
+  }
+
+  #[test]
+  fn fee_share_cannot_exceed_whole_fee() {
+    assert!(ReserveFund::validate_fee_share(BPS_DENOMINATOR).is_ok());
+    assert_error(ReserveFund::validate_fee_share(BPS_DENOMINATOR + 1), ReserveFundError::FeeShareTooHigh);
+  }
+
+  #[test]
+  fn tracks_balance_inflows_and_payouts() {
+    let mut reserve_fund = reserve_fund(Pubkey::new_unique(), 2_000, 0);
+
+    reserve_fund.record_inflow(1_000).unwrap();
+    reserve_fund.record_payout(400).unwrap();
+
+    assert_eq!(reserve_fund.balance, 600);
+    assert_eq!(reserve_fund.total_inflow, 1_000);
+    assert_eq!(reserve_fund.total_payout, 400);
+    assert_error(reserve_fund.record_payout(601), ReserveFundError::InsufficientBalance);
+  }
+}
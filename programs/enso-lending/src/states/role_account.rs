@@ -0,0 +1,18 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{Role, MAX_ROLE_MEMBERS};
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct RoleAccount {
+  pub role: Role,
+  #[max_len(MAX_ROLE_MEMBERS)]
+  pub members: Vec<Pubkey>,
+  pub bump: u8
+}
+
+impl RoleAccount {
+  pub fn is_member(&self, key: &Pubkey) -> bool {
+    self.members.contains(key)
+  }
+}
@@ -1,17 +1,90 @@
 pub use anchor_lang::prelude::*;
 
+use crate::{LendOfferError, MathError, PauseFlags, RiskParameters, SettingAccountError};
+
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct SettingAccount {
+  /// Largest amount a single lend offer in this tier may post.
   pub amount: u64,
+  /// Smallest amount a single lend offer in this tier may post.
+  pub min_amount: u64,
   pub duration: u64,
   pub owner: Pubkey,
+  pub pending_owner: Option<Pubkey>,
   pub receiver: Pubkey,
   pub lend_mint_asset: Pubkey,
   pub collateral_mint_asset: Pubkey,
   #[max_len(50)]
   pub tier_id: String,
-  pub lender_fee_percent: f64,
-  pub borrower_fee_percent: f64,
+  pub lender_fee_bps: u64,
+  pub borrower_fee_bps: u64,
+  pub risk_parameters: RiskParameters,
+  pub pause_flags: PauseFlags,
+  pub bad_debt_amount: u64,
+  pub defaulted_loan_count: u64,
   pub bump: u8
-}
\ No newline at end of file
+}
+
+impl SettingAccount {
+  pub fn validate_amount_bounds(min_amount: u64, amount: u64) -> Result<()> {
+    if min_amount == 0 || min_amount > amount {
+      return err!(SettingAccountError::InvalidAmountBounds);
+    }
+
+    Ok(())
+  }
+
+  pub fn validate_lend_amount(&self, lend_amount: u64) -> Result<()> {
+    if lend_amount < self.min_amount || lend_amount > self.amount {
+      return err!(LendOfferError::InvalidLendAmount);
+    }
+
+    Ok(())
+  }
+
+  pub fn record_bad_debt(&mut self, deficit_amount: u64) -> Result<()> {
+    self.bad_debt_amount = self.bad_debt_amount
+      .checked_add(deficit_amount)
+      .ok_or(MathError::Overflow)?;
+    self.defaulted_loan_count = self.defaulted_loan_count
+      .checked_add(1)
+      .ok_or(MathError::Overflow)?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  #[test]
+  fn lend_amount_must_be_within_tier_bounds() {
+    let setting_account = setting_account(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+    setting_account.validate_lend_amount(1_000_000).unwrap();
+    setting_account.validate_lend_amount(1_000_000_000).unwrap();
+    assert_error(setting_account.validate_lend_amount(999_999), LendOfferError::InvalidLendAmount);
+    assert_error(setting_account.validate_lend_amount(1_000_000_001), LendOfferError::InvalidLendAmount);
+  }
+
+  #[test]
+  fn rejects_zero_or_inverted_amount_bounds() {
+    SettingAccount::validate_amount_bounds(1, 1).unwrap();
+    assert_error(SettingAccount::validate_amount_bounds(0, 1), SettingAccountError::InvalidAmountBounds);
+    assert_error(SettingAccount::validate_amount_bounds(2, 1), SettingAccountError::InvalidAmountBounds);
+  }
+
+  #[test]
+  fn bad_debt_accumulates_per_tier() {
+    let mut setting_account = setting_account(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+    setting_account.record_bad_debt(19_500_000).unwrap();
+    setting_account.record_bad_debt(500_000).unwrap();
+
+    assert_eq!(setting_account.bad_debt_amount, 20_000_000);
+    assert_eq!(setting_account.defaulted_loan_count, 2);
+  }
+}
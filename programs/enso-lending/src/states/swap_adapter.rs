@@ -0,0 +1,33 @@
+pub use anchor_lang::prelude::*;
+
+use crate::{SwapAccountKind, SwapAccountSlot, SwapAdapterError, MAX_SWAP_ACCOUNTS};
+
+/// Program and account layout that swap liquidations CPI into. The instruction data is the
+/// discriminator followed by the input amount and the minimum output, both as little-endian u64.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct SwapAdapter {
+  pub program_id: Pubkey,
+  pub instruction_discriminator: [u8; 8],
+  #[max_len(MAX_SWAP_ACCOUNTS)]
+  pub account_layout: Vec<SwapAccountSlot>,
+  pub bump: u8
+}
+
+impl SwapAdapter {
+  pub fn validate_account_layout(account_layout: &[SwapAccountSlot]) -> Result<()> {
+    if account_layout.len() > MAX_SWAP_ACCOUNTS {
+      return err!(SwapAdapterError::TooManyAccounts);
+    }
+
+    Ok(())
+  }
+
+  /// Number of accounts the caller has to append for the `Extra` slots.
+  pub fn extra_account_count(&self) -> usize {
+    self.account_layout
+      .iter()
+      .filter(|slot| slot.kind == SwapAccountKind::Extra)
+      .count()
+  }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TransferChecked};
+
+/// Refunds `amount` from an escrow owned by the PDA `authority` and closes the escrow into
+/// `rent_destination` once nothing is left in it.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_escrow<'info>(
+    escrow: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    rent_destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let transfer_accounts = TransferChecked {
+        from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to,
+        authority: authority.clone(),
+    };
+    transfer_checked(
+        CpiContext::new_with_signer(token_program.clone(), transfer_accounts, signer),
+        amount,
+        mint.decimals,
+    )?;
+
+    escrow.reload()?;
+    if escrow.amount > 0 {
+        return Ok(());
+    }
+
+    let close_accounts = CloseAccount {
+        account: escrow.to_account_info(),
+        destination: rent_destination,
+        authority,
+    };
+    close_account(CpiContext::new_with_signer(token_program, close_accounts, signer))
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TransferChecked};
+
+use crate::{BadDebtEvent, LoanOfferAccount, SettingAccount};
+
+/// Pays each `(recipient, amount)` share of the liquidation proceeds held by the PDA
+/// `authority`, skipping empty shares, then closes the proceeds account into `rent_destination`.
+pub fn distribute_proceeds<'info>(
+    proceeds: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    shares: [(AccountInfo<'info>, u64); 3],
+    authority: AccountInfo<'info>,
+    rent_destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    for (to, amount) in shares {
+        if amount == 0 {
+            continue;
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: proceeds.clone(),
+            mint: mint.to_account_info(),
+            to,
+            authority: authority.clone(),
+        };
+        transfer_checked(
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer),
+            amount,
+            mint.decimals,
+        )?;
+    }
+
+    let close_accounts = CloseAccount {
+        account: proceeds,
+        destination: rent_destination,
+        authority,
+    };
+    close_account(CpiContext::new_with_signer(token_program, close_accounts, signer))
+}
+
+/// Reports the deficit a loan left behind, with the tier totals once it has been recorded.
+pub fn emit_event_bad_debt(
+    label: String,
+    loan_offer: &LoanOfferAccount,
+    setting_account: &SettingAccount,
+    deficit_amount: u64,
+) -> Result<()> {
+    emit!(BadDebtEvent {
+        loan_offer_id: loan_offer.offer_id.clone(),
+        tier_id: setting_account.tier_id.clone(),
+        lender: loan_offer.lender,
+        deficit_amount,
+        tier_bad_debt_amount: setting_account.bad_debt_amount,
+        tier_defaulted_loan_count: setting_account.defaulted_loan_count,
+    });
+
+    msg!(&label.clone());
+    Ok(())
+}
@@ -2,4 +2,25 @@ pub mod pyth;
 pub use pyth::*;
 
 pub mod tools;
-pub use tools::*;
\ No newline at end of file
+pub use tools::*;
+
+pub mod token;
+pub use token::*;
+
+pub mod swap;
+pub use swap::*;
+
+pub mod vault;
+pub use vault::*;
+
+pub mod escrow;
+pub use escrow::*;
+
+pub mod liquidation;
+pub use liquidation::*;
+
+pub mod reserve;
+pub use reserve::*;
+
+#[cfg(test)]
+pub mod test_utils;
@@ -1,19 +1,32 @@
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::MAXIMUM_AGE_PRICE_UPDATE;
+use crate::{mul_div, MathError, OracleError, Rounding, USD_DECIMALS};
 
-pub fn convert_to_usd_price(
+/// Converts a raw token `amount` into a USD value with `USD_DECIMALS` decimals.
+pub fn convert_to_usd_value(
     price_feed_account: &PriceUpdateV2,
-    price_fee_id: &str,
-    amount: f64,
-) -> Result<f64> {
-    let feed_id: [u8; 32] =
-        get_feed_id_from_hex(price_fee_id)?;
+    feed_id: &[u8; 32],
+    max_price_age: u64,
+    amount: u64,
+    decimals: u8,
+    rounding: Rounding,
+) -> Result<u128> {
     let current_price =
-        price_feed_account.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE_PRICE_UPDATE, &feed_id)?;
+        price_feed_account.get_price_no_older_than(&Clock::get()?, max_price_age, feed_id)?;
 
-    let display_price = current_price.price as f64 / 10f64.powf(-current_price.exponent as f64);
+    if current_price.price <= 0 {
+        return err!(OracleError::InvalidPrice);
+    }
 
-    Ok(display_price * amount)
+    let scale = USD_DECIMALS as i32 + current_price.exponent - decimals as i32;
+    let power = 10u128
+        .checked_pow(scale.unsigned_abs())
+        .ok_or(MathError::Overflow)?;
+
+    if scale >= 0 {
+        mul_div(amount as u128, current_price.price as u128 * power, 1, rounding)
+    } else {
+        mul_div(amount as u128, current_price.price as u128, power, rounding)
+    }
 }
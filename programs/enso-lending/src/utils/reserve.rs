@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+use crate::{calculate_amount_with_transfer_fee, ReserveFund, ReserveFundError, ReserveFundInflowEvent};
+
+/// Share of `fee_amount` owed to the reserve fund. Lend mints without a reserve fund skip
+/// the inflow, so repayment never depends on one being set up.
+pub fn reserve_amount(
+    reserve_fund: &Option<Account<ReserveFund>>,
+    reserve_vault: &Option<InterfaceAccount<TokenAccount>>,
+    fee_amount: u64,
+) -> Result<u64> {
+    match (reserve_fund, reserve_vault) {
+        (Some(reserve_fund), Some(_)) => reserve_fund.fee_share(fee_amount),
+        (None, None) => Ok(0),
+        _ => err!(ReserveFundError::IncompleteReserveAccounts),
+    }
+}
+
+/// Moves `reserve_amount` from `from` into the reserve vault, grossed up for any transfer fee,
+/// and credits it to the reserve fund.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_reserve<'info>(
+    reserve_fund: &mut Option<Account<'info, ReserveFund>>,
+    reserve_vault: &Option<InterfaceAccount<'info, TokenAccount>>,
+    from: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: AccountInfo<'info>,
+    loan_offer_id: &str,
+    reserve_amount: u64,
+) -> Result<()> {
+    if reserve_amount == 0 {
+        return Ok(());
+    }
+
+    let (Some(reserve_fund), Some(reserve_vault)) = (reserve_fund.as_mut(), reserve_vault.as_ref()) else {
+        return Ok(());
+    };
+
+    let transfer_amount = calculate_amount_with_transfer_fee(&mint.to_account_info(), reserve_amount)?;
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to: reserve_vault.to_account_info(),
+        authority,
+    };
+    transfer_checked(CpiContext::new(token_program, cpi_accounts), transfer_amount, mint.decimals)?;
+
+    reserve_fund.record_inflow(reserve_amount)?;
+
+    emit!(ReserveFundInflowEvent {
+        lend_mint: reserve_fund.lend_mint,
+        loan_offer_id: loan_offer_id.to_string(),
+        amount: reserve_amount,
+        balance: reserve_fund.balance,
+    });
+
+    Ok(())
+}
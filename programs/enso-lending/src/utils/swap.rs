@@ -0,0 +1,86 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::{SwapAccountKind, SwapAdapter, SwapAdapterError};
+
+/// Accounts the liquidation provides to the swap adapter itself.
+pub struct SwapAccounts<'info> {
+    pub authority: AccountInfo<'info>,
+    pub source: AccountInfo<'info>,
+    pub destination: AccountInfo<'info>,
+    pub source_mint: AccountInfo<'info>,
+    pub destination_mint: AccountInfo<'info>,
+    pub source_token_program: AccountInfo<'info>,
+    pub destination_token_program: AccountInfo<'info>,
+}
+
+/// Swaps `amount_in` of the source into the destination through the configured adapter,
+/// signing for `authority` with `signer_seeds`. The adapter is trusted to revert below
+/// `minimum_out`; callers still check the destination balance afterwards.
+pub fn execute_swap<'info>(
+    adapter: &SwapAdapter,
+    swap_program: &AccountInfo<'info>,
+    accounts: &SwapAccounts<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    minimum_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if swap_program.key() != adapter.program_id {
+        return err!(SwapAdapterError::InvalidSwapProgram);
+    }
+
+    if extra_accounts.len() != adapter.extra_account_count() {
+        return err!(SwapAdapterError::AccountLayoutMismatch);
+    }
+
+    let mut extra_accounts = extra_accounts.iter();
+    let mut metas = Vec::with_capacity(adapter.account_layout.len());
+    let mut account_infos = Vec::with_capacity(adapter.account_layout.len() + 1);
+
+    for slot in adapter.account_layout.iter() {
+        let account = match slot.kind {
+            SwapAccountKind::Authority => &accounts.authority,
+            SwapAccountKind::Source => &accounts.source,
+            SwapAccountKind::Destination => &accounts.destination,
+            SwapAccountKind::SourceMint => &accounts.source_mint,
+            SwapAccountKind::DestinationMint => &accounts.destination_mint,
+            SwapAccountKind::SourceTokenProgram => &accounts.source_token_program,
+            SwapAccountKind::DestinationTokenProgram => &accounts.destination_token_program,
+            SwapAccountKind::Extra => extra_accounts.next().ok_or(SwapAdapterError::AccountLayoutMismatch)?,
+        };
+
+        if let Some(address) = slot.address {
+            if account.key() != address {
+                return err!(SwapAdapterError::AccountLayoutMismatch);
+            }
+        }
+
+        let is_signer = slot.kind == SwapAccountKind::Authority;
+        metas.push(if slot.is_writable {
+            AccountMeta::new(account.key(), is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), is_signer)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(swap_program.clone());
+
+    let mut data = adapter.instruction_discriminator.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_out.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: adapter.program_id,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
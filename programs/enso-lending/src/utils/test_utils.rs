@@ -0,0 +1,734 @@
+//! Off-chain harness to run instruction contexts in unit tests. Accounts are laid out the way the
+//! runtime serializes them, the clock is set per test thread and calls into the system and token
+//! programs are processed in place.
+use std::{cell::Cell, collections::BTreeSet, sync::Once};
+
+use anchor_lang::{
+  solana_program::{
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    program_utils::limited_deserialize,
+    rent::Rent,
+    system_instruction::SystemInstruction,
+    system_program,
+  },
+  prelude::*,
+  Bumps, Discriminator,
+};
+use anchor_spl::{
+  token::spl_token,
+  token_2022::spl_token_2022::{
+    self,
+    extension::{
+      permanent_delegate::PermanentDelegate,
+      transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+      ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+    },
+    state::{Account as Account2022, Mint as Mint2022},
+  },
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+pub const TOKEN_DECIMALS: u8 = 6;
+
+thread_local! {
+  static UNIX_TIMESTAMP: Cell<i64> = const { Cell::new(1_700_000_000) };
+  static SWAP_OUT: Cell<u64> = const { Cell::new(0) };
+}
+
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+  fn sol_log(&self, _message: &str) {}
+
+  fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+  fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+    let clock = Clock {
+      unix_timestamp: now(),
+      ..Clock::default()
+    };
+    unsafe { *(var_addr as *mut Clock) = clock };
+
+    0
+  }
+
+  fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+    unsafe { *(var_addr as *mut Rent) = Rent::default() };
+
+    0
+  }
+
+  fn sol_invoke_signed(
+    &self,
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let signers = signers_seeds
+      .iter()
+      .map(|seeds| Pubkey::create_program_address(seeds, &crate::ID))
+      .collect::<std::result::Result<Vec<_>, _>>()?;
+    let accounts = instruction
+      .accounts
+      .iter()
+      .map(|meta| {
+        let mut account_info = account_infos
+          .iter()
+          .find(|account_info| account_info.key == &meta.pubkey)
+          .ok_or(ProgramError::NotEnoughAccountKeys)?
+          .clone();
+        account_info.is_signer = account_info.is_signer || signers.contains(&meta.pubkey);
+        account_info.is_writable = meta.is_writable;
+        Ok(account_info)
+      })
+      .collect::<std::result::Result<Vec<_>, ProgramError>>()?;
+
+    match instruction.program_id {
+      id if id == system_program::ID => process_system_instruction(&accounts, &instruction.data),
+      id if id == spl_token::ID => spl_token::processor::Processor::process(&id, &accounts, &instruction.data),
+      id if id == spl_token_2022::ID => spl_token_2022::processor::Processor::process(&id, &accounts, &instruction.data),
+      id if id == MOCK_SWAP_PROGRAM_ID => process_mock_swap(&accounts, &instruction.data),
+      _ => Err(ProgramError::IncorrectProgramId),
+    }
+  }
+}
+
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+  let instruction: SystemInstruction = limited_deserialize(data, 1024).map_err(|_| ProgramError::InvalidInstructionData)?;
+  match instruction {
+    SystemInstruction::Transfer { lamports } => move_lamports(&accounts[0], &accounts[1], lamports),
+    SystemInstruction::CreateAccount { lamports, space, owner } => {
+      move_lamports(&accounts[0], &accounts[1], lamports)?;
+      allocate(&accounts[1], space)?;
+      accounts[1].assign(&owner);
+      Ok(())
+    }
+    SystemInstruction::Allocate { space } => allocate(&accounts[0], space),
+    SystemInstruction::Assign { owner } => {
+      accounts[0].assign(&owner);
+      Ok(())
+    }
+    _ => Err(ProgramError::InvalidInstructionData),
+  }
+}
+
+/// Sells the input into the pool and pays out whatever `set_swap_out` was given, ignoring the
+/// minimum output so the caller's own check is what gets exercised. Accounts follow
+/// `mock_swap_adapter`.
+fn process_mock_swap(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+  let [authority, source, destination, source_mint, destination_mint, pool_source, pool_destination, pool_authority] = accounts else {
+    return Err(ProgramError::NotEnoughAccountKeys);
+  };
+  let amount_in = u64::from_le_bytes(data[8..16].try_into().unwrap());
+
+  token_transfer(source, source_mint, pool_source, authority, amount_in)?;
+
+  let mut pool_authority = pool_authority.clone();
+  pool_authority.is_signer = true;
+  token_transfer(pool_destination, destination_mint, destination, &pool_authority, SWAP_OUT.with(Cell::get))
+}
+
+fn token_transfer<'a>(
+  from: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  to: &AccountInfo<'a>,
+  authority: &AccountInfo<'a>,
+  amount: u64,
+) -> ProgramResult {
+  let decimals = spl_token::state::Mint::unpack_from_slice(&mint.try_borrow_data()?[..spl_token::state::Mint::LEN])?.decimals;
+  let instruction = spl_token_2022::instruction::transfer_checked(
+    from.owner,
+    from.key,
+    mint.key,
+    to.key,
+    authority.key,
+    &[],
+    amount,
+    decimals,
+  )?;
+  let accounts = [from.clone(), mint.clone(), to.clone(), authority.clone()];
+
+  match *from.owner {
+    id if id == spl_token::ID => spl_token::processor::Processor::process(&id, &accounts, &instruction.data),
+    id => spl_token_2022::processor::Processor::process(&id, &accounts, &instruction.data),
+  }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+  if !from.is_signer {
+    return Err(ProgramError::MissingRequiredSignature);
+  }
+
+  let from_lamports = from.lamports().checked_sub(lamports).ok_or(ProgramError::InsufficientFunds)?;
+  **from.try_borrow_mut_lamports()? = from_lamports;
+  **to.try_borrow_mut_lamports()? += lamports;
+
+  Ok(())
+}
+
+fn allocate(account_info: &AccountInfo, space: u64) -> ProgramResult {
+  if !account_info.data_is_empty() {
+    return Err(ProgramError::AccountAlreadyInitialized);
+  }
+
+  account_info.realloc(space as usize, true)
+}
+
+pub fn now() -> i64 {
+  UNIX_TIMESTAMP.with(|unix_timestamp| unix_timestamp.get())
+}
+
+fn install_stubs() {
+  static STUBS: Once = Once::new();
+  STUBS.call_once(|| {
+    set_syscall_stubs(Box::new(TestSyscallStubs));
+  });
+}
+
+/// Sets what the mock swap program pays out on the current test thread.
+pub fn set_swap_out(amount: u64) {
+  SWAP_OUT.with(|swap_out| swap_out.set(amount));
+}
+
+/// Sets the clock seen by the instructions run on the current test thread.
+pub fn set_now(unix_timestamp: i64) {
+  UNIX_TIMESTAMP.with(|current| current.set(unix_timestamp));
+}
+
+#[repr(C)]
+struct KeySlot {
+  _flags: u32,
+  original_data_len: u32,
+  key: Pubkey,
+}
+
+/// Builds an account with the same memory layout the runtime gives to a program, so reallocs and
+/// closes behave as they do on chain.
+pub fn account(key: Pubkey, owner: Pubkey, lamports: u64, data: Vec<u8>, is_signer: bool, executable: bool) -> AccountInfo<'static> {
+  let key_slot = Box::leak(Box::new(KeySlot {
+    _flags: 0,
+    original_data_len: data.len() as u32,
+    key,
+  }));
+  let data_len = data.len();
+  let buffer = Box::leak(vec![0u8; 8 + data_len + MAX_PERMITTED_DATA_INCREASE].into_boxed_slice());
+  buffer[..8].copy_from_slice(&(data_len as u64).to_le_bytes());
+  buffer[8..8 + data_len].copy_from_slice(&data);
+
+  install_stubs();
+
+  AccountInfo::new(
+    &key_slot.key,
+    is_signer,
+    true,
+    Box::leak(Box::new(lamports)),
+    &mut buffer[8..8 + data_len],
+    Box::leak(Box::new(owner)),
+    executable,
+    0,
+  )
+}
+
+pub fn signer(key: Pubkey) -> AccountInfo<'static> {
+  account(key, system_program::ID, 100_000_000_000, vec![], true, false)
+}
+
+pub fn wallet(key: Pubkey) -> AccountInfo<'static> {
+  account(key, system_program::ID, 0, vec![], false, false)
+}
+
+pub fn program(id: Pubkey) -> AccountInfo<'static> {
+  account(id, Pubkey::default(), 1, vec![], false, true)
+}
+
+pub fn empty(key: Pubkey) -> AccountInfo<'static> {
+  wallet(key)
+}
+
+/// Builds an account of this program holding `state`, sized for its largest layout.
+pub fn state<T: AccountSerialize + Space>(key: Pubkey, state: &T) -> AccountInfo<'static> {
+  let mut data = Vec::with_capacity(8 + T::INIT_SPACE);
+  state.try_serialize(&mut data).unwrap();
+  data.resize(8 + T::INIT_SPACE, 0);
+
+  account(key, crate::ID, Rent::default().minimum_balance(data.len()), data, false, false)
+}
+
+pub fn read_state<T: AccountDeserialize>(account_info: &AccountInfo) -> T {
+  T::try_deserialize(&mut &account_info.try_borrow_data().unwrap()[..]).unwrap()
+}
+
+pub fn mint(key: Pubkey, token_program: Pubkey) -> AccountInfo<'static> {
+  let mut data = vec![0u8; spl_token::state::Mint::LEN];
+  spl_token::state::Mint {
+    mint_authority: COption::None,
+    supply: u64::MAX / 2,
+    decimals: TOKEN_DECIMALS,
+    is_initialized: true,
+    freeze_authority: COption::None,
+  }
+  .pack_into_slice(&mut data);
+
+  account(key, token_program, 1_000_000, data, false, false)
+}
+
+pub fn token_account(key: Pubkey, token_program: Pubkey, mint: Pubkey, authority: Pubkey, amount: u64) -> AccountInfo<'static> {
+  let mut data = vec![0u8; spl_token::state::Account::LEN];
+  spl_token::state::Account {
+    mint,
+    owner: authority,
+    amount,
+    delegate: COption::None,
+    state: spl_token::state::AccountState::Initialized,
+    is_native: COption::None,
+    delegated_amount: 0,
+    close_authority: COption::None,
+  }
+  .pack_into_slice(&mut data);
+
+  account(key, token_program, 2_039_280, data, false, false)
+}
+
+/// Builds a Token-2022 mint withholding `fee_bps` of every transfer, capped at `maximum_fee`.
+pub fn transfer_fee_mint(key: Pubkey, fee_bps: u16, maximum_fee: u64) -> AccountInfo<'static> {
+  let mut data = vec![0u8; ExtensionType::try_calculate_account_len::<Mint2022>(&[ExtensionType::TransferFeeConfig]).unwrap()];
+  let mut state = StateWithExtensionsMut::<Mint2022>::unpack_uninitialized(&mut data).unwrap();
+  let transfer_fee = TransferFee {
+    epoch: 0.into(),
+    maximum_fee: maximum_fee.into(),
+    transfer_fee_basis_points: fee_bps.into(),
+  };
+  let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+  config.older_transfer_fee = transfer_fee;
+  config.newer_transfer_fee = transfer_fee;
+  state.base = mint_2022_base();
+  state.pack_base();
+  state.init_account_type().unwrap();
+
+  account(key, spl_token_2022::ID, 1_000_000, data, false, false)
+}
+
+/// Builds a Token-2022 mint whose tokens `delegate` can move out of any account.
+pub fn permanent_delegate_mint(key: Pubkey, delegate: Pubkey) -> AccountInfo<'static> {
+  let mut data = vec![0u8; ExtensionType::try_calculate_account_len::<Mint2022>(&[ExtensionType::PermanentDelegate]).unwrap()];
+  let mut state = StateWithExtensionsMut::<Mint2022>::unpack_uninitialized(&mut data).unwrap();
+  state.init_extension::<PermanentDelegate>(true).unwrap().delegate = Some(delegate).try_into().unwrap();
+  state.base = mint_2022_base();
+  state.pack_base();
+  state.init_account_type().unwrap();
+
+  account(key, spl_token_2022::ID, 1_000_000, data, false, false)
+}
+
+fn mint_2022_base() -> Mint2022 {
+  Mint2022 {
+    mint_authority: COption::None,
+    supply: u64::MAX / 2,
+    decimals: TOKEN_DECIMALS,
+    is_initialized: true,
+    freeze_authority: COption::None,
+  }
+}
+
+/// Builds a Token-2022 account of a transfer fee mint, able to hold the withheld fee.
+pub fn transfer_fee_token_account(key: Pubkey, mint: Pubkey, authority: Pubkey, amount: u64) -> AccountInfo<'static> {
+  let mut data = vec![0u8; ExtensionType::try_calculate_account_len::<Account2022>(&[ExtensionType::TransferFeeAmount]).unwrap()];
+  let mut state = StateWithExtensionsMut::<Account2022>::unpack_uninitialized(&mut data).unwrap();
+  state.init_extension::<TransferFeeAmount>(true).unwrap();
+  state.base = Account2022 {
+    mint,
+    owner: authority,
+    amount,
+    state: spl_token_2022::state::AccountState::Initialized,
+    ..Account2022::default()
+  };
+  state.pack_base();
+  state.init_account_type().unwrap();
+
+  account(key, spl_token_2022::ID, 2_039_280, data, false, false)
+}
+
+pub fn token_amount(account_info: &AccountInfo) -> u64 {
+  StateWithExtensions::<Account2022>::unpack(&account_info.try_borrow_data().unwrap()).unwrap().base.amount
+}
+
+/// Builds a Pyth price update posted now, with `price` scaled by `10^exponent`.
+pub fn price_feed(key: Pubkey, feed_id: [u8; 32], price: i64, exponent: i32) -> AccountInfo<'static> {
+  let mut data = Vec::with_capacity(PriceUpdateV2::LEN);
+  data.extend_from_slice(&PriceUpdateV2::DISCRIMINATOR);
+  data.extend_from_slice(Pubkey::default().as_ref());
+  // Full verification level
+  data.push(1);
+  data.extend_from_slice(&feed_id);
+  data.extend_from_slice(&price.to_le_bytes());
+  data.extend_from_slice(&0u64.to_le_bytes());
+  data.extend_from_slice(&exponent.to_le_bytes());
+  data.extend_from_slice(&now().to_le_bytes());
+  data.extend_from_slice(&now().to_le_bytes());
+  data.extend_from_slice(&price.to_le_bytes());
+  data.extend_from_slice(&0u64.to_le_bytes());
+  data.extend_from_slice(&0u64.to_le_bytes());
+
+  account(key, pyth_solana_receiver_sdk::ID, 1_000_000, data, false, false)
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+  Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+/// Validates `accounts` against the context `T` the way the program entrypoint does, with
+/// `ix_data` holding the borsh encoded instruction arguments the context reads.
+pub fn load<T, B>(accounts: Vec<AccountInfo<'static>>, ix_data: &[u8]) -> Result<(T, B, &'static [AccountInfo<'static>])>
+where
+  T: Accounts<'static, B> + Bumps<Bumps = B>,
+  B: Default,
+{
+  install_stubs();
+
+  let mut remaining_accounts: &'static [AccountInfo<'static>] = Box::leak(accounts.into_boxed_slice());
+  let mut bumps = B::default();
+  let context = T::try_accounts(&crate::ID, &mut remaining_accounts, ix_data, &mut bumps, &mut BTreeSet::new())?;
+
+  Ok((context, bumps, remaining_accounts))
+}
+
+pub fn args<T: AnchorSerialize>(args: T) -> Vec<u8> {
+  args.try_to_vec().unwrap()
+}
+
+pub fn error_code(error: Error) -> u32 {
+  match error {
+    Error::AnchorError(anchor_error) => anchor_error.error_code_number,
+    Error::ProgramError(program_error) => u64::from(program_error.program_error) as u32,
+  }
+}
+
+pub fn assert_error<T: std::fmt::Debug>(result: Result<T>, expected: impl Into<u32>) {
+  match result {
+    Ok(value) => panic!("expected error {}, got Ok({:?})", expected.into(), value),
+    Err(error) => assert_eq!(error_code(error), expected.into()),
+  }
+}
+
+pub fn global_config_key() -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::GLOBAL_CONFIG_SEED, crate::ID.as_ref()])
+}
+
+pub fn global_config(admin: Pubkey, hot_wallet: Pubkey) -> crate::GlobalConfig {
+  crate::GlobalConfig {
+    admin,
+    hot_wallet,
+    pause_flags: crate::PauseFlags::default(),
+    setting_change_delay: 0,
+    withdraw_request_cooldown: 0,
+    auction_parameters: crate::AuctionParameters {
+      start_premium_bps: 1_000,
+      floor_discount_bps: 2_000,
+      duration: 3_600,
+    },
+    liquidation_slippage_bps: 100,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::GLOBAL_CONFIG_SEED, crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub const TIER_ID: &str = "tier";
+
+pub fn role_account_key(role: crate::Role) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::ROLE_ACCOUNT_SEED, &[role as u8], crate::ID.as_ref()])
+}
+
+pub fn role_account(role: crate::Role, members: Vec<Pubkey>) -> crate::RoleAccount {
+  crate::RoleAccount {
+    role,
+    members,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::ROLE_ACCOUNT_SEED, &[role as u8], crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn setting_account_key(tier_id: &str) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::SETTING_ACCOUNT_SEED, tier_id.as_bytes(), crate::ID.as_ref()])
+}
+
+pub fn setting_account(owner: Pubkey, lend_mint_asset: Pubkey, collateral_mint_asset: Pubkey) -> crate::SettingAccount {
+  crate::SettingAccount {
+    amount: 1_000_000_000,
+    min_amount: 1_000_000,
+    duration: 30 * 24 * 60 * 60,
+    owner,
+    pending_owner: None,
+    receiver: Pubkey::new_unique(),
+    lend_mint_asset,
+    collateral_mint_asset,
+    tier_id: TIER_ID.to_string(),
+    lender_fee_bps: 500,
+    borrower_fee_bps: 500,
+    risk_parameters: crate::RiskParameters {
+      initial_ltv_bps: 5_000,
+      liquidation_threshold_bps: 8_000,
+      liquidation_penalty_bps: 500,
+    },
+    pause_flags: crate::PauseFlags::default(),
+    bad_debt_amount: 0,
+    defaulted_loan_count: 0,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::SETTING_ACCOUNT_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn lend_offer_key(lender: &Pubkey, offer_id: &str) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::LEND_OFFER_ACCOUNT_SEED, lender.as_ref(), offer_id.as_bytes(), crate::ID.as_ref()])
+}
+
+pub fn lend_offer(lender: Pubkey, offer_id: &str, lend_mint_token: Pubkey, amount: u64) -> crate::LendOfferAccount {
+  crate::LendOfferAccount {
+    interest_bps: 1_000,
+    lender_fee_bps: 500,
+    duration: 30 * 24 * 60 * 60,
+    offer_id: offer_id.to_string(),
+    tier_id: TIER_ID.to_string(),
+    lender,
+    lend_mint_token,
+    amount,
+    remaining_amount: amount,
+    loan_count: 0,
+    bump: Pubkey::find_program_address(
+      &[crate::ENSO_SEED, crate::LEND_OFFER_ACCOUNT_SEED, lender.as_ref(), offer_id.as_bytes(), crate::ID.as_ref()],
+      &crate::ID
+    ).1,
+    status: crate::LendOfferStatus::Created,
+  }
+}
+
+pub fn loan_offer_key(borrower: &Pubkey, offer_id: &str) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::LOAN_OFFER_ACCOUNT_SEED, borrower.as_ref(), offer_id.as_bytes(), crate::ID.as_ref()])
+}
+
+/// A loan of 100 lend tokens at 10% a year over 365 days with 5% fees on both sides.
+pub fn loan_offer(borrower: Pubkey, offer_id: &str, lender: Pubkey, lend_mint_token: Pubkey, collateral_mint_token: Pubkey) -> crate::LoanOfferAccount {
+  crate::LoanOfferAccount {
+    tier_id: TIER_ID.to_string(),
+    lend_offer_id: "lend_offer".to_string(),
+    interest_bps: 1_000,
+    borrow_amount: 100_000_000,
+    lender_fee_bps: 500,
+    duration: crate::SECONDS_PER_YEAR,
+    lend_mint_token,
+    lender,
+    offer_id: offer_id.to_string(),
+    borrower,
+    collateral_mint_token,
+    collateral_amount: 1_000_000_000,
+    request_withdraw_amount: None,
+    request_withdraw_at: None,
+    request_withdraw_approved: false,
+    request_withdraw_mint: None,
+    collateral_positions: vec![],
+    status: crate::LoanOfferStatus::FundTransferred,
+    borrower_fee_bps: 500,
+    started_at: now(),
+    liquidating_at: None,
+    liquidating_price: None,
+    liquidated_tx: None,
+    liquidated_price: None,
+    deficit_amount: 0,
+    bump: Pubkey::find_program_address(
+      &[crate::ENSO_SEED, crate::LOAN_OFFER_ACCOUNT_SEED, borrower.as_ref(), offer_id.as_bytes(), crate::ID.as_ref()],
+      &crate::ID
+    ).1,
+  }
+}
+
+pub fn ata(authority: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+  anchor_spl::associated_token::get_associated_token_address_with_program_id(authority, mint, token_program)
+}
+
+pub const LEND_FEED_ID: [u8; 32] = [1; 32];
+pub const COLLATERAL_FEED_ID: [u8; 32] = [2; 32];
+pub const PRICE_EXPONENT: i32 = -8;
+
+/// Raw Pyth price of `usd` dollars at `PRICE_EXPONENT`.
+pub fn usd_price(usd: i64) -> i64 {
+  usd * 100_000_000
+}
+
+pub fn price_update(feed_id: [u8; 32], price: i64) -> PriceUpdateV2 {
+  read_state(&price_feed(Pubkey::new_unique(), feed_id, price, PRICE_EXPONENT))
+}
+
+pub fn asset_config_key(mint: &Pubkey) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::ASSET_CONFIG_SEED, mint.as_ref(), crate::ID.as_ref()])
+}
+
+pub fn asset_config(mint: Pubkey, price_feed_id: [u8; 32], decimals: u8) -> crate::AssetConfig {
+  crate::AssetConfig {
+    mint,
+    price_feed_id,
+    max_price_age: 60,
+    decimals,
+    enabled: true,
+    collateral_haircut_bps: 0,
+    liquidation_priority: 0,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::ASSET_CONFIG_SEED, mint.as_ref(), crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn liquidation_auction_key(loan_offer: &Pubkey) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::LIQUIDATION_AUCTION_SEED, loan_offer.as_ref(), crate::ID.as_ref()])
+}
+
+/// Hour long auction of one SOL started now, decaying from 132 to 96 lend tokens and raising
+/// the 110.5 lend tokens owed on the `loan_offer` fixture.
+pub fn liquidation_auction(loan_offer: Pubkey, initiator: Pubkey) -> crate::LiquidationAuction {
+  crate::LiquidationAuction {
+    loan_offer,
+    initiator,
+    collateral_amount: 1_000_000_000,
+    remaining_collateral: 1_000_000_000,
+    start_lot_price: 132_000_000,
+    floor_lot_price: 96_000_000,
+    started_at: now(),
+    duration: 3_600,
+    debt_amount: 110_500_000,
+    proceeds: 0,
+    bump: Pubkey::find_program_address(
+      &[crate::ENSO_SEED, crate::LIQUIDATION_AUCTION_SEED, loan_offer.as_ref(), crate::ID.as_ref()],
+      &crate::ID
+    ).1,
+  }
+}
+
+pub fn approval_config_key() -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::APPROVAL_CONFIG_SEED, crate::ID.as_ref()])
+}
+
+pub fn approval_config(approvers: Vec<Pubkey>, threshold: u8) -> crate::ApprovalConfig {
+  crate::ApprovalConfig {
+    approvers,
+    threshold,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::APPROVAL_CONFIG_SEED, crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn proposal_key(proposal_id: &str) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::PROPOSAL_SEED, proposal_id.as_bytes(), crate::ID.as_ref()])
+}
+
+pub fn proposal(proposal_id: &str, action: crate::ProposalAction, approvals: Vec<Pubkey>) -> crate::Proposal {
+  crate::Proposal {
+    proposal_id: proposal_id.to_string(),
+    proposer: Pubkey::new_unique(),
+    action,
+    approvals,
+    executed: false,
+    created_at: now(),
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::PROPOSAL_SEED, proposal_id.as_bytes(), crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn reserve_fund_key(lend_mint: &Pubkey) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::RESERVE_FUND_SEED, lend_mint.as_ref(), crate::ID.as_ref()])
+}
+
+pub fn reserve_vault_key(lend_mint: &Pubkey) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::RESERVE_VAULT_SEED, lend_mint.as_ref(), crate::ID.as_ref()])
+}
+
+pub fn reserve_fund(lend_mint: Pubkey, fee_share_bps: u64, balance: u64) -> crate::ReserveFund {
+  crate::ReserveFund {
+    lend_mint,
+    fee_share_bps,
+    balance,
+    total_inflow: balance,
+    total_payout: 0,
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::RESERVE_FUND_SEED, lend_mint.as_ref(), crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+pub fn borrow_request_key(borrower: &Pubkey, request_id: &str) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::BORROW_REQUEST_SEED, borrower.as_ref(), request_id.as_bytes(), crate::ID.as_ref()])
+}
+
+pub fn borrow_request_escrow_key(borrow_request: &Pubkey) -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::BORROW_REQUEST_ESCROW_SEED, borrow_request.as_ref(), crate::ID.as_ref()])
+}
+
+/// Request for 100 lend tokens over 30 days against 250 collateral tokens, at most 15% a year.
+pub fn borrow_request(borrower: Pubkey, request_id: &str, lend_mint_token: Pubkey, collateral_mint_token: Pubkey) -> crate::BorrowRequestAccount {
+  crate::BorrowRequestAccount {
+    request_id: request_id.to_string(),
+    tier_id: TIER_ID.to_string(),
+    borrower,
+    lend_mint_token,
+    collateral_mint_token,
+    collateral_amount: 250_000_000,
+    amount: 100_000_000,
+    max_interest_bps: 1_500,
+    duration: 30 * 24 * 60 * 60,
+    bump: Pubkey::find_program_address(
+      &[crate::ENSO_SEED, crate::BORROW_REQUEST_SEED, borrower.as_ref(), request_id.as_bytes(), crate::ID.as_ref()],
+      &crate::ID
+    ).1,
+    status: crate::BorrowRequestStatus::Created,
+  }
+}
+
+pub fn order_book_key() -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()])
+}
+
+/// Empty order book of the fixture tier, listing offers of at least 1 lend token.
+pub fn order_book() -> crate::OrderBook {
+  crate::OrderBook {
+    tier_id: TIER_ID.to_string(),
+    min_amount: 1_000_000,
+    entries: Vec::new(),
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::ORDER_BOOK_SEED, TIER_ID.as_bytes(), crate::ID.as_ref()], &crate::ID).1,
+  }
+}
+
+/// Order book of the fixture tier filled with 100 lend token offers at 10% interest.
+pub fn full_order_book() -> crate::OrderBook {
+  let mut order_book = order_book();
+  for _ in 0..crate::MAX_ORDER_BOOK_ENTRIES {
+    order_book.insert(Pubkey::new_unique(), 1_000, 100_000_000).unwrap();
+  }
+
+  order_book
+}
+
+pub const MOCK_SWAP_PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+pub fn swap_adapter_key() -> Pubkey {
+  pda(&[crate::ENSO_SEED, crate::SWAP_ADAPTER_SEED, crate::ID.as_ref()])
+}
+
+/// Adapter for the mock swap program, trading against a pool passed in the three `Extra` slots:
+/// its input account, its output account and the authority of the output account.
+pub fn mock_swap_adapter() -> crate::SwapAdapter {
+  let slot = |kind, is_writable| crate::SwapAccountSlot {
+    kind,
+    is_writable,
+    address: None,
+  };
+
+  crate::SwapAdapter {
+    program_id: MOCK_SWAP_PROGRAM_ID,
+    instruction_discriminator: [9; 8],
+    account_layout: vec![
+      slot(crate::SwapAccountKind::Authority, false),
+      slot(crate::SwapAccountKind::Source, true),
+      slot(crate::SwapAccountKind::Destination, true),
+      slot(crate::SwapAccountKind::SourceMint, false),
+      slot(crate::SwapAccountKind::DestinationMint, false),
+      slot(crate::SwapAccountKind::Extra, true),
+      slot(crate::SwapAccountKind::Extra, true),
+      slot(crate::SwapAccountKind::Extra, false),
+    ],
+    bump: Pubkey::find_program_address(&[crate::ENSO_SEED, crate::SWAP_ADAPTER_SEED, crate::ID.as_ref()], &crate::ID).1,
+  }
+}
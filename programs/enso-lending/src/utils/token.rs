@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::ID as TOKEN_PROGRAM_ID,
+    token_2022::spl_token_2022::{
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        state::Mint as MintState,
+    },
+};
+
+use crate::{MathError, MintError};
+
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 5] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+/// Rejects Token-2022 mints with extensions that let a third party move, freeze or
+/// intercept escrowed funds, such as a permanent delegate or a transfer hook.
+pub fn validate_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if mint.owner == &TOKEN_PROGRAM_ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    for extension in mint_state.get_extension_types()? {
+        if !SUPPORTED_MINT_EXTENSIONS.contains(&extension) {
+            return err!(MintError::UnsupportedExtension);
+        }
+    }
+
+    Ok(())
+}
+
+/// Transfer fee withheld by the mint when `amount` is sent in the current epoch.
+pub fn calculate_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if mint.owner == &TOKEN_PROGRAM_ID {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(MathError::Overflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Amount that arrives at the destination when `amount` is sent.
+pub fn calculate_received_amount(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let fee = calculate_transfer_fee(mint, amount)?;
+
+    amount.checked_sub(fee).ok_or(error!(MathError::Overflow))
+}
+
+/// Amount that has to be sent so that `received_amount` arrives at the destination.
+pub fn calculate_amount_with_transfer_fee(mint: &AccountInfo, received_amount: u64) -> Result<u64> {
+    if mint.owner == &TOKEN_PROGRAM_ID {
+        return Ok(received_amount);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, received_amount)
+            .ok_or(error!(MathError::Overflow))?,
+        Err(_) => 0,
+    };
+
+    received_amount.checked_add(fee).ok_or(error!(MathError::Overflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn spl_token_mints_carry_no_fee() {
+        let mint = mint(Pubkey::new_unique(), TOKEN_PROGRAM_ID);
+
+        assert!(validate_mint_extensions(&mint).is_ok());
+        assert_eq!(calculate_transfer_fee(&mint, 1_000_000).unwrap(), 0);
+        assert_eq!(calculate_received_amount(&mint, 1_000_000).unwrap(), 1_000_000);
+        assert_eq!(calculate_amount_with_transfer_fee(&mint, 1_000_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn transfer_fee_is_withheld_from_received_amount() {
+        let mint = transfer_fee_mint(Pubkey::new_unique(), 100, u64::MAX);
+
+        assert!(validate_mint_extensions(&mint).is_ok());
+        assert_eq!(calculate_transfer_fee(&mint, 1_000_000).unwrap(), 10_000);
+        assert_eq!(calculate_received_amount(&mint, 1_000_000).unwrap(), 990_000);
+    }
+
+    #[test]
+    fn amount_with_transfer_fee_delivers_requested_amount() {
+        let mint = transfer_fee_mint(Pubkey::new_unique(), 100, u64::MAX);
+
+        let amount = calculate_amount_with_transfer_fee(&mint, 990_000).unwrap();
+
+        assert_eq!(amount, 1_000_000);
+        assert_eq!(calculate_received_amount(&mint, amount).unwrap(), 990_000);
+    }
+
+    #[test]
+    fn transfer_fee_is_capped_at_maximum_fee() {
+        let mint = transfer_fee_mint(Pubkey::new_unique(), 100, 5_000);
+
+        assert_eq!(calculate_received_amount(&mint, 1_000_000).unwrap(), 995_000);
+        assert_eq!(calculate_amount_with_transfer_fee(&mint, 995_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn rejects_permanent_delegate_mint() {
+        let mint = permanent_delegate_mint(Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_error(validate_mint_extensions(&mint), MintError::UnsupportedExtension);
+    }
+}
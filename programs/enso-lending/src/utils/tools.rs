@@ -1,3 +1,222 @@
-pub fn duration_to_year(duration: u64) -> f64 {
-    (duration as f64) / ((24 * 60 * 60 * 365) as f64)
-}
\ No newline at end of file
+use anchor_lang::prelude::*;
+
+use crate::{LiquidateOfferError, MathError, BPS_DENOMINATOR, SECONDS_PER_YEAR};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+pub fn mul_div(value: u128, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    if denominator == 0 {
+        return err!(MathError::DivisionByZero);
+    }
+
+    let product = value.checked_mul(numerator).ok_or(MathError::Overflow)?;
+    let quotient = product / denominator;
+
+    if rounding == Rounding::Up && product % denominator != 0 {
+        return Ok(quotient + 1);
+    }
+
+    Ok(quotient)
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(MathError::Overflow))
+}
+
+/// Simple interest accrued on `amount` at `interest_bps` per year over `duration` seconds.
+pub fn calculate_interest(amount: u64, interest_bps: u64, duration: u64, rounding: Rounding) -> Result<u64> {
+    let rate_time = (interest_bps as u128)
+        .checked_mul(duration as u128)
+        .ok_or(MathError::Overflow)?;
+
+    to_u64(mul_div(
+        amount as u128,
+        rate_time,
+        BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128,
+        rounding,
+    )?)
+}
+
+pub fn calculate_fee(amount: u64, fee_bps: u64, rounding: Rounding) -> Result<u64> {
+    to_u64(mul_div(amount as u128, fee_bps as u128, BPS_DENOMINATOR as u128, rounding)?)
+}
+
+/// Ratio of debt value to collateral value, in basis points. A position without
+/// collateral value saturates at `u64::MAX`.
+pub fn calculate_ltv_bps(debt_value: u128, collateral_value: u128) -> Result<u64> {
+    if collateral_value == 0 {
+        return Ok(u64::MAX);
+    }
+
+    let ltv_bps = mul_div(debt_value, BPS_DENOMINATOR as u128, collateral_value, Rounding::Up)?;
+
+    Ok(u64::try_from(ltv_bps).unwrap_or(u64::MAX))
+}
+
+/// Debt value a partial liquidation must repay to bring the LTV back to `target_ltv_bps`,
+/// given that each unit of repaid debt also seizes `penalty_bps` of extra collateral value.
+pub fn calculate_partial_liquidation_repay_value(
+    debt_value: u128,
+    collateral_value: u128,
+    target_ltv_bps: u64,
+    penalty_bps: u64,
+) -> Result<u128> {
+    let bps = BPS_DENOMINATOR as u128;
+    let target_debt_value = mul_div(collateral_value, target_ltv_bps as u128, bps, Rounding::Down)?;
+    let seized_ltv_bps = mul_div(target_ltv_bps as u128, bps + penalty_bps as u128, bps, Rounding::Up)?;
+
+    if seized_ltv_bps >= bps {
+        return err!(LiquidateOfferError::PartialLiquidationNotPossible);
+    }
+
+    mul_div(debt_value.saturating_sub(target_debt_value), bps, bps - seized_ltv_bps, Rounding::Up)
+}
+
+/// Checks that `value` lies within `band_bps` of `reference`, on either side.
+pub fn validate_within_band(value: u128, reference: u128, band_bps: u64) -> Result<()> {
+    let bps = BPS_DENOMINATOR as u128;
+    let lower_bound = mul_div(reference, bps.saturating_sub(band_bps as u128), bps, Rounding::Down)?;
+    let upper_bound = mul_div(reference, bps + band_bps as u128, bps, Rounding::Up)?;
+
+    if value < lower_bound || value > upper_bound {
+        return err!(LiquidateOfferError::OutsideSlippageBand);
+    }
+
+    Ok(())
+}
+
+/// Splits liquidation proceeds into the lender payout, the protocol fees and the borrower
+/// surplus, paying the lender first when the proceeds fall short of the debt.
+pub fn split_liquidation_proceeds(proceeds: u64, lender_due: u64, fee_due: u64) -> Result<(u64, u64, u64)> {
+    let lender_amount = proceeds.min(lender_due);
+    let fee_amount = (proceeds - lender_amount).min(fee_due);
+    let surplus = proceeds
+        .checked_sub(lender_amount + fee_amount)
+        .ok_or(MathError::Overflow)?;
+
+    Ok((lender_amount, fee_amount, surplus))
+}
+
+/// Splits `seized_value` across collateral holdings given as `(amount, value)` pairs in the
+/// order they are seized. Holdings are taken whole until the remaining value only covers part
+/// of the next one, which is seized pro rata and rounded down.
+pub fn allocate_seizure(seized_value: u128, holdings: &[(u64, u128)]) -> Result<Vec<u64>> {
+    let mut remaining_value = seized_value;
+
+    holdings
+        .iter()
+        .map(|&(amount, value)| {
+            if remaining_value == 0 {
+                return Ok(0);
+            }
+
+            if value <= remaining_value {
+                remaining_value -= value;
+                return Ok(amount);
+            }
+
+            let seized_amount = to_u64(mul_div(remaining_value, amount as u128, value, Rounding::Down)?)?;
+            remaining_value = 0;
+
+            Ok(seized_amount)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_error;
+
+    #[test]
+    fn mul_div_rounds_in_requested_direction() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up).unwrap(), 4);
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up).unwrap(), 3);
+    }
+
+    #[test]
+    fn mul_div_rejects_zero_denominator_and_overflow() {
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down).unwrap_err(), MathError::DivisionByZero.into());
+        assert_eq!(mul_div(u128::MAX, 2, 1, Rounding::Down).unwrap_err(), MathError::Overflow.into());
+        assert_eq!(to_u64(u64::MAX as u128 + 1).unwrap_err(), MathError::Overflow.into());
+    }
+
+    #[test]
+    fn interest_accrues_pro_rata_over_the_year() {
+        assert_eq!(calculate_interest(100_000_000, 1_000, SECONDS_PER_YEAR, Rounding::Down).unwrap(), 10_000_000);
+        assert_eq!(calculate_interest(100_000_000, 1_000, SECONDS_PER_YEAR / 2, Rounding::Down).unwrap(), 5_000_000);
+        assert_eq!(calculate_interest(100_000_000, 1_000, 0, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn interest_rounds_dust_up_for_borrowers() {
+        assert_eq!(calculate_interest(1, 1, 1, Rounding::Down).unwrap(), 0);
+        assert_eq!(calculate_interest(1, 1, 1, Rounding::Up).unwrap(), 1);
+    }
+
+    #[test]
+    fn fee_is_a_share_of_amount_in_bps() {
+        assert_eq!(calculate_fee(10_000_000, 500, Rounding::Down).unwrap(), 500_000);
+        assert_eq!(calculate_fee(1, 500, Rounding::Down).unwrap(), 0);
+        assert_eq!(calculate_fee(1, 500, Rounding::Up).unwrap(), 1);
+    }
+
+    #[test]
+    fn ltv_rounds_up_and_saturates_without_collateral() {
+        assert_eq!(calculate_ltv_bps(50, 100).unwrap(), 5_000);
+        assert_eq!(calculate_ltv_bps(1, 3).unwrap(), 3_334);
+        assert_eq!(calculate_ltv_bps(1, 0).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn partial_liquidation_restores_target_ltv() {
+        let repay_value = calculate_partial_liquidation_repay_value(90_000_000, 100_000_000, 5_000, 500).unwrap();
+        let seized_value = mul_div(repay_value, 10_500, 10_000, Rounding::Up).unwrap();
+
+        assert_eq!(repay_value, 84_210_527);
+        assert_eq!(calculate_ltv_bps(90_000_000 - repay_value, 100_000_000 - seized_value).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn partial_liquidation_of_healthy_loan_repays_nothing() {
+        assert_eq!(calculate_partial_liquidation_repay_value(40_000_000, 100_000_000, 5_000, 500).unwrap(), 0);
+    }
+
+    #[test]
+    fn partial_liquidation_needs_room_for_the_penalty() {
+        assert_error(
+            calculate_partial_liquidation_repay_value(99_000_000, 100_000_000, 9_600, 500),
+            LiquidateOfferError::PartialLiquidationNotPossible,
+        );
+    }
+
+    #[test]
+    fn seizure_takes_holdings_whole_then_pro_rata() {
+        let holdings = [(1_000, 100), (2_000, 100), (3_000, 100)];
+
+        assert_eq!(allocate_seizure(150, &holdings).unwrap(), vec![1_000, 1_000, 0]);
+        assert_eq!(allocate_seizure(300, &holdings).unwrap(), vec![1_000, 2_000, 3_000]);
+        assert_eq!(allocate_seizure(1_000, &holdings).unwrap(), vec![1_000, 2_000, 3_000]);
+        assert_eq!(allocate_seizure(0, &holdings).unwrap(), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn band_accepts_values_within_slippage_on_either_side() {
+        assert!(validate_within_band(99, 100, 100).is_ok());
+        assert!(validate_within_band(101, 100, 100).is_ok());
+        assert_error(validate_within_band(98, 100, 100), LiquidateOfferError::OutsideSlippageBand);
+        assert_error(validate_within_band(102, 100, 100), LiquidateOfferError::OutsideSlippageBand);
+    }
+
+    #[test]
+    fn liquidation_proceeds_pay_lender_then_fees_then_borrower() {
+        assert_eq!(split_liquidation_proceeds(120_000_000, 109_500_000, 1_000_000).unwrap(), (109_500_000, 1_000_000, 9_500_000));
+        assert_eq!(split_liquidation_proceeds(110_000_000, 109_500_000, 1_000_000).unwrap(), (109_500_000, 500_000, 0));
+        assert_eq!(split_liquidation_proceeds(100_000_000, 109_500_000, 1_000_000).unwrap(), (100_000_000, 0, 0));
+    }
+}
@@ -0,0 +1,189 @@
+use anchor_lang::{prelude::*, solana_program::{program::invoke_signed, system_instruction}};
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    CollateralPosition, CollateralPositionError, CollateralVaultError, LoanOfferAccount, MathError, COLLATERAL_VAULT_SEED, ENSO_SEED,
+    LOAN_OFFER_ACCOUNT_SEED, NATIVE_COLLATERAL_VAULT_SEED
+};
+
+/// Lamports held by a native collateral vault on top of its rent-exempt reserve.
+pub fn native_vault_balance(vault: &AccountInfo) -> Result<u64> {
+    let rent_reserve = Rent::get()?.minimum_balance(0);
+
+    vault.lamports().checked_sub(rent_reserve).ok_or(error!(MathError::Overflow))
+}
+
+/// Checks that a collateral vault holds at least the amount recorded on the loan. Vaults are
+/// PDAs anyone can send funds to, so a surplus is tolerated and released with the vault.
+pub fn validate_vault_balance(vault_balance: u64, collateral_amount: u64) -> Result<()> {
+    if vault_balance < collateral_amount {
+        return err!(CollateralVaultError::InsufficientBalance);
+    }
+
+    Ok(())
+}
+
+/// Moves lamports out of the native collateral vault of `loan_offer`, signing for the vault PDA.
+pub fn transfer_from_native_vault<'info>(
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    loan_offer: Pubkey,
+    vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+        ENSO_SEED,
+        NATIVE_COLLATERAL_VAULT_SEED,
+        loan_offer.as_ref(),
+        program_id.as_ref(),
+        &[vault_bump],
+    ]];
+
+    invoke_signed(
+        &system_instruction::transfer(vault.key, to.key, amount),
+        &[vault.clone(), to.clone(), system_program.clone()],
+        signer,
+    )?;
+
+    Ok(())
+}
+
+/// Moves the seized part of each basket position of `loan_offer` to the liquidator and shrinks
+/// the positions, closing the vaults that are emptied. `position_accounts` holds the mint, the
+/// vault, the receiving token account and the token program of each position, in the order the
+/// positions are stored. Returns what was taken from each seized position.
+pub fn seize_collateral_positions<'info>(
+    loan_offer: &mut Account<'info, LoanOfferAccount>,
+    position_accounts: &'info [AccountInfo<'info>],
+    seized_amounts: &[u64],
+    rent_destination: &AccountInfo<'info>,
+) -> Result<Vec<CollateralPosition>> {
+    if position_accounts.len() != loan_offer.collateral_positions.len() * 4 {
+        return err!(CollateralPositionError::PositionAccountsMismatch);
+    }
+
+    let loan_offer_key = loan_offer.key();
+    let borrower = loan_offer.borrower;
+    let program_id = crate::ID;
+    let signer: &[&[&[u8]]] = &[&[
+        ENSO_SEED,
+        LOAN_OFFER_ACCOUNT_SEED,
+        borrower.as_ref(),
+        loan_offer.offer_id.as_bytes(),
+        program_id.as_ref(),
+        &[loan_offer.bump],
+    ]];
+
+    let mut seized_positions = Vec::new();
+    for ((position, accounts), &seized_amount) in loan_offer.collateral_positions
+        .iter()
+        .zip(position_accounts.chunks(4))
+        .zip(seized_amounts)
+    {
+        if seized_amount == 0 {
+            continue;
+        }
+
+        let (vault_address, _) = Pubkey::find_program_address(
+            &[ENSO_SEED, COLLATERAL_VAULT_SEED, loan_offer_key.as_ref(), position.mint.as_ref(), program_id.as_ref()],
+            &program_id,
+        );
+        if accounts[0].key() != position.mint || accounts[1].key() != vault_address {
+            return err!(CollateralPositionError::PositionAccountsMismatch);
+        }
+
+        let mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        let vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        let token_program = Interface::<TokenInterface>::try_from(&accounts[3])?;
+        validate_vault_balance(vault.amount, position.amount)?;
+
+        // An emptied position releases the whole vault, surplus included, so it can be closed.
+        let emptied = seized_amount >= position.amount;
+        let transfer_amount = if emptied { vault.amount } else { seized_amount };
+
+        let transfer_accounts = TransferChecked {
+            from: accounts[1].clone(),
+            mint: accounts[0].clone(),
+            to: accounts[2].clone(),
+            authority: loan_offer.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new_with_signer(token_program.to_account_info(), transfer_accounts, signer),
+            transfer_amount,
+            mint.decimals,
+        )?;
+
+        if emptied {
+            let close_accounts = CloseAccount {
+                account: accounts[1].clone(),
+                destination: rent_destination.clone(),
+                authority: loan_offer.to_account_info(),
+            };
+            close_account(CpiContext::new_with_signer(token_program.to_account_info(), close_accounts, signer))?;
+        }
+
+        seized_positions.push(CollateralPosition { mint: position.mint, amount: transfer_amount });
+    }
+
+    loan_offer.collateral_positions = loan_offer.collateral_positions
+        .iter()
+        .zip(seized_amounts)
+        .filter(|(position, &seized_amount)| position.amount > seized_amount)
+        .map(|(position, &seized_amount)| CollateralPosition { mint: position.mint, amount: position.amount - seized_amount })
+        .collect();
+
+    Ok(seized_positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn native_vault(loan_offer: &Pubkey, lamports: u64) -> (AccountInfo<'static>, u8) {
+        let (vault, bump) = Pubkey::find_program_address(
+            &[ENSO_SEED, NATIVE_COLLATERAL_VAULT_SEED, loan_offer.as_ref(), crate::ID.as_ref()],
+            &crate::ID,
+        );
+
+        (account(vault, System::id(), lamports, vec![], false, false), bump)
+    }
+
+    #[test]
+    fn native_vault_balance_excludes_rent_reserve() {
+        let rent_reserve = Rent::default().minimum_balance(0);
+        let (vault, _) = native_vault(&Pubkey::new_unique(), rent_reserve + 1_000);
+
+        assert_eq!(native_vault_balance(&vault).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn vault_balance_must_cover_collateral_amount() {
+        assert!(validate_vault_balance(1_000, 1_000).is_ok());
+        assert!(validate_vault_balance(1_001, 1_000).is_ok());
+        assert_error(validate_vault_balance(999, 1_000), CollateralVaultError::InsufficientBalance);
+    }
+
+    #[test]
+    fn transfers_out_of_native_vault_signed_by_vault_pda() {
+        let loan_offer = Pubkey::new_unique();
+        let (vault, bump) = native_vault(&loan_offer, 5_000);
+        let borrower = wallet(Pubkey::new_unique());
+
+        transfer_from_native_vault(&vault, &borrower, &program(System::id()), loan_offer, bump, 2_000).unwrap();
+
+        assert_eq!(vault.lamports(), 3_000);
+        assert_eq!(borrower.lamports(), 2_000);
+    }
+
+    #[test]
+    fn native_vault_of_other_loan_cannot_be_drained() {
+        let (vault, bump) = native_vault(&Pubkey::new_unique(), 5_000);
+        let borrower = wallet(Pubkey::new_unique());
+
+        assert!(transfer_from_native_vault(&vault, &borrower, &program(System::id()), Pubkey::new_unique(), bump, 2_000).is_err());
+        assert_eq!(vault.lamports(), 5_000);
+    }
+}
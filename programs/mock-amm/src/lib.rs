@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("BytzFbDoaJCniZ5pQvjGaMf2stp2CfVCaUW9BE4NJ3Un");
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const INPUT_VAULT_SEED: &[u8] = b"input_vault";
+pub const OUTPUT_VAULT_SEED: &[u8] = b"output_vault";
+
+/// Swaps `input_mint` into `output_mint` at a fixed rate. It only exists so that the lending
+/// program's swap adapter can be exercised against a local validator without a real DEX.
+#[program]
+pub mod mock_amm {
+  use super::*;
+
+  pub fn init_pool(ctx: Context<InitPool>, rate_numerator: u64, rate_denominator: u64) -> Result<()> {
+    if rate_denominator == 0 {
+      return err!(MockAmmError::InvalidRate);
+    }
+
+    ctx.accounts.pool.set_inner(Pool {
+      input_mint: ctx.accounts.input_mint.key(),
+      output_mint: ctx.accounts.output_mint.key(),
+      rate_numerator,
+      rate_denominator,
+      bump: ctx.bumps.pool,
+    });
+
+    Ok(())
+  }
+
+  pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+    let amount_out = ctx.accounts.pool.amount_out(amount_in)?;
+    if amount_out < minimum_amount_out {
+      return err!(MockAmmError::SlippageExceeded);
+    }
+
+    ctx.accounts.swap(amount_in, amount_out)
+  }
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Pool {
+  pub input_mint: Pubkey,
+  pub output_mint: Pubkey,
+  pub rate_numerator: u64,
+  pub rate_denominator: u64,
+  pub bump: u8,
+}
+
+impl Pool {
+  pub fn amount_out(&self, amount_in: u64) -> Result<u64> {
+    let amount_out = (amount_in as u128)
+      .checked_mul(self.rate_numerator as u128)
+      .ok_or(MockAmmError::Overflow)?
+      / self.rate_denominator as u128;
+
+    u64::try_from(amount_out).map_err(|_| error!(MockAmmError::Overflow))
+  }
+}
+
+#[derive(Accounts)]
+pub struct InitPool<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub input_mint: InterfaceAccount<'info, Mint>,
+  pub output_mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    init,
+    payer = payer,
+    space = Pool::INIT_SPACE + 8,
+    seeds = [POOL_SEED, input_mint.key().as_ref(), output_mint.key().as_ref()],
+    bump
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(
+    init,
+    payer = payer,
+    token::mint = input_mint,
+    token::authority = pool,
+    token::token_program = token_program,
+    seeds = [INPUT_VAULT_SEED, pool.key().as_ref()],
+    bump
+  )]
+  pub input_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(
+    init,
+    payer = payer,
+    token::mint = output_mint,
+    token::authority = pool,
+    token::token_program = token_program,
+    seeds = [OUTPUT_VAULT_SEED, pool.key().as_ref()],
+    bump
+  )]
+  pub output_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, token::mint = input_mint)]
+  pub source: InterfaceAccount<'info, TokenAccount>,
+  #[account(mut, token::mint = output_mint)]
+  pub destination: InterfaceAccount<'info, TokenAccount>,
+  pub input_mint: InterfaceAccount<'info, Mint>,
+  pub output_mint: InterfaceAccount<'info, Mint>,
+  #[account(
+    has_one = input_mint,
+    has_one = output_mint,
+    seeds = [POOL_SEED, input_mint.key().as_ref(), output_mint.key().as_ref()],
+    bump = pool.bump
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(mut, seeds = [INPUT_VAULT_SEED, pool.key().as_ref()], bump)]
+  pub input_vault: InterfaceAccount<'info, TokenAccount>,
+  #[account(mut, seeds = [OUTPUT_VAULT_SEED, pool.key().as_ref()], bump)]
+  pub output_vault: InterfaceAccount<'info, TokenAccount>,
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Swap<'info> {
+  fn swap(&self, amount_in: u64, amount_out: u64) -> Result<()> {
+    let deposit_accounts = TransferChecked {
+      from: self.source.to_account_info(),
+      mint: self.input_mint.to_account_info(),
+      to: self.input_vault.to_account_info(),
+      authority: self.authority.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new(self.token_program.to_account_info(), deposit_accounts),
+      amount_in,
+      self.input_mint.decimals,
+    )?;
+
+    let input_mint = self.input_mint.key();
+    let output_mint = self.output_mint.key();
+    let signer: &[&[&[u8]]] = &[&[POOL_SEED, input_mint.as_ref(), output_mint.as_ref(), &[self.pool.bump]]];
+
+    let withdraw_accounts = TransferChecked {
+      from: self.output_vault.to_account_info(),
+      mint: self.output_mint.to_account_info(),
+      to: self.destination.to_account_info(),
+      authority: self.pool.to_account_info(),
+    };
+    transfer_checked(
+      CpiContext::new_with_signer(self.token_program.to_account_info(), withdraw_accounts, signer),
+      amount_out,
+      self.output_mint.decimals,
+    )
+  }
+}
+
+#[error_code]
+pub enum MockAmmError {
+  #[msg("Rate denominator must be positive")]
+  InvalidRate,
+  #[msg("Swap output is below the requested minimum")]
+  SlippageExceeded,
+  #[msg("Overflow")]
+  Overflow,
+}
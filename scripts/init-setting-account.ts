@@ -49,12 +49,31 @@ const program = new Program<EnsoLending>(
 	provider
 );
 
+// SOL/USDC margins: borrow up to 50% LTV, liquidate past 80% with a 5% penalty
+const riskParameters = {
+	initialLtvBps: new anchor.BN(5_000),
+	liquidationThresholdBps: new anchor.BN(8_000),
+	liquidationPenaltyBps: new anchor.BN(500),
+};
+
+// Role account holding the tier admins allowed to create setting accounts
+const tierAdminRoleAccount = PublicKey.findProgramAddressSync(
+	[
+		Buffer.from('enso'),
+		Buffer.from('role_account'),
+		Buffer.from([0]),
+		program.programId.toBuffer(),
+	],
+	program.programId
+)[0];
+
 const initSettingAccount = async (params: {
   amount: number;
+  minAmount: number;
   duration: number;
   tierId: string;
-  lenderFeePercent: number;
-  borrowerFeePercent: number;
+  lenderFeeBps: number;
+  borrowerFeeBps: number;
   lendMintAsset: PublicKey;
   collateralMintAsset: PublicKey;
   settingAccount: anchor.web3.PublicKey;
@@ -66,9 +85,10 @@ const initSettingAccount = async (params: {
 }) => {
   const {
     amount,
+    minAmount,
     duration,
-    lenderFeePercent,
-    borrowerFeePercent,
+    lenderFeeBps,
+    borrowerFeeBps,
     tierId,
     lendMintAsset,
     collateralMintAsset,
@@ -82,9 +102,11 @@ const initSettingAccount = async (params: {
     .initSettingAccount(
       tierId,
       new anchor.BN(amount),
+      new anchor.BN(minAmount),
       new anchor.BN(duration),
-      lenderFeePercent,
-      borrowerFeePercent
+      new anchor.BN(lenderFeeBps),
+      new anchor.BN(borrowerFeeBps),
+      riskParameters
     )
     .accounts({
       owner: ownerAccountSetting.publicKey,
@@ -92,6 +114,7 @@ const initSettingAccount = async (params: {
       settingAccount,
       lendMintAsset,
       collateralMintAsset,
+      roleAccount: tierAdminRoleAccount,
       systemProgram: SystemProgram.programId,
       collateralPriceFeedAccount,
       lendPriceFeedAccount,
@@ -107,10 +130,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 100000000; // 100 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_001';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -128,13 +152,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
@@ -150,10 +175,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 200000000; // 200 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_002';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -171,13 +197,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
@@ -193,10 +220,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 500000000; // 500 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_003';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -214,13 +242,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
@@ -236,10 +265,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 1000000000; // 1000 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_004';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -257,13 +287,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
@@ -280,10 +311,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 2000000000; // 2000 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_005';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -301,13 +333,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
@@ -323,10 +356,11 @@ const log = (tx: string) => {
 
 (async () => {
 	const amount = 5000000000; // 5000 USDC
+	const minAmount = amount / 10; // partial fills down to 10% of the tier
 	const duration = DURATION_TO_SECOND;
 	const tierId = 'tier_006';
-	const lenderFeePercent = 0;
-	const borrowerFeePercent = 0;
+	const lenderFeeBps = 0;
+	const borrowerFeeBps = 0;
 
 	const seedSettingAccount = [
 		Buffer.from('enso'),
@@ -344,13 +378,14 @@ const log = (tx: string) => {
 
 	const transaction = await initSettingAccount({
 		amount,
+		minAmount,
 		duration,
 		tierId,
-		lenderFeePercent,
+		lenderFeeBps,
 		lendMintAsset: mintUsdcAccount,
 		collateralMintAsset: mintSolWrappedAccount,
 		settingAccount,
-		borrowerFeePercent,
+		borrowerFeeBps,
 		lendPriceFeedAccount: usdc_usd_price_feed,
 		collateralPriceFeedAccount: sol_usd_price_feed,
 		ownerAccountSetting: ownerAccountSetting,
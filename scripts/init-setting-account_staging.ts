@@ -69,12 +69,31 @@ const program = new Program<EnsoLending>(
   provider
 );
 
+// SOL/USDC margins: borrow up to 50% LTV, liquidate past 80% with a 5% penalty
+const riskParameters = {
+  initialLtvBps: new anchor.BN(5_000),
+  liquidationThresholdBps: new anchor.BN(8_000),
+  liquidationPenaltyBps: new anchor.BN(500),
+};
+
+// Role account holding the tier admins allowed to create setting accounts
+const tierAdminRoleAccount = PublicKey.findProgramAddressSync(
+  [
+    Buffer.from("enso"),
+    Buffer.from("role_account"),
+    Buffer.from([0]),
+    program.programId.toBuffer(),
+  ],
+  program.programId
+)[0];
+
 const initSettingAccount = async (params: {
   amount: number;
+  minAmount: number;
   duration: number;
   tierId: string;
-  lenderFeePercent: number;
-  borrowerFeePercent: number;
+  lenderFeeBps: number;
+  borrowerFeeBps: number;
   lendMintAsset: PublicKey;
   collateralMintAsset: PublicKey;
   settingAccount: anchor.web3.PublicKey;
@@ -85,9 +104,10 @@ const initSettingAccount = async (params: {
 }) => {
   const {
     amount,
+    minAmount,
     duration,
-    lenderFeePercent,
-    borrowerFeePercent,
+    lenderFeeBps,
+    borrowerFeeBps,
     tierId,
     lendMintAsset,
     collateralMintAsset,
@@ -101,9 +121,11 @@ const initSettingAccount = async (params: {
     .initSettingAccount(
       tierId,
       new anchor.BN(amount),
+      new anchor.BN(minAmount),
       new anchor.BN(duration),
-      lenderFeePercent,
-      borrowerFeePercent
+      new anchor.BN(lenderFeeBps),
+      new anchor.BN(borrowerFeeBps),
+      riskParameters
     )
     .accounts({
       owner: ownerAccountSetting.publicKey,
@@ -111,6 +133,7 @@ const initSettingAccount = async (params: {
       settingAccount,
       lendMintAsset,
       collateralMintAsset,
+      roleAccount: tierAdminRoleAccount,
       systemProgram: SystemProgram.programId,
       collateralPriceFeedAccount,
       lendPriceFeedAccount,
@@ -128,10 +151,11 @@ const log = (tx: string) => {
 
 (async () => {
   const amount = 10000000000; // 10000 USDC
+  const minAmount = amount / 10; // partial fills down to 10% of the tier
   const duration = DURATION_TO_SECOND;
   const tierId = "solana_tier_005";
-  const lenderFeePercent = 5;
-  const borrowerFeePercent = 5;
+  const lenderFeeBps = 500;
+  const borrowerFeeBps = 500;
 
   const seedSettingAccount = [
     Buffer.from("enso"),
@@ -149,13 +173,14 @@ const log = (tx: string) => {
 
   const initSettingTx = await initSettingAccount({
     amount,
+    minAmount,
     duration,
     tierId,
-    lenderFeePercent,
+    lenderFeeBps,
     lendMintAsset: mintUsdcAccount,
     collateralMintAsset: mintSolWrappedAccount,
     settingAccount,
-    borrowerFeePercent,
+    borrowerFeeBps,
     lendPriceFeedAccount: usdc_usd_price_feed,
     collateralPriceFeedAccount: sol_usd_price_feed,
     ownerAccountSetting: ownerAccountSetting,
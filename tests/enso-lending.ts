@@ -62,9 +62,29 @@ describe("enso-lending", () => {
   const usdcMintDecimal = 6;
   const totalUsdcSupply = 1e9 * 10 ** usdcMintDecimal; // 1000000000 USDC
   const wrappedSolDecimal = 9;
+  // SOL/USDC margins: borrow up to 50% LTV, liquidate past 80% with a 5% penalty
+  const riskParameters = {
+    initialLtvBps: new anchor.BN(5_000),
+    liquidationThresholdBps: new anchor.BN(8_000),
+    liquidationPenaltyBps: new anchor.BN(500),
+  };
   const sol_usd_price_feed_id = "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix";
   const usdc_usd_price_feed_id = "5SSkXsEKQepHHAewytPVwdej4epN1nxgLVM84L4KXgy7";
 
+  const [globalConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("enso"), Buffer.from("global_config"), program.programId.toBuffer()],
+    program.programId
+  );
+
+  const [approvalConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("enso"), Buffer.from("approval_config"), program.programId.toBuffer()],
+    program.programId
+  );
+  const [tierAdminRoleAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("enso"), Buffer.from("role_account"), Buffer.from([0]), program.programId.toBuffer()],
+    program.programId
+  );
+
   const providerAtaUsdc = getAssociatedTokenAddressSync(
     usdcMint.publicKey,
     providerWallet.publicKey
@@ -206,6 +226,58 @@ describe("enso-lending", () => {
     assert.equal(+lenderUsdcBalance.value.amount, usdcTransferToLender);
   });
 
+  it("Init global config", async () => {
+    const globalConfigInfo = await connection.getAccountInfo(globalConfig);
+    if (!globalConfigInfo) {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+
+      await program.methods
+        .initGlobalConfig(
+          hotWallet.publicKey,
+          new anchor.BN(0),
+          new anchor.BN(0),
+          {
+            startPremiumBps: new anchor.BN(500),
+            floorDiscountBps: new anchor.BN(2000),
+            duration: new anchor.BN(3600),
+          },
+          new anchor.BN(300)
+        )
+        .accounts({
+          admin: providerWallet.publicKey,
+          globalConfig,
+          program: program.programId,
+          programData,
+        })
+        .rpc()
+        .then((sig) => confirm(connection, sig))
+        .then((sig) => log(connection, sig));
+    }
+
+    const { admin } = await program.account.globalConfig.fetch(globalConfig);
+    assert.equal(admin.toString(), providerWallet.publicKey.toString());
+  });
+
+  it("Grant tier admin role to the setting account owner", async () => {
+    await program.methods
+      .grantRole({ tierAdmin: {} }, ownerAccountSetting.publicKey)
+      .accounts({
+        admin: providerWallet.publicKey,
+        globalConfig,
+        roleAccount: tierAdminRoleAccount,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+      .then((sig) => confirm(connection, sig))
+      .then((sig) => log(connection, sig));
+
+    const { members } = await program.account.roleAccount.fetch(tierAdminRoleAccount);
+    assert.isTrue(members.some((member) => member.equals(ownerAccountSetting.publicKey)));
+  });
+
   // Util
   const airdrop = async (to: PublicKey): Promise<void> => {
     let tx = new Transaction();
@@ -223,10 +295,11 @@ describe("enso-lending", () => {
 
   const initSettingAccount = async (params: {
     amount: number;
+    minAmount?: number;
     duration: number;
     tierId: string;
-    lenderFeePercent: number;
-    borrowerFeePercent: number;
+    lenderFeeBps: number;
+    borrowerFeeBps: number;
     lendMintAsset: PublicKey;
     collateralMintAsset: PublicKey;
     settingAccount: anchor.web3.PublicKey;
@@ -234,20 +307,24 @@ describe("enso-lending", () => {
     const {
       amount,
       duration,
-      lenderFeePercent,
-      borrowerFeePercent,
+      lenderFeeBps,
+      borrowerFeeBps,
       tierId,
       lendMintAsset,
       collateralMintAsset,
       settingAccount,
     } = params;
+    // Lend offers are taken whole unless a test opts into partial fills
+    const minAmount = params.minAmount ?? amount;
     await program.methods
       .initSettingAccount(
         tierId,
         new anchor.BN(amount),
+        new anchor.BN(minAmount),
         new anchor.BN(duration),
-        lenderFeePercent,
-        borrowerFeePercent
+        new anchor.BN(lenderFeeBps),
+        new anchor.BN(borrowerFeeBps),
+        riskParameters
       )
       .accounts({
         owner: ownerAccountSetting.publicKey,
@@ -255,6 +332,7 @@ describe("enso-lending", () => {
         settingAccount,
         lendMintAsset,
         collateralMintAsset,
+        roleAccount: tierAdminRoleAccount,
         systemProgram: SystemProgram.programId,
       })
       .signers([ownerAccountSetting])
@@ -623,8 +701,8 @@ describe("enso-lending", () => {
       const amount = 200 * usdcMintDecimal;
       const duration = 14;
       const tierId = "1234_tier_1";
-      const lenderFeePercent = 0.01;
-      const borrowerFeePercent = 0.01;
+      const lenderFeeBps = 1;
+      const borrowerFeeBps = 1;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -642,29 +720,40 @@ describe("enso-lending", () => {
         amount,
         duration,
         tierId,
-        lenderFeePercent,
+        lenderFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
-        borrowerFeePercent,
+        borrowerFeeBps,
       });
 
       // Read data from PDA account
       const {
         amount: fetchedAmount,
+        minAmount: fetchedMinAmount,
         collateralMintAsset,
         lendMintAsset,
         owner,
         receiver,
         tierId: fetchedTierId,
         duration: fetchDuration,
-        lenderFeePercent: fetchedLenderFeePercent,
-        borrowerFeePercent: fetchedBorrowerFeePercent,
+        lenderFeeBps: fetchedLenderFeeBps,
+        borrowerFeeBps: fetchedBorrowerFeeBps,
+        riskParameters: fetchedRiskParameters,
       } = await program.account.settingAccount.fetch(settingAccount);
       assert.equal(fetchedTierId, tierId);
       assert.equal(amount, fetchedAmount.toNumber());
-      assert.equal(fetchedLenderFeePercent, lenderFeePercent);
-      assert.equal(fetchedBorrowerFeePercent, borrowerFeePercent);
+      assert.equal(amount, fetchedMinAmount.toNumber());
+      assert.equal(fetchedLenderFeeBps.toNumber(), lenderFeeBps);
+      assert.equal(fetchedBorrowerFeeBps.toNumber(), borrowerFeeBps);
+      assert.equal(
+        fetchedRiskParameters.initialLtvBps.toNumber(),
+        riskParameters.initialLtvBps.toNumber()
+      );
+      assert.equal(
+        fetchedRiskParameters.liquidationThresholdBps.toNumber(),
+        riskParameters.liquidationThresholdBps.toNumber()
+      );
       assert.equal(duration, fetchDuration.toNumber());
       assert.equal(ownerAccountSetting.publicKey.toString(), owner.toString());
       assert.equal(hotWallet.publicKey.toString(), receiver.toString());
@@ -679,8 +768,8 @@ describe("enso-lending", () => {
       const amount = 200 * usdcMintDecimal;
       const duration = 14;
       const tierId = "1234_tier_1";
-      const lenderFeePercent = 0.01;
-      const borrowerFeePercent = 0.01;
+      const lenderFeeBps = 1;
+      const borrowerFeeBps = 1;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -698,25 +787,42 @@ describe("enso-lending", () => {
         amount,
         duration,
         tierId,
-        lenderFeePercent,
+        lenderFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
-        borrowerFeePercent,
+        borrowerFeeBps,
       });
 
+      const pendingSettingChange = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("enso"),
+          Buffer.from("pending_setting_change"),
+          Buffer.from(tierId),
+          program.programId.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
       const newAmount = 400;
+      const newMinAmount = 100;
       const newDuration = 28;
-      const newLenderFeePercent = 0.02;
-      const newBorrowerFeePercent = 0.03;
+      const newLenderFeeBps = 2;
+      const newBorrowerFeeBps = 3;
+      const newRiskParameters = {
+        ...riskParameters,
+        initialLtvBps: new anchor.BN(4_000),
+      };
 
       await program.methods
         .editSettingAccount(
           tierId,
           new anchor.BN(newAmount),
+          new anchor.BN(newMinAmount),
           new anchor.BN(newDuration),
-          newLenderFeePercent,
-          newBorrowerFeePercent
+          new anchor.BN(newLenderFeeBps),
+          new anchor.BN(newBorrowerFeeBps),
+          newRiskParameters
         )
         .accounts({
           owner: ownerAccountSetting.publicKey,
@@ -724,6 +830,8 @@ describe("enso-lending", () => {
           settingAccount,
           lendMintAsset: usdcMint.publicKey,
           collateralMintAsset: wrappedSol.publicKey,
+          pendingSettingChange,
+          globalConfig,
           systemProgram: SystemProgram.programId,
         })
         .signers([ownerAccountSetting])
@@ -731,21 +839,41 @@ describe("enso-lending", () => {
         .then((sig) => confirm(connection, sig))
         .then((sig) => log(connection, sig));
 
+      // The edit is only queued, the global config test setup uses no timelock so it applies at once
+      await program.methods
+        .applySettingChange(tierId)
+        .accounts({
+          owner: ownerAccountSetting.publicKey,
+          settingAccount,
+          pendingSettingChange,
+        })
+        .signers([ownerAccountSetting])
+        .rpc({ skipPreflight: true })
+        .then((sig) => confirm(connection, sig))
+        .then((sig) => log(connection, sig));
+
       const {
         amount: fetchedNewAmount,
+        minAmount: fetchedNewMinAmount,
         collateralMintAsset: fetchedNewCollateralMintAsset,
         lendMintAsset: fetchedNewLendMintAsset,
         owner: fetchedOwner,
         receiver: fetchedReceiver,
         tierId: fetchedTierId,
         duration: fetchedNewDuration,
-        lenderFeePercent: fetchedNewLenderFeePercent,
-        borrowerFeePercent: fetchedNewBorrowerFeePercent,
+        lenderFeeBps: fetchedNewLenderFeeBps,
+        borrowerFeeBps: fetchedNewBorrowerFeeBps,
+        riskParameters: fetchedNewRiskParameters,
       } = await program.account.settingAccount.fetch(settingAccount);
       assert.equal(tierId, fetchedTierId);
       assert.equal(newAmount, fetchedNewAmount.toNumber());
-      assert.equal(newLenderFeePercent, fetchedNewLenderFeePercent);
-      assert.equal(newBorrowerFeePercent, fetchedNewBorrowerFeePercent);
+      assert.equal(newMinAmount, fetchedNewMinAmount.toNumber());
+      assert.equal(newLenderFeeBps, fetchedNewLenderFeeBps.toNumber());
+      assert.equal(newBorrowerFeeBps, fetchedNewBorrowerFeeBps.toNumber());
+      assert.equal(
+        newRiskParameters.initialLtvBps.toNumber(),
+        fetchedNewRiskParameters.initialLtvBps.toNumber()
+      );
       assert.equal(newDuration, fetchedNewDuration.toNumber());
       assert.equal(
         ownerAccountSetting.publicKey.toString(),
@@ -766,13 +894,9 @@ describe("enso-lending", () => {
       const amount = 200 * usdcMintDecimal;
       const duration = 14;
       const tierId = "1234_tier_1";
-      const lenderFeePercent = 0.01;
-      const borrowerFeePercent = 0.01;
-      const dataSize = 279; // Replace with the desired account size in bytes
-      const expectedLoanRentReturned =
-        await program.provider.connection.getMinimumBalanceForRentExemption(
-          dataSize
-        );
+      const lenderFeeBps = 1;
+      const borrowerFeeBps = 1;
+      const proposalId = generateId(10);
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -790,13 +914,50 @@ describe("enso-lending", () => {
         amount,
         duration,
         tierId,
-        lenderFeePercent,
+        lenderFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
-        borrowerFeePercent,
+        borrowerFeeBps,
       });
 
+      // Closing a tier needs an approved proposal, the owner is the only approver here
+      const proposal = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("enso"),
+          Buffer.from("proposal"),
+          Buffer.from(proposalId),
+          program.programId.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
+      await program.methods
+        .setApprovalConfig([ownerAccountSetting.publicKey], 1)
+        .accounts({
+          admin: providerWallet.publicKey,
+          globalConfig,
+          approvalConfig,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+        .then((sig) => confirm(connection, sig))
+        .then((sig) => log(connection, sig));
+
+      await program.methods
+        .createProposal(proposalId, { closeSettingAccount: { settingAccount } })
+        .accounts({
+          proposer: ownerAccountSetting.publicKey,
+          approvalConfig,
+          proposal,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([ownerAccountSetting])
+        .rpc()
+        .then((sig) => confirm(connection, sig))
+        .then((sig) => log(connection, sig));
+
+      const expectedLoanRentReturned = await checkWalletBalance(settingAccount);
       const walletBalanceBeforeCloseLoan = await checkWalletBalance(
         ownerAccountSetting.publicKey
       );
@@ -806,6 +967,8 @@ describe("enso-lending", () => {
         .accounts({
           owner: ownerAccountSetting.publicKey,
           settingAccount,
+          approvalConfig,
+          proposal,
           systemProgram: SystemProgram.programId,
         })
         .signers([ownerAccountSetting])
@@ -841,8 +1004,8 @@ describe("enso-lending", () => {
         const amountTier = 50 * 10 ** usdcMintDecimal;
         const duration = 14;
         const tierId = `tier_id_${generateId(10)}`;
-        const lenderFeePercent = 0.01;
-        const borrowerFeePercent = 0.01;
+        const lenderFeeBps = 1;
+        const borrowerFeeBps = 1;
 
         const seedSettingAccount = [
           Buffer.from("enso"),
@@ -860,8 +1023,8 @@ describe("enso-lending", () => {
           amount: amountTier,
           duration,
           tierId,
-          lenderFeePercent,
-          borrowerFeePercent,
+          lenderFeeBps,
+          borrowerFeeBps,
           lendMintAsset: usdcMint.publicKey,
           collateralMintAsset: wrappedSol.publicKey,
           settingAccount,
@@ -930,7 +1093,7 @@ describe("enso-lending", () => {
           amount,
           duration: fetchedDuration,
           interest: fetchedInterest,
-          lenderFeePercent: fetchedLenderFee,
+          lenderFeeBps: fetchedLenderFee,
           lender: fetchedLender,
           lendMintToken,
           offerId: fetchedOfferId,
@@ -950,8 +1113,8 @@ describe("enso-lending", () => {
           const amountTier = 50 * 10 ** usdcMintDecimal;
           const duration = 14;
           const tierId = `tier_id_${generateId(10)}`;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -969,8 +1132,8 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
-            borrowerFeePercent,
+            lenderFeeBps,
+            borrowerFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
@@ -1095,8 +1258,8 @@ describe("enso-lending", () => {
           const amountTier = 200 * 10 ** usdcMintDecimal;
           const duration = 14;
           const tierId = `tier_id_${generateId(10)}`;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -1114,8 +1277,8 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
-            borrowerFeePercent,
+            lenderFeeBps,
+            borrowerFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
@@ -1184,8 +1347,8 @@ describe("enso-lending", () => {
           const tierId = `tier_id_${generateId(10)}`;
           const amountTier = 50 * 10 ** usdcMintDecimal;
           const duration = 14;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -1205,11 +1368,11 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
+            lenderFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
-            borrowerFeePercent,
+            borrowerFeeBps,
           });
 
           const offerId = `lend_offer_id_${generateId(10)}`;
@@ -1264,8 +1427,8 @@ describe("enso-lending", () => {
         const amountTier = 10 * 10 ** usdcMintDecimal;
         const duration = 14;
         const tierId = `tier_id_${generateId(10)}`;
-        const lenderFeePercent = 0.01;
-        const borrowerFeePercent = 0.01;
+        const lenderFeeBps = 1;
+        const borrowerFeeBps = 1;
 
         const seedSettingAccount = [
           Buffer.from("enso"),
@@ -1283,8 +1446,8 @@ describe("enso-lending", () => {
           amount: amountTier,
           duration,
           tierId,
-          lenderFeePercent,
-          borrowerFeePercent,
+          lenderFeeBps,
+          borrowerFeeBps,
           lendMintAsset: usdcMint.publicKey,
           collateralMintAsset: wrappedSol.publicKey,
           settingAccount,
@@ -1357,8 +1520,8 @@ describe("enso-lending", () => {
           const amountTier = 10 * 10 ** usdcMintDecimal;
           const duration = 14;
           const tierId = `tier_id_${generateId(10)}`;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -1376,8 +1539,8 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
-            borrowerFeePercent,
+            lenderFeeBps,
+            borrowerFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
@@ -1446,8 +1609,8 @@ describe("enso-lending", () => {
           const amountTier = 10 * 10 ** usdcMintDecimal;
           const duration = 14;
           const tierId = `tier_id_${generateId(10)}`;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -1465,11 +1628,11 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
+            lenderFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
-            borrowerFeePercent
+            borrowerFeeBps
           });
 
           const offerId = `lend_offer_id_${generateId(10)}`;
@@ -1536,8 +1699,8 @@ describe("enso-lending", () => {
         const amountTier = 10 * 10 ** usdcMintDecimal;
         const duration = 1209600; //14 days
         const tierId = `tier_id_${generateId(10)}`;
-        const lenderFeePercent = 0;
-        const borrowerFeePercent = 0;
+        const lenderFeeBps = 0;
+        const borrowerFeeBps = 0;
         const interest = 2.1;
 
         const seedSettingAccount = [
@@ -1556,11 +1719,11 @@ describe("enso-lending", () => {
           amount: amountTier,
           duration,
           tierId,
-          lenderFeePercent,
+          lenderFeeBps,
           lendMintAsset: usdcMint.publicKey,
           collateralMintAsset: wrappedSol.publicKey,
           settingAccount,
-          borrowerFeePercent: borrowerFeePercent,
+          borrowerFeeBps: borrowerFeeBps,
         });
 
         const offerId = `lend_offer_id_${generateId(10)}`;
@@ -1675,8 +1838,8 @@ describe("enso-lending", () => {
           const amountTier = 10 * 10 ** usdcMintDecimal;
           const duration = 14;
           const tierId = `tier_id_${generateId(10)}`;
-          const lenderFeePercent = 0.01;
-          const borrowerFeePercent = 0.01;
+          const lenderFeeBps = 1;
+          const borrowerFeeBps = 1;
 
           const seedSettingAccount = [
             Buffer.from("enso"),
@@ -1694,11 +1857,11 @@ describe("enso-lending", () => {
             amount: amountTier,
             duration,
             tierId,
-            lenderFeePercent,
+            lenderFeeBps,
             lendMintAsset: usdcMint.publicKey,
             collateralMintAsset: wrappedSol.publicKey,
             settingAccount,
-            borrowerFeePercent,
+            borrowerFeeBps,
           });
 
           const offerId = `lend_offer_id_${generateId(10)}`;
@@ -1765,8 +1928,8 @@ describe("enso-lending", () => {
       const collateralAmount = 10 * 10 ** wrappedSolDecimal;
       const duration = 14;
       const tierId = `tier_id_${generateId(10)}`;
-      const lenderFeePercent = 0;
-      const borrowerFeePercent = 0;
+      const lenderFeeBps = 0;
+      const borrowerFeeBps = 0;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -1787,8 +1950,8 @@ describe("enso-lending", () => {
         amount: amountTier,
         duration,
         tierId,
-        lenderFeePercent,
-        borrowerFeePercent,
+        lenderFeeBps,
+        borrowerFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
@@ -1889,8 +2052,8 @@ describe("enso-lending", () => {
       const collateralAmount = 10 * 10 ** wrappedSolDecimal; // 10 SOL
       const duration = 14 * 24 * 60 * 60;
       const tierId = `tier_id_${generateId(10)}`;
-      const lenderFeePercent = 0;
-      const borrowerFeePercent = 0;
+      const lenderFeeBps = 0;
+      const borrowerFeeBps = 0;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -1911,8 +2074,8 @@ describe("enso-lending", () => {
         amount: amountTier,
         duration,
         tierId,
-        lenderFeePercent,
-        borrowerFeePercent,
+        lenderFeeBps,
+        borrowerFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
@@ -2135,8 +2298,8 @@ describe("enso-lending", () => {
       const collateralAmount = 10 * 10 ** wrappedSolDecimal;
       const duration = 14;
       const tierId = `tier_id_${generateId(10)}`;
-      const lenderFeePercent = 0;
-      const borrowerFeePercent = 0;
+      const lenderFeeBps = 0;
+      const borrowerFeeBps = 0;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -2157,8 +2320,8 @@ describe("enso-lending", () => {
         amount: amountTier,
         duration,
         tierId,
-        lenderFeePercent,
-        borrowerFeePercent,
+        lenderFeeBps,
+        borrowerFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
@@ -2373,8 +2536,8 @@ describe("enso-lending", () => {
       const collateralAmount = 10 * 10 ** wrappedSolDecimal;
       const duration = 14;
       const tierId = `tier_id_${generateId(10)}`;
-      const lenderFeePercent = 0;
-      const borrowerFeePercent = 0;
+      const lenderFeeBps = 0;
+      const borrowerFeeBps = 0;
 
       const seedSettingAccount = [
         Buffer.from("enso"),
@@ -2395,8 +2558,8 @@ describe("enso-lending", () => {
         amount: amountTier,
         duration,
         tierId,
-        lenderFeePercent,
-        borrowerFeePercent,
+        lenderFeeBps,
+        borrowerFeeBps,
         lendMintAsset: usdcMint.publicKey,
         collateralMintAsset: wrappedSol.publicKey,
         settingAccount,
@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorError, Program } from "@coral-xyz/anchor";
+import { EnsoLending } from "../target/types/enso_lending";
+import { MockAmm } from "../target/types/mock_amm";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+import { log } from "./utils";
+
+describe("swap-adapter", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider();
+  // @ts-ignore
+  const providerWallet = provider.wallet.payer as Keypair;
+  const connection = provider.connection;
+
+  const program = anchor.workspace.EnsoLending as Program<EnsoLending>;
+  const mockAmm = anchor.workspace.MockAmm as Program<MockAmm>;
+
+  const collateralDecimal = 9;
+  const lendDecimal = 6;
+  // 1 collateral token (1e9) swaps into 150 lend tokens (150e6)
+  const rateNumerator = new anchor.BN(150_000_000);
+  const rateDenominator = new anchor.BN(1_000_000_000);
+
+  let collateralMint: PublicKey;
+  let lendMint: PublicKey;
+  let pool: PublicKey;
+  let inputVault: PublicKey;
+  let outputVault: PublicKey;
+
+  const [globalConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("enso"), Buffer.from("global_config"), program.programId.toBuffer()],
+    program.programId
+  );
+  const [swapAdapter] = PublicKey.findProgramAddressSync(
+    [Buffer.from("enso"), Buffer.from("swap_adapter"), program.programId.toBuffer()],
+    program.programId
+  );
+
+  const swapDiscriminator = Array.from(
+    anchor.utils.sha256.hash("global:swap").match(/../g).slice(0, 8).map((byte) => parseInt(byte, 16))
+  );
+
+  it("Create mints and a funded mock pool", async () => {
+    collateralMint = await createMint(connection, providerWallet, providerWallet.publicKey, null, collateralDecimal);
+    lendMint = await createMint(connection, providerWallet, providerWallet.publicKey, null, lendDecimal);
+
+    [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), collateralMint.toBuffer(), lendMint.toBuffer()],
+      mockAmm.programId
+    );
+    [inputVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("input_vault"), pool.toBuffer()],
+      mockAmm.programId
+    );
+    [outputVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("output_vault"), pool.toBuffer()],
+      mockAmm.programId
+    );
+
+    await mockAmm.methods
+      .initPool(rateNumerator, rateDenominator)
+      .accounts({
+        payer: providerWallet.publicKey,
+        inputMint: collateralMint,
+        outputMint: lendMint,
+        pool,
+        inputVault,
+        outputVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+      .then((sig) => log(connection, sig));
+
+    await mintTo(connection, providerWallet, lendMint, outputVault, providerWallet, 1_000_000 * 10 ** lendDecimal);
+
+    const outputVaultAccount = await getAccount(connection, outputVault);
+    assert.equal(Number(outputVaultAccount.amount), 1_000_000 * 10 ** lendDecimal);
+  });
+
+  it("Admin sets the swap adapter", async () => {
+    const globalConfigInfo = await connection.getAccountInfo(globalConfig);
+    if (!globalConfigInfo) {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+
+      await program.methods
+        .initGlobalConfig(
+          providerWallet.publicKey,
+          new anchor.BN(0),
+          new anchor.BN(0),
+          {
+            startPremiumBps: new anchor.BN(500),
+            floorDiscountBps: new anchor.BN(2000),
+            duration: new anchor.BN(3600),
+          },
+          new anchor.BN(300)
+        )
+        .accounts({
+          admin: providerWallet.publicKey,
+          globalConfig,
+          program: program.programId,
+          programData,
+        })
+        .rpc()
+        .then((sig) => log(connection, sig));
+    }
+
+    const accountLayout = [
+      { kind: { authority: {} }, isWritable: false, address: null },
+      { kind: { source: {} }, isWritable: true, address: null },
+      { kind: { destination: {} }, isWritable: true, address: null },
+      { kind: { sourceMint: {} }, isWritable: false, address: null },
+      { kind: { destinationMint: {} }, isWritable: false, address: null },
+      { kind: { extra: {} }, isWritable: false, address: pool },
+      { kind: { extra: {} }, isWritable: true, address: inputVault },
+      { kind: { extra: {} }, isWritable: true, address: outputVault },
+      { kind: { sourceTokenProgram: {} }, isWritable: false, address: null },
+    ];
+
+    await program.methods
+      .setSwapAdapter(mockAmm.programId, swapDiscriminator, accountLayout)
+      .accounts({
+        admin: providerWallet.publicKey,
+        globalConfig,
+        swapAdapter,
+      })
+      .rpc()
+      .then((sig) => log(connection, sig));
+
+    const adapter = await program.account.swapAdapter.fetch(swapAdapter);
+    assert.equal(adapter.programId.toBase58(), mockAmm.programId.toBase58());
+    assert.deepEqual(adapter.instructionDiscriminator, swapDiscriminator);
+    assert.equal(adapter.accountLayout.length, accountLayout.length);
+    assert.equal(adapter.accountLayout[5].address.toBase58(), pool.toBase58());
+  });
+
+  it("Mock pool swaps at its fixed rate and honours the minimum out", async () => {
+    const sourceAta = await getOrCreateAssociatedTokenAccount(
+      connection,
+      providerWallet,
+      collateralMint,
+      providerWallet.publicKey
+    );
+    const destinationAta = await getOrCreateAssociatedTokenAccount(
+      connection,
+      providerWallet,
+      lendMint,
+      providerWallet.publicKey
+    );
+    await mintTo(connection, providerWallet, collateralMint, sourceAta.address, providerWallet, 2 * 10 ** collateralDecimal);
+
+    const swapAccounts = {
+      authority: providerWallet.publicKey,
+      source: sourceAta.address,
+      destination: destinationAta.address,
+      inputMint: collateralMint,
+      outputMint: lendMint,
+      pool,
+      inputVault,
+      outputVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const amountIn = new anchor.BN(10 ** collateralDecimal);
+
+    try {
+      await mockAmm.methods
+        .swap(amountIn, new anchor.BN(150 * 10 ** lendDecimal + 1))
+        .accounts(swapAccounts)
+        .rpc();
+      assert.fail("swap below the minimum out should fail");
+    } catch (error) {
+      assert.isTrue(error instanceof AnchorError);
+      assert.equal((error as AnchorError).error.errorCode.code, "SlippageExceeded");
+    }
+
+    await mockAmm.methods
+      .swap(amountIn, new anchor.BN(150 * 10 ** lendDecimal))
+      .accounts(swapAccounts)
+      .rpc()
+      .then((sig) => log(connection, sig));
+
+    const destination = await getAccount(connection, destinationAta.address);
+    assert.equal(Number(destination.amount), 150 * 10 ** lendDecimal);
+  });
+});